@@ -1,3 +1,4 @@
+pub mod merkle_tree;
 pub mod multilinear_poly;
 pub mod prover;
 pub mod traits;
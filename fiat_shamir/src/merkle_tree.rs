@@ -0,0 +1,239 @@
+use crate::traits::HashTrait;
+use crate::utility::utils::field_element_to_bytes;
+use ark_ff::PrimeField;
+use std::marker::PhantomData;
+
+// Leaves and internal nodes are hashed with different prefixes,
+// so that an internal node can never be passed off as a leaf (second pre-image attack)
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+// Padding nodes get their own prefix: no leaf hashes to a padding node, so padded indices can't be opened
+const PADDING_PREFIX: u8 = 2;
+
+// A binary Merkle tree: layers[0] holds the leaf hashes (padded to a power of two), the last layer holds the root
+#[derive(Clone, Debug)]
+pub struct MerkleTree<H: HashTrait + Default> {
+    pub layers: Vec<Vec<Vec<u8>>>,
+    // the number of real leaves, ie: without the padding
+    number_of_leaves: usize,
+    _phantom: PhantomData<H>,
+}
+
+// Inclusion proof for one leaf: the sibling hashes from the leaf layer up to (but excluding) the root
+#[derive(Clone, Debug, PartialEq)]
+pub struct MerkleProof {
+    pub leaf_index: usize,
+    pub siblings: Vec<Vec<u8>>,
+}
+
+// Inclusion proof for several leaves at once.
+// Nodes that the verifier can compute from the opened leaves are not included,
+// so leaves that share a path only pay for the shared part once.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MerkleMultiProof {
+    // sorted and without duplicates
+    pub leaf_indices: Vec<usize>,
+    // the missing sibling hashes, in the order the verifier consumes them: layer by layer, left to right
+    pub nodes: Vec<Vec<u8>>,
+    pub depth: usize,
+}
+
+impl<H: HashTrait + Default> MerkleTree<H> {
+    pub fn new(leaves: &[Vec<u8>]) -> Self {
+        assert!(!leaves.is_empty(), "merkle tree needs at least one leaf");
+
+        let mut leaf_hashes: Vec<Vec<u8>> =
+            leaves.iter().map(|leaf| Self::hash_leaf(leaf)).collect();
+        leaf_hashes.resize(leaves.len().next_power_of_two(), Self::hash_padding());
+
+        let mut layers = vec![leaf_hashes];
+
+        while layers[layers.len() - 1].len() > 1 {
+            let next_layer = layers[layers.len() - 1]
+                .chunks(2)
+                .map(|pair| Self::hash_nodes(&pair[0], &pair[1]))
+                .collect();
+            layers.push(next_layer);
+        }
+
+        Self {
+            layers,
+            number_of_leaves: leaves.len(),
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn from_field_elements<F: PrimeField>(leaves: &[F]) -> Self {
+        let leaves: Vec<Vec<u8>> = leaves
+            .iter()
+            .map(|leaf| field_element_to_bytes(*leaf))
+            .collect();
+
+        Self::new(&leaves)
+    }
+
+    pub fn root(&self) -> Vec<u8> {
+        self.layers[self.layers.len() - 1][0].clone()
+    }
+
+    pub fn depth(&self) -> usize {
+        self.layers.len() - 1
+    }
+
+    pub fn prove(&self, leaf_index: usize) -> MerkleProof {
+        assert!(
+            leaf_index < self.number_of_leaves,
+            "leaf index out of bounds"
+        );
+
+        let mut siblings = Vec::with_capacity(self.depth());
+        let mut index = leaf_index;
+
+        for layer in self.layers.iter().take(self.depth()) {
+            siblings.push(layer[index ^ 1].clone());
+            index /= 2;
+        }
+
+        MerkleProof {
+            leaf_index,
+            siblings,
+        }
+    }
+
+    pub fn prove_batch(&self, leaf_indices: &[usize]) -> MerkleMultiProof {
+        let mut known_indices = leaf_indices.to_vec();
+        known_indices.sort_unstable();
+        known_indices.dedup();
+
+        assert!(
+            known_indices
+                .iter()
+                .all(|index| *index < self.number_of_leaves),
+            "leaf index out of bounds"
+        );
+
+        let sorted_leaf_indices = known_indices.clone();
+        let mut nodes = Vec::new();
+
+        // At every layer, a sibling is only added to the proof if the verifier can't compute it
+        // ie: if it is not one of the nodes already known at that layer
+        for layer in self.layers.iter().take(self.depth()) {
+            let mut i = 0;
+
+            while i < known_indices.len() {
+                let index = known_indices[i];
+
+                if index.is_multiple_of(2)
+                    && i + 1 < known_indices.len()
+                    && known_indices[i + 1] == index + 1
+                {
+                    i += 1;
+                } else {
+                    nodes.push(layer[index ^ 1].clone());
+                }
+
+                i += 1;
+            }
+
+            known_indices = known_indices.iter().map(|index| index / 2).collect();
+            known_indices.dedup();
+        }
+
+        MerkleMultiProof {
+            leaf_indices: sorted_leaf_indices,
+            nodes,
+            depth: self.depth(),
+        }
+    }
+
+    pub fn verify(root: &[u8], leaf: &[u8], proof: &MerkleProof) -> bool {
+        let mut current_hash = Self::hash_leaf(leaf);
+        let mut index = proof.leaf_index;
+
+        for sibling in proof.siblings.iter() {
+            current_hash = if index.is_multiple_of(2) {
+                Self::hash_nodes(&current_hash, sibling)
+            } else {
+                Self::hash_nodes(sibling, &current_hash)
+            };
+            index /= 2;
+        }
+
+        index == 0 && current_hash == root
+    }
+
+    // The leaves must be given in the same order as proof.leaf_indices
+    pub fn verify_batch(root: &[u8], leaves: &[Vec<u8>], proof: &MerkleMultiProof) -> bool {
+        if leaves.len() != proof.leaf_indices.len()
+            || proof.leaf_indices.is_empty()
+            || proof.leaf_indices.windows(2).any(|pair| pair[0] >= pair[1])
+            // a tree that deep can't exist, and 1 << depth would overflow
+            || proof.depth >= usize::BITS as usize
+            || proof.leaf_indices[proof.leaf_indices.len() - 1] >= 1 << proof.depth
+        {
+            return false;
+        }
+
+        let mut known_nodes: Vec<(usize, Vec<u8>)> = proof
+            .leaf_indices
+            .iter()
+            .zip(leaves.iter())
+            .map(|(index, leaf)| (*index, Self::hash_leaf(leaf)))
+            .collect();
+        let mut proof_nodes = proof.nodes.iter();
+
+        for _ in 0..proof.depth {
+            let mut next_nodes = Vec::with_capacity(known_nodes.len());
+            let mut i = 0;
+
+            while i < known_nodes.len() {
+                let (index, hash) = &known_nodes[i];
+
+                let parent_hash = if index.is_multiple_of(2) {
+                    if i + 1 < known_nodes.len() && known_nodes[i + 1].0 == index + 1 {
+                        i += 1;
+                        Self::hash_nodes(hash, &known_nodes[i].1)
+                    } else {
+                        match proof_nodes.next() {
+                            Some(sibling) => Self::hash_nodes(hash, sibling),
+                            None => return false,
+                        }
+                    }
+                } else {
+                    match proof_nodes.next() {
+                        Some(sibling) => Self::hash_nodes(sibling, hash),
+                        None => return false,
+                    }
+                };
+
+                next_nodes.push((index / 2, parent_hash));
+                i += 1;
+            }
+
+            known_nodes = next_nodes;
+        }
+
+        proof_nodes.next().is_none() && known_nodes.len() == 1 && known_nodes[0].1 == root
+    }
+
+    pub fn hash_leaf(data: &[u8]) -> Vec<u8> {
+        let mut hasher = H::default();
+        hasher.append(&[LEAF_PREFIX]);
+        hasher.append(data);
+        hasher.generate_hash()
+    }
+
+    pub fn hash_padding() -> Vec<u8> {
+        let mut hasher = H::default();
+        hasher.append(&[PADDING_PREFIX]);
+        hasher.generate_hash()
+    }
+
+    pub fn hash_nodes(left: &[u8], right: &[u8]) -> Vec<u8> {
+        let mut hasher = H::default();
+        hasher.append(&[NODE_PREFIX]);
+        hasher.append(left);
+        hasher.append(right);
+        hasher.generate_hash()
+    }
+}
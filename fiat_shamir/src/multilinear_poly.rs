@@ -67,17 +67,18 @@ impl<F: PrimeField> MultilinearPolynomial<F> {
         bytes
     }
 
+    // The inverse of convert_to_bytes: every element takes the byte size of the field's big integers,
+    // eg: 32 bytes for BN254 and 8 bytes for 64 bits fields
     pub fn from_bytes(bytes: &[u8]) -> Vec<F> {
-        let mut computation = Vec::new();
-        let mut i = 0;
-
-        while i < bytes.len() {
-            let mut bytes_array = [0u8; 32];
-            bytes_array.copy_from_slice(&bytes[i..i + 32]);
-            computation.push(F::from_be_bytes_mod_order(&bytes_array));
-            i += 32;
-        }
+        let element_size = F::zero().into_bigint().to_bytes_be().len();
+        assert!(
+            bytes.len().is_multiple_of(element_size),
+            "the bytes are not a whole number of field elements"
+        );
 
-        computation
+        bytes
+            .chunks_exact(element_size)
+            .map(F::from_be_bytes_mod_order)
+            .collect()
     }
 }
@@ -107,3 +107,71 @@ mod tests {
         assert_eq!(verifier.verify(proof), true);
     }
 }
+
+#[cfg(test)]
+mod merkle_tree_tests {
+    use ark_bn254::Fr;
+    use fiat_shamir::merkle_tree::MerkleTree;
+    use fiat_shamir::utility::utils::field_element_to_bytes;
+    use sha3::Keccak256;
+
+    #[test]
+    fn test_merkle_tree_single_and_batch_proofs() {
+        let values: Vec<Fr> = (1..=6).map(Fr::from).collect();
+        let tree = MerkleTree::<Keccak256>::from_field_elements(&values);
+
+        let proof = tree.prove(4);
+        assert!(MerkleTree::<Keccak256>::verify(
+            &tree.root(),
+            &field_element_to_bytes(values[4]),
+            &proof
+        ));
+
+        let batch_proof = tree.prove_batch(&[2, 3, 4]);
+        let opened_leaves: Vec<Vec<u8>> = [2, 3, 4]
+            .iter()
+            .map(|index| field_element_to_bytes(values[*index]))
+            .collect();
+        assert!(MerkleTree::<Keccak256>::verify_batch(
+            &tree.root(),
+            &opened_leaves,
+            &batch_proof
+        ));
+    }
+}
+
+#[cfg(test)]
+mod multilinear_poly_bytes_tests {
+    use ark_bn254::Fr;
+    use ark_ff::fields::{Fp64, MontBackend, MontConfig};
+    use fiat_shamir::multilinear_poly::MultilinearPolynomial;
+
+    #[derive(MontConfig)]
+    #[modulus = "18446744069414584321"]
+    #[generator = "7"]
+    struct GoldilocksConfig;
+    type Goldilocks = Fp64<MontBackend<GoldilocksConfig, 1>>;
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let values: Vec<Fr> = (1..=4).map(Fr::from).collect();
+        let bytes = MultilinearPolynomial::new(values.clone()).convert_to_bytes();
+        assert_eq!(MultilinearPolynomial::<Fr>::from_bytes(&bytes), values);
+
+        // 8 bytes per element for a 64 bits field
+        let values: Vec<Goldilocks> = vec![Goldilocks::from(3), -Goldilocks::from(1)];
+        let bytes = MultilinearPolynomial::new(values.clone()).convert_to_bytes();
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(
+            MultilinearPolynomial::<Goldilocks>::from_bytes(&bytes),
+            values
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the bytes are not a whole number of field elements")]
+    fn test_from_bytes_rejects_partial_element() {
+        let bytes = MultilinearPolynomial::new(vec![Goldilocks::from(3)]).convert_to_bytes();
+        MultilinearPolynomial::<Goldilocks>::from_bytes(&bytes[..7]);
+    }
+}
@@ -0,0 +1,22 @@
+// A distributed GKR worker over the BN254 base field, in its own process:
+// it connects to the coordinator listening at the address given as argument, eg: gkr_worker 127.0.0.1:4000,
+// and answers it until the coordinator shuts it down
+use ark_bn254::Fq;
+use gkr::distributed::connect_worker;
+use std::env;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let Some(address) = env::args().nth(1) else {
+        eprintln!("usage: gkr_worker <coordinator address>");
+        return ExitCode::FAILURE;
+    };
+
+    match connect_worker::<Fq, _>(address.as_str()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("gkr_worker: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,289 @@
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+// Binary tower fields: GF(2^(2^LEVEL)) for LEVEL = 0 (the bits) up to 7 (128 bits).
+// The tower is built as T_0 = GF(2) and T_(k+1) = T_k[X_k] / (X_k^2 + X_(k-1) * X_k + 1), with X_(-1) = 1.
+// An element of T_(k+1) is a1 * X_k + a0 with a0, a1 in T_k: its bits are the bits of a0 followed by the bits of a1,
+// so every field of the tower is the subfield of the next one made of the elements with a zero upper half,
+// and embedding an element into a larger field leaves its bits unchanged.
+// Addition is XOR (and subtraction is addition): boolean circuits get XOR gates for free,
+// and the witness of a boolean circuit lives in T_0 while the challenges come from the 128 bits field.
+// The tower is not an ark_ff Field, so it has its own sum-check (binary_sum_check) instead of going through the generic code:
+// ark_ff::Field needs a BasePrimeField implementing PrimeField, ie: GF(2) as a Montgomery big integer field,
+// which would lose the XOR additions and the bit level embeddings of the tower, and the generic round polynomials
+// are evaluated at the integers 0, 1, 2, ..., which are not distinct in characteristic 2.
+// The rounds themselves (folding, interpolation, challenges) are shared with extension_sum_check through sum_check_rounds.
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct BinaryTower<const LEVEL: usize>(u128);
+
+pub type BinaryField1b = BinaryTower<0>;
+pub type BinaryField2b = BinaryTower<1>;
+pub type BinaryField4b = BinaryTower<2>;
+pub type BinaryField8b = BinaryTower<3>;
+pub type BinaryField16b = BinaryTower<4>;
+pub type BinaryField32b = BinaryTower<5>;
+pub type BinaryField64b = BinaryTower<6>;
+pub type BinaryField128b = BinaryTower<7>;
+
+impl<const LEVEL: usize> BinaryTower<LEVEL> {
+    pub const BITS: usize = 1 << LEVEL;
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(1);
+
+    // The element with the given bits, the bits above Self::BITS are dropped
+    pub fn new(bits: u128) -> Self {
+        assert!(LEVEL <= 7, "the largest field of the tower has 128 bits");
+
+        Self(bits & mask(Self::BITS))
+    }
+
+    pub fn bits(&self) -> u128 {
+        self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn square(&self) -> Self {
+        *self * *self
+    }
+
+    // a^(-1) = a^(2^BITS - 2) = a^2 * a^4 * ... * a^(2^(BITS - 1))
+    pub fn inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+
+        let mut power = *self;
+        let mut inverse = Self::ONE;
+        for _ in 1..Self::BITS {
+            power = power.square();
+            inverse *= power;
+        }
+
+        Some(inverse)
+    }
+
+    // The same element, seen in a larger field of the tower
+    pub fn embed<const TARGET: usize>(self) -> BinaryTower<TARGET> {
+        assert!(
+            LEVEL <= TARGET,
+            "an element can only be embedded in a larger field"
+        );
+
+        BinaryTower(self.0)
+    }
+
+    // The little endian bytes of the element, at least one byte
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_le_bytes()[..Self::BITS.div_ceil(8)].to_vec()
+    }
+}
+
+impl<const LEVEL: usize> From<bool> for BinaryTower<LEVEL> {
+    fn from(bit: bool) -> Self {
+        Self(bit as u128)
+    }
+}
+
+impl<const LEVEL: usize> Add for BinaryTower<LEVEL> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, other: Self) -> Self {
+        Self(self.0 ^ other.0)
+    }
+}
+
+impl<const LEVEL: usize> Sub for BinaryTower<LEVEL> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 ^ other.0)
+    }
+}
+
+impl<const LEVEL: usize> Neg for BinaryTower<LEVEL> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self
+    }
+}
+
+impl<const LEVEL: usize> Mul for BinaryTower<LEVEL> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self(tower_mul(self.0, other.0, LEVEL))
+    }
+}
+
+impl<const LEVEL: usize> AddAssign for BinaryTower<LEVEL> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<const LEVEL: usize> SubAssign for BinaryTower<LEVEL> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<const LEVEL: usize> MulAssign for BinaryTower<LEVEL> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl<const LEVEL: usize> Sum for BinaryTower<LEVEL> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |sum, element| sum + element)
+    }
+}
+
+impl<const LEVEL: usize> Product for BinaryTower<LEVEL> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |product, element| product * element)
+    }
+}
+
+// A multilinear polynomial over a field of the tower, by its evaluations over the boolean hypercube.
+// As for MultilinearPolynomialEV, variable 0 is the most significant bit of the index
+#[derive(Debug, Clone, PartialEq)]
+pub struct BinaryMultilinearPolynomial<const LEVEL: usize> {
+    pub evaluated_values: Vec<BinaryTower<LEVEL>>,
+}
+
+impl<const LEVEL: usize> BinaryMultilinearPolynomial<LEVEL> {
+    pub fn new(evaluated_values: &[BinaryTower<LEVEL>]) -> Self {
+        assert!(
+            evaluated_values.len().is_power_of_two(),
+            "the number of evaluations must be a power of two"
+        );
+
+        Self {
+            evaluated_values: evaluated_values.to_vec(),
+        }
+    }
+
+    // The polynomial of T_0 with the given bits as evaluations
+    pub fn from_bits(bits: &[bool]) -> Self {
+        let values: Vec<BinaryTower<LEVEL>> = bits.iter().map(|bit| (*bit).into()).collect();
+
+        Self::new(&values)
+    }
+
+    pub fn number_of_variables(&self) -> u32 {
+        self.evaluated_values.len().ilog2()
+    }
+
+    // The same polynomial over a larger field of the tower
+    pub fn embed<const TARGET: usize>(&self) -> BinaryMultilinearPolynomial<TARGET> {
+        BinaryMultilinearPolynomial {
+            evaluated_values: self
+                .evaluated_values
+                .iter()
+                .map(|value| value.embed())
+                .collect(),
+        }
+    }
+
+    // Binds the first variable to value: f(value, x) = f(0, x) + value * (f(1, x) + f(0, x))
+    pub fn fix_first_variable(&self, value: BinaryTower<LEVEL>) -> Self {
+        let (low, high) = self
+            .evaluated_values
+            .split_at(self.evaluated_values.len() / 2);
+
+        Self {
+            evaluated_values: low
+                .iter()
+                .zip(high.iter())
+                .map(|(low, high)| *low + value * (*high + *low))
+                .collect(),
+        }
+    }
+
+    // The evaluation at a point of a field that contains the field of the polynomial
+    pub fn evaluate<const TARGET: usize>(
+        &self,
+        point: &[BinaryTower<TARGET>],
+    ) -> BinaryTower<TARGET> {
+        assert_eq!(
+            self.number_of_variables() as usize,
+            point.len(),
+            "the point has the wrong number of variables"
+        );
+
+        point
+            .iter()
+            .fold(self.embed::<TARGET>(), |polynomial, value| {
+                polynomial.fix_first_variable(*value)
+            })
+            .evaluated_values[0]
+    }
+
+    // eq(point, x) = product of (point_i * x_i + (1 + point_i) * (1 + x_i)), in characteristic 2
+    pub fn eq_polynomial(point: &[BinaryTower<LEVEL>]) -> Self {
+        let mut evaluated_values = vec![BinaryTower::ONE];
+
+        // the variable added last is the most significant bit
+        for value in point.iter().rev() {
+            evaluated_values = evaluated_values
+                .iter()
+                .map(|evaluation| *evaluation * (BinaryTower::ONE + *value))
+                .chain(
+                    evaluated_values
+                        .iter()
+                        .map(|evaluation| *evaluation * *value),
+                )
+                .collect();
+        }
+
+        Self { evaluated_values }
+    }
+}
+
+fn mask(bits: usize) -> u128 {
+    if bits == 128 {
+        u128::MAX
+    } else {
+        (1 << bits) - 1
+    }
+}
+
+// The product in T_level, with Karatsuba on the halves:
+// (a1 X + a0) (b1 X + b0) = a1 b1 X^2 + (a1 b0 + a0 b1) X + a0 b0
+//                         = (a1 b0 + a0 b1 + a1 b1 X') X + (a0 b0 + a1 b1), with X^2 = X' X + 1
+fn tower_mul(a: u128, b: u128, level: usize) -> u128 {
+    if level == 0 {
+        return a & b & 1;
+    }
+
+    let half = 1 << (level - 1);
+    let (a0, a1) = (a & mask(half), a >> half);
+    let (b0, b1) = (b & mask(half), b >> half);
+
+    let low = tower_mul(a0, b0, level - 1);
+    let high = tower_mul(a1, b1, level - 1);
+    let middle = tower_mul(a0 ^ a1, b0 ^ b1, level - 1) ^ low ^ high;
+
+    (low ^ high) | ((middle ^ mul_by_generator(high, level - 1)) << half)
+}
+
+// The product in T_level by its generator X_(level - 1) (X_(-1) = 1):
+// (x1 X + x0) X = x1 (X' X + 1) + x0 X = (x0 + x1 X') X + x1
+fn mul_by_generator(x: u128, level: usize) -> u128 {
+    if level == 0 {
+        return x;
+    }
+
+    let half = 1 << (level - 1);
+    let (x0, x1) = (x & mask(half), x >> half);
+
+    x1 | ((x0 ^ mul_by_generator(x1, level - 1)) << half)
+}
@@ -0,0 +1,308 @@
+use crate::binary_field::{BinaryField128b, BinaryMultilinearPolynomial};
+use crate::gkr_sumcheck_dependencies::transcript::Transcript;
+use crate::sum_check_rounds::{absorb_round, fold, interpolate_at};
+
+// Sum-check over the binary tower: the polynomial is a sum of products of multilinear polynomials over
+// any field of the tower (eg: bits), the challenges and the round messages are in the 128 bits field.
+// In characteristic 2 the integers 0, 1, 2, ... are not distinct field elements, so the round polynomials
+// are sent as their evaluations at the elements whose bits are 0, 1, ..., degree.
+// The boolean gate layer below uses it as a zerocheck: XOR gates are the additions of the field,
+// AND gates are its multiplications, so a layer of a boolean circuit is a degree 2 relation on its wires.
+
+#[derive(Clone, Debug)]
+pub struct BinarySumcheckProof {
+    pub claimed_sum: BinaryField128b,
+    pub round_evaluations: Vec<Vec<BinaryField128b>>,
+    pub random_challenges: Vec<BinaryField128b>,
+}
+
+#[derive(Clone, Debug)]
+pub struct BinarySumcheckVerifierProof {
+    pub is_proof_valid: bool,
+    pub random_challenges: Vec<BinaryField128b>,
+    pub last_claimed_sum: BinaryField128b,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BinaryClaim {
+    pub point: Vec<BinaryField128b>,
+    pub value: BinaryField128b,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BooleanGate {
+    Xor,
+    And,
+}
+
+#[derive(Clone, Debug)]
+pub struct BooleanGateProof {
+    pub sumcheck_proof: BinarySumcheckProof,
+    // The evaluations of the left input, the right input and the output at the point of the sum-check
+    pub wire_evaluations: [BinaryField128b; 3],
+}
+
+// Proves the sum over the boolean hypercube of the sum of the products of the polynomials
+pub fn prove<const LEVEL: usize>(
+    products: &[Vec<BinaryMultilinearPolynomial<LEVEL>>],
+    claimed_sum: BinaryField128b,
+    transcript: &mut Transcript,
+) -> BinarySumcheckProof {
+    assert!(
+        !products.is_empty() && products.iter().all(|factors| !factors.is_empty()),
+        "the sum needs at least one product of at least one polynomial"
+    );
+    let number_of_variables = products[0][0].number_of_variables() as usize;
+    assert!(
+        products
+            .iter()
+            .flatten()
+            .all(|polynomial| polynomial.number_of_variables() as usize == number_of_variables),
+        "the polynomials must have the same number of variables"
+    );
+
+    let degree = products.iter().map(Vec::len).max().unwrap();
+    let mut products: Vec<Vec<BinaryMultilinearPolynomial<7>>> = products
+        .iter()
+        .map(|factors| {
+            factors
+                .iter()
+                .map(|polynomial| polynomial.embed())
+                .collect()
+        })
+        .collect();
+
+    transcript.absorb(&claimed_sum.to_bytes());
+
+    let mut round_evaluations = Vec::with_capacity(number_of_variables);
+    let mut random_challenges = Vec::with_capacity(number_of_variables);
+
+    for _round in 0..number_of_variables {
+        let evaluations = round_evaluations_of(&products, degree);
+        let challenge = absorb_round(&evaluations, transcript);
+
+        for factors in products.iter_mut() {
+            for polynomial in factors.iter_mut() {
+                polynomial.evaluated_values = fold(&polynomial.evaluated_values, challenge);
+            }
+        }
+
+        round_evaluations.push(evaluations);
+        random_challenges.push(challenge);
+    }
+
+    BinarySumcheckProof {
+        claimed_sum,
+        round_evaluations,
+        random_challenges,
+    }
+}
+
+// As in extension_sum_check, the degree is the verifier's own: every round must send exactly degree + 1 evaluations
+pub fn verify(
+    proof: &BinarySumcheckProof,
+    degree: usize,
+    transcript: &mut Transcript,
+) -> BinarySumcheckVerifierProof {
+    transcript.absorb(&proof.claimed_sum.to_bytes());
+
+    let mut current_sum = proof.claimed_sum;
+    let mut random_challenges = Vec::with_capacity(proof.round_evaluations.len());
+
+    for evaluations in proof.round_evaluations.iter() {
+        if evaluations.len() != degree + 1 || evaluations[0] + evaluations[1] != current_sum {
+            return BinarySumcheckVerifierProof {
+                is_proof_valid: false,
+                random_challenges: vec![],
+                last_claimed_sum: current_sum,
+            };
+        }
+
+        let challenge = absorb_round(evaluations, transcript);
+        current_sum = interpolate_at(evaluations, challenge);
+
+        random_challenges.push(challenge);
+    }
+
+    BinarySumcheckVerifierProof {
+        is_proof_valid: true,
+        random_challenges,
+        last_claimed_sum: current_sum,
+    }
+}
+
+// The values of the output wires of a layer of gates
+pub fn evaluate_gate_layer(gates: &[BooleanGate], left: &[bool], right: &[bool]) -> Vec<bool> {
+    gates
+        .iter()
+        .zip(left.iter().zip(right.iter()))
+        .map(|(gate, (left, right))| match gate {
+            BooleanGate::Xor => left ^ right,
+            BooleanGate::And => left & right,
+        })
+        .collect()
+}
+
+// Proves that output[i] = gates[i](left[i], right[i]) for every gate of the layer, with the zerocheck
+// sum over x of eq(r, x) * (and(x) * left(x) * right(x) + xor(x) * (left(x) + right(x)) + output(x)) = 0
+// for a random r, where and and xor are the selectors of the gates.
+// Returns the claims on left, right and output at the point of the sum-check, left to the caller
+pub fn prove_gate_layer(
+    gates: &[BooleanGate],
+    left: &BinaryMultilinearPolynomial<0>,
+    right: &BinaryMultilinearPolynomial<0>,
+    output: &BinaryMultilinearPolynomial<0>,
+    transcript: &mut Transcript,
+) -> (BooleanGateProof, [BinaryClaim; 3]) {
+    let number_of_variables = left.number_of_variables() as usize;
+    assert!(
+        gates.len() == left.evaluated_values.len()
+            && [right, output]
+                .iter()
+                .all(|polynomial| polynomial.evaluated_values.len() == gates.len()),
+        "one value per gate on every wire"
+    );
+
+    absorb_gates(gates, transcript);
+    let point: Vec<BinaryField128b> = (0..number_of_variables)
+        .map(|_| transcript.random_challenge_as_binary_element())
+        .collect();
+
+    let eq_point = BinaryMultilinearPolynomial::eq_polynomial(&point);
+    let (and, xor) = selectors(gates);
+    let (left, right, output) = (left.embed::<7>(), right.embed::<7>(), output.embed::<7>());
+
+    let sumcheck_proof = prove(
+        &[
+            vec![eq_point.clone(), and, left.clone(), right.clone()],
+            vec![eq_point.clone(), xor.clone(), left.clone()],
+            vec![eq_point.clone(), xor, right.clone()],
+            vec![eq_point, output.clone()],
+        ],
+        BinaryField128b::ZERO,
+        transcript,
+    );
+    let sumcheck_point = sumcheck_proof.random_challenges.clone();
+
+    let wire_evaluations = [&left, &right, &output].map(|wire| wire.evaluate(&sumcheck_point));
+    for evaluation in wire_evaluations.iter() {
+        transcript.absorb(&evaluation.to_bytes());
+    }
+
+    (
+        BooleanGateProof {
+            sumcheck_proof,
+            wire_evaluations,
+        },
+        wire_evaluations.map(|value| BinaryClaim {
+            point: sumcheck_point.clone(),
+            value,
+        }),
+    )
+}
+
+// Returns the claims on left, right and output when the zerocheck holds, None otherwise
+pub fn verify_gate_layer(
+    gates: &[BooleanGate],
+    proof: &BooleanGateProof,
+    transcript: &mut Transcript,
+) -> Option<[BinaryClaim; 3]> {
+    if !gates.len().is_power_of_two()
+        || proof.sumcheck_proof.round_evaluations.len() != gates.len().ilog2() as usize
+        || !proof.sumcheck_proof.claimed_sum.is_zero()
+    {
+        return None;
+    }
+
+    absorb_gates(gates, transcript);
+    let point: Vec<BinaryField128b> = (0..gates.len().ilog2())
+        .map(|_| transcript.random_challenge_as_binary_element())
+        .collect();
+
+    // eq(r, x) * and(x) * left(x) * right(x) is of degree 4
+    let verification = verify(&proof.sumcheck_proof, 4, transcript);
+    if !verification.is_proof_valid {
+        return None;
+    }
+
+    let sumcheck_point = verification.random_challenges;
+    let (and, xor) = selectors(gates);
+    let [left, right, output] = proof.wire_evaluations;
+
+    let expected = eq_evaluation(&point, &sumcheck_point)
+        * (and.evaluate(&sumcheck_point) * left * right
+            + xor.evaluate(&sumcheck_point) * (left + right)
+            + output);
+    if verification.last_claimed_sum != expected {
+        return None;
+    }
+
+    for evaluation in proof.wire_evaluations.iter() {
+        transcript.absorb(&evaluation.to_bytes());
+    }
+
+    Some(proof.wire_evaluations.map(|value| BinaryClaim {
+        point: sumcheck_point.clone(),
+        value,
+    }))
+}
+
+// eq(x, y) = product of (x_i * y_i + (1 + x_i) * (1 + y_i))
+pub fn eq_evaluation(x: &[BinaryField128b], y: &[BinaryField128b]) -> BinaryField128b {
+    x.iter()
+        .zip(y.iter())
+        .map(|(x, y)| *x * *y + (BinaryField128b::ONE + *x) * (BinaryField128b::ONE + *y))
+        .product()
+}
+
+fn selectors(
+    gates: &[BooleanGate],
+) -> (
+    BinaryMultilinearPolynomial<7>,
+    BinaryMultilinearPolynomial<7>,
+) {
+    let and: Vec<bool> = gates.iter().map(|gate| *gate == BooleanGate::And).collect();
+    let xor: Vec<bool> = gates.iter().map(|gate| *gate == BooleanGate::Xor).collect();
+
+    (
+        BinaryMultilinearPolynomial::from_bits(&and),
+        BinaryMultilinearPolynomial::from_bits(&xor),
+    )
+}
+
+fn absorb_gates(gates: &[BooleanGate], transcript: &mut Transcript) {
+    let gate_bytes: Vec<u8> = gates.iter().map(|gate| *gate as u8).collect();
+    transcript.absorb(&gate_bytes);
+}
+
+// The evaluations of the round polynomial at the points 0, 1, ..., degree (as bits):
+// the sum over the remaining variables with the first one set to each point
+fn round_evaluations_of(
+    products: &[Vec<BinaryMultilinearPolynomial<7>>],
+    degree: usize,
+) -> Vec<BinaryField128b> {
+    (0..=degree)
+        .map(|point| {
+            let point = BinaryField128b::new(point as u128);
+
+            products
+                .iter()
+                .map(|factors| {
+                    let half = factors[0].evaluated_values.len() / 2;
+
+                    (0..half)
+                        .map(|index| {
+                            factors
+                                .iter()
+                                .map(|polynomial| {
+                                    let values = &polynomial.evaluated_values;
+                                    values[index] + point * (values[index + half] + values[index])
+                                })
+                                .product::<BinaryField128b>()
+                        })
+                        .sum::<BinaryField128b>()
+                })
+                .sum()
+        })
+        .collect()
+}
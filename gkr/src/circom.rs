@@ -0,0 +1,325 @@
+use crate::r1cs::{SparseMatrix, SparseMultilinearPolynomial, R1CS};
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use std::fmt;
+use std::path::Path;
+
+// Readers for the iden3 binary formats produced by Circom over BN254:
+// .r1cs holds the constraints, .wtns the values of every wire.
+// Both files are: a 4 bytes magic, a u32 version, a u32 number of sections,
+// then the sections as (u32 type, u64 size, content), in any order. Integers and field elements are little endian.
+// The wires are ordered as (1, public outputs, public inputs, private inputs, internal wires),
+// which is the assignment z = (1, public inputs, witness) of R1CS, the public inputs being the outputs then the inputs.
+
+const R1CS_MAGIC: &[u8; 4] = b"r1cs";
+const R1CS_VERSION: u32 = 1;
+const R1CS_HEADER_SECTION: u32 = 1;
+const R1CS_CONSTRAINTS_SECTION: u32 = 2;
+const R1CS_WIRE_TO_LABEL_SECTION: u32 = 3;
+
+const WTNS_MAGIC: &[u8; 4] = b"wtns";
+const WTNS_VERSION: u32 = 2;
+const WTNS_HEADER_SECTION: u32 = 1;
+const WTNS_VALUES_SECTION: u32 = 2;
+
+#[derive(Debug)]
+pub enum CircomError {
+    Io(std::io::Error),
+    InvalidMagic,
+    UnsupportedVersion(u32),
+    // Custom gates sections (PLONK only) and unknown sections
+    UnsupportedSection(u32),
+    MissingSection(u32),
+    DuplicateSection(u32),
+    // The file is not over the scalar field of BN254
+    WrongField,
+    UnexpectedEnd,
+    Malformed(&'static str),
+}
+
+impl fmt::Display for CircomError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CircomError::Io(error) => write!(f, "io error: {error}"),
+            CircomError::InvalidMagic => write!(f, "invalid magic number"),
+            CircomError::UnsupportedVersion(version) => {
+                write!(f, "unsupported version {version}")
+            }
+            CircomError::UnsupportedSection(section) => {
+                write!(f, "unsupported section type {section}")
+            }
+            CircomError::MissingSection(section) => write!(f, "missing section type {section}"),
+            CircomError::DuplicateSection(section) => {
+                write!(f, "duplicate section type {section}")
+            }
+            CircomError::WrongField => write!(f, "the prime is not the BN254 scalar field"),
+            CircomError::UnexpectedEnd => write!(f, "unexpected end of data"),
+            CircomError::Malformed(reason) => write!(f, "malformed file: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for CircomError {}
+
+impl From<std::io::Error> for CircomError {
+    fn from(error: std::io::Error) -> Self {
+        CircomError::Io(error)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CircomHeader {
+    pub num_wires: usize,
+    pub num_public_outputs: usize,
+    pub num_public_inputs: usize,
+    pub num_private_inputs: usize,
+    pub num_labels: u64,
+    pub num_constraints: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CircomR1CS {
+    pub header: CircomHeader,
+    pub r1cs: R1CS<Fr>,
+    // The label of every wire, empty when the file has no wire to label section
+    pub wire_labels: Vec<u64>,
+}
+
+impl CircomR1CS {
+    // Splits the values of every wire, as read from a .wtns file, into (public inputs, witness) for R1CS
+    pub fn split_witness<'a>(&self, wire_values: &'a [Fr]) -> Option<(&'a [Fr], &'a [Fr])> {
+        if wire_values.len() != self.header.num_wires || wire_values[0] != Fr::from(1) {
+            return None;
+        }
+
+        Some(wire_values[1..].split_at(self.r1cs.num_public_inputs))
+    }
+
+    pub fn is_satisfied(&self, wire_values: &[Fr]) -> bool {
+        self.split_witness(wire_values)
+            .is_some_and(|(public_inputs, witness)| self.r1cs.is_satisfied(public_inputs, witness))
+    }
+
+    // The multilinear extensions of A, B and C, indexed by (constraint bits, wire bits)
+    pub fn matrix_mles(&self) -> [SparseMultilinearPolynomial<Fr>; 3] {
+        self.r1cs.matrix_mles()
+    }
+}
+
+pub fn read_r1cs(bytes: &[u8]) -> Result<CircomR1CS, CircomError> {
+    let sections = read_sections(bytes, R1CS_MAGIC, R1CS_VERSION)?;
+
+    for (section_type, _) in sections.iter() {
+        if ![
+            R1CS_HEADER_SECTION,
+            R1CS_CONSTRAINTS_SECTION,
+            R1CS_WIRE_TO_LABEL_SECTION,
+        ]
+        .contains(section_type)
+        {
+            return Err(CircomError::UnsupportedSection(*section_type));
+        }
+    }
+
+    let mut reader = Reader::new(section(&sections, R1CS_HEADER_SECTION)?);
+    let field_size = read_prime(&mut reader)?;
+    let header = CircomHeader {
+        num_wires: reader.read_u32()? as usize,
+        num_public_outputs: reader.read_u32()? as usize,
+        num_public_inputs: reader.read_u32()? as usize,
+        num_private_inputs: reader.read_u32()? as usize,
+        num_labels: reader.read_u64()?,
+        num_constraints: reader.read_u32()? as usize,
+    };
+    reader.finish()?;
+
+    let num_public_inputs = header.num_public_outputs + header.num_public_inputs;
+    if header.num_wires < 1 + num_public_inputs + header.num_private_inputs {
+        return Err(CircomError::Malformed("more inputs than wires"));
+    }
+
+    let mut matrices = [Vec::new(), Vec::new(), Vec::new()];
+    let mut reader = Reader::new(section(&sections, R1CS_CONSTRAINTS_SECTION)?);
+
+    for constraint in 0..header.num_constraints {
+        for entries in matrices.iter_mut() {
+            let num_factors = reader.read_u32()?;
+
+            for _ in 0..num_factors {
+                let wire = reader.read_u32()? as usize;
+                if wire >= header.num_wires {
+                    return Err(CircomError::Malformed("wire out of bounds"));
+                }
+
+                entries.push((constraint, wire, reader.read_field(field_size)?));
+            }
+        }
+    }
+    reader.finish()?;
+
+    let wire_labels = match sections
+        .iter()
+        .find(|(section_type, _)| *section_type == R1CS_WIRE_TO_LABEL_SECTION)
+    {
+        Some((_, content)) => {
+            let mut reader = Reader::new(content);
+            let labels = (0..header.num_wires)
+                .map(|_| reader.read_u64())
+                .collect::<Result<Vec<u64>, CircomError>>()?;
+            reader.finish()?;
+            labels
+        }
+        None => Vec::new(),
+    };
+
+    let [a, b, c] = matrices
+        .map(|entries| SparseMatrix::new(header.num_constraints, header.num_wires, entries));
+
+    Ok(CircomR1CS {
+        r1cs: R1CS::new(a, b, c, num_public_inputs),
+        header,
+        wire_labels,
+    })
+}
+
+// The values of every wire, the first one being 1
+pub fn read_witness(bytes: &[u8]) -> Result<Vec<Fr>, CircomError> {
+    let sections = read_sections(bytes, WTNS_MAGIC, WTNS_VERSION)?;
+
+    if let Some((section_type, _)) = sections.iter().find(|(section_type, _)| {
+        *section_type != WTNS_HEADER_SECTION && *section_type != WTNS_VALUES_SECTION
+    }) {
+        return Err(CircomError::UnsupportedSection(*section_type));
+    }
+
+    let mut reader = Reader::new(section(&sections, WTNS_HEADER_SECTION)?);
+    let field_size = read_prime(&mut reader)?;
+    let num_values = reader.read_u32()? as usize;
+    reader.finish()?;
+
+    let mut reader = Reader::new(section(&sections, WTNS_VALUES_SECTION)?);
+    let values = (0..num_values)
+        .map(|_| reader.read_field(field_size))
+        .collect::<Result<Vec<Fr>, CircomError>>()?;
+    reader.finish()?;
+
+    Ok(values)
+}
+
+pub fn read_r1cs_file(path: impl AsRef<Path>) -> Result<CircomR1CS, CircomError> {
+    read_r1cs(&std::fs::read(path)?)
+}
+
+pub fn read_witness_file(path: impl AsRef<Path>) -> Result<Vec<Fr>, CircomError> {
+    read_witness(&std::fs::read(path)?)
+}
+
+fn read_sections<'a>(
+    bytes: &'a [u8],
+    magic: &[u8; 4],
+    version: u32,
+) -> Result<Vec<(u32, &'a [u8])>, CircomError> {
+    let mut reader = Reader::new(bytes);
+
+    if reader.read_bytes(4)? != magic {
+        return Err(CircomError::InvalidMagic);
+    }
+
+    let file_version = reader.read_u32()?;
+    if file_version != version {
+        return Err(CircomError::UnsupportedVersion(file_version));
+    }
+
+    // The number of sections comes from the file, nothing is allocated for them before they are read
+    let num_sections = reader.read_u32()?;
+    let mut sections: Vec<(u32, &[u8])> = Vec::new();
+
+    for _ in 0..num_sections {
+        let section_type = reader.read_u32()?;
+        let size = usize::try_from(reader.read_u64()?).map_err(|_| CircomError::UnexpectedEnd)?;
+
+        if sections
+            .iter()
+            .any(|(existing, _)| *existing == section_type)
+        {
+            return Err(CircomError::DuplicateSection(section_type));
+        }
+
+        sections.push((section_type, reader.read_bytes(size)?));
+    }
+    reader.finish()?;
+
+    Ok(sections)
+}
+
+fn section<'a>(sections: &[(u32, &'a [u8])], section_type: u32) -> Result<&'a [u8], CircomError> {
+    sections
+        .iter()
+        .find(|(existing, _)| *existing == section_type)
+        .map(|(_, content)| *content)
+        .ok_or(CircomError::MissingSection(section_type))
+}
+
+// Reads the size of the field elements in bytes and the prime, which must be the BN254 scalar field modulus
+fn read_prime(reader: &mut Reader) -> Result<usize, CircomError> {
+    let field_size = reader.read_u32()? as usize;
+    let modulus = Fr::MODULUS.to_bytes_le();
+
+    if field_size != modulus.len() || reader.read_bytes(field_size)? != modulus.as_slice() {
+        return Err(CircomError::WrongField);
+    }
+
+    Ok(field_size)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], CircomError> {
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or(CircomError::UnexpectedEnd)?;
+
+        let bytes = &self.bytes[self.position..end];
+        self.position = end;
+
+        Ok(bytes)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, CircomError> {
+        Ok(u32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap()))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, CircomError> {
+        Ok(u64::from_le_bytes(self.read_bytes(8)?.try_into().unwrap()))
+    }
+
+    // Field elements are stored in canonical form, not in Montgomery form
+    fn read_field(&mut self, field_size: usize) -> Result<Fr, CircomError> {
+        let bytes = self.read_bytes(field_size)?;
+        let element = Fr::from_le_bytes_mod_order(bytes);
+
+        if element.into_bigint().to_bytes_le() != bytes {
+            return Err(CircomError::Malformed("field element out of range"));
+        }
+
+        Ok(element)
+    }
+
+    fn finish(&self) -> Result<(), CircomError> {
+        if self.position != self.bytes.len() {
+            return Err(CircomError::Malformed("trailing bytes"));
+        }
+
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
+use crate::circuits::gate::{CustomGateTerm, Operation};
 use crate::circuits::layer::Layer;
-use crate::{circuits::gate::Operation, evaluation::MultilinearPolynomialEV};
+use crate::evaluation::MultilinearPolynomialEV;
 use ark_ff::PrimeField;
+use std::collections::{BTreeMap, BTreeSet};
 use std::marker::PhantomData;
 
 pub struct Circuit<F: PrimeField> {
@@ -13,6 +15,36 @@ pub struct CircuitEvaluationResult<F: PrimeField> {
     pub layer_evaluations: Vec<Vec<F>>,
 }
 
+// The wiring of the gates of a layer that read their left input from left_layer and their right input from right_layer
+// add_i and mul_i are polynomials over (a, b, c), like for a layer without skip connections
+pub struct WiringPredicate<F: PrimeField> {
+    pub left_layer: usize,
+    pub right_layer: usize,
+    pub add_i: MultilinearPolynomialEV<F>,
+    pub mul_i: MultilinearPolynomialEV<F>,
+}
+
+// The wiring of the multi-input gates of a layer with the same operation (and fan-in for multiplications),
+// as a polynomial over (a, b_1, ..., b_arity). The blocks of inputs after the first fan_in ones are always 0
+pub struct MultiInputPredicate<F: PrimeField> {
+    pub operation: Operation,
+    pub fan_in: usize,
+    pub predicate: MultilinearPolynomialEV<F>,
+}
+
+// The wiring of the custom gates of a layer with the same polynomial (terms)
+pub struct CustomGatePredicate<F: PrimeField> {
+    pub terms: Vec<CustomGateTerm>,
+    pub predicate: MultilinearPolynomialEV<F>,
+}
+
+// All the wiring predicates of a layer
+pub struct LayerPredicates<F: PrimeField> {
+    pub wiring: Vec<WiringPredicate<F>>,
+    pub multi_input: Vec<MultiInputPredicate<F>>,
+    pub custom: Vec<CustomGatePredicate<F>>,
+}
+
 impl<F: PrimeField> Circuit<F> {
     pub fn new(layers: Vec<Layer>) -> Self {
         Self {
@@ -22,29 +54,34 @@ impl<F: PrimeField> Circuit<F> {
     }
 
     pub fn evaluate(&mut self, values: Vec<F>) -> CircuitEvaluationResult<F> {
-        let mut current_input = values;
+        let number_of_layers = self.layers.len();
 
-        let mut reversed_evaluations = Vec::new();
-        reversed_evaluations.push(current_input.clone());
+        // layer_evaluations[number_of_layers] is the input layer, the layers are filled from the bottom up,
+        // so that every layer a gate reads from is already evaluated
+        let mut layer_evaluations = vec![Vec::new(); number_of_layers + 1];
+        layer_evaluations[number_of_layers] = values;
 
         // Iterate through the layers vector: in each iteration, iterate through the gates of each layer
-        for layer in self.layers.iter().rev() {
-            let max_output_index = layer
-                .gates
-                .iter()
-                .map(|gate| gate.output)
-                .max()
-                .unwrap_or(0);
-
-            let mut resultant_evaluations = vec![F::zero(); max_output_index + 1];
+        for layer_index in (0..number_of_layers).rev() {
+            let layer = &self.layers[layer_index];
+            let mut resultant_evaluations = vec![F::zero(); layer.max_output_index() + 1];
 
             // Iterate through the gates vector of each layer:
             // use the left_index, right_index and operator of each Gate struct to perform an operation
-            // based on the values in the left and right index positions.
+            // based on the values in the left and right index positions of the layers the gate reads from.
             // The operation is based on the Operator of the Gate: Add or Mul
             for gate in layer.gates.iter() {
-                let left_index_value = current_input[gate.left];
-                let right_index_value = current_input[gate.right];
+                let (left_layer, right_layer) = gate.source_layers(layer_index);
+                assert!(
+                    left_layer > layer_index
+                        && right_layer > layer_index
+                        && left_layer <= number_of_layers
+                        && right_layer <= number_of_layers,
+                    "a gate can only read from the layers below it"
+                );
+
+                let left_index_value = layer_evaluations[left_layer][gate.left];
+                let right_index_value = layer_evaluations[right_layer][gate.right];
 
                 let current_gate_evaluation = match gate.operation {
                     Operation::ADD => left_index_value + right_index_value,
@@ -55,21 +92,30 @@ impl<F: PrimeField> Circuit<F> {
                 resultant_evaluations[gate.output] += current_gate_evaluation;
             }
 
-            current_input = resultant_evaluations;
-            reversed_evaluations.push(current_input.clone());
-        }
+            // The multi-input gates and the custom gates always read from the layer directly below
+            for gate in layer.multi_input_gates.iter() {
+                resultant_evaluations[gate.output] +=
+                    gate.evaluate(&layer_evaluations[layer_index + 1]);
+            }
+
+            for gate in layer.custom_gates.iter() {
+                resultant_evaluations[gate.output] +=
+                    gate.evaluate(&layer_evaluations[layer_index + 1]);
+            }
 
-        reversed_evaluations.reverse();
+            layer_evaluations[layer_index] = resultant_evaluations;
+        }
 
         CircuitEvaluationResult {
-            output: reversed_evaluations[0].clone(),
-            layer_evaluations: reversed_evaluations,
+            output: layer_evaluations[0].clone(),
+            layer_evaluations,
         }
     }
 
     // This function gets the evaluations of a layer: Vec<F> whose index is passed as layer_index,
     // then it converts it to a Multilinear polynomial
     // This will be used for the MLE: Multilinear Extension
+    // The evaluations are padded with zeros to a power of two (at least 2), so that they make up a full boolean hypercube
     pub fn w_i_polynomial(
         circuit_evaluation: &CircuitEvaluationResult<F>,
         layer_index: usize,
@@ -79,71 +125,385 @@ impl<F: PrimeField> Circuit<F> {
             "layer index out of bounds"
         );
 
-        MultilinearPolynomialEV::new(&circuit_evaluation.layer_evaluations[layer_index])
+        pad_to_boolean_hypercube(&circuit_evaluation.layer_evaluations[layer_index])
+    }
+
+    // The number of variables needed to index the gates (outputs) of the layer at layer_index
+    pub fn layer_output_num_vars(&self, layer_index: usize) -> usize {
+        num_vars_for_size(self.layers[layer_index].max_output_index() + 1)
+    }
+
+    // The number of variables of the values of a layer, indexed like the circuit evaluations:
+    // layer_index == self.layers.len() is the input layer
+    pub fn layer_num_vars(&self, layer_index: usize) -> usize {
+        if layer_index < self.layers.len() {
+            self.layer_output_num_vars(layer_index)
+        } else {
+            self.input_num_vars()
+        }
+    }
+
+    // The number of variables needed to index the circuit inputs: the largest input index read by any gate
+    pub fn input_num_vars(&self) -> usize {
+        let input_layer = self.layers.len();
+
+        let max_input_index = self
+            .layers
+            .iter()
+            .enumerate()
+            .flat_map(|(layer_index, layer)| {
+                layer.gates.iter().flat_map(move |gate| {
+                    let (left_layer, right_layer) = gate.source_layers(layer_index);
+                    [(left_layer, gate.left), (right_layer, gate.right)]
+                })
+            })
+            .filter(|(source_layer, _)| *source_layer == input_layer)
+            .map(|(_, index)| index)
+            // the multi input and custom gates of the last layer read the inputs, an empty circuit has no last layer
+            .chain(self.layers.last().into_iter().flat_map(|layer| {
+                layer
+                    .multi_input_gates
+                    .iter()
+                    .flat_map(|gate| gate.inputs.iter().copied())
+                    .chain(
+                        layer
+                            .custom_gates
+                            .iter()
+                            .flat_map(|gate| gate.inputs.iter().copied()),
+                    )
+            }))
+            .max()
+            .unwrap_or(0);
+
+        num_vars_for_size(max_input_index + 1)
+    }
+
+    // The layers (sorted, without duplicates) that the gates of the layer at layer_index read from
+    pub fn source_layers(&self, layer_index: usize) -> Vec<usize> {
+        let mut source_layers: Vec<usize> = self.layers[layer_index]
+            .gates
+            .iter()
+            .flat_map(|gate| {
+                let (left_layer, right_layer) = gate.source_layers(layer_index);
+                [left_layer, right_layer]
+            })
+            .collect();
+
+        if !self.layers[layer_index].multi_input_gates.is_empty()
+            || !self.layers[layer_index].custom_gates.is_empty()
+        {
+            source_layers.push(layer_index + 1);
+        }
+
+        source_layers.sort_unstable();
+        source_layers.dedup();
+
+        source_layers
+    }
+
+    pub fn has_skip_connections(&self) -> bool {
+        (0..self.layers.len()).any(|layer_index| {
+            self.source_layers(layer_index)
+                .iter()
+                .any(|source_layer| *source_layer != layer_index + 1)
+        })
     }
 
+    pub fn has_multi_input_gates(&self) -> bool {
+        self.layers
+            .iter()
+            .any(|layer| !layer.multi_input_gates.is_empty())
+    }
+
+    pub fn has_custom_gates(&self) -> bool {
+        self.layers
+            .iter()
+            .any(|layer| !layer.custom_gates.is_empty())
+    }
+
+    // The number of blocks of input variables of the wiring predicates of a layer:
+    // two (b and c) for the gates with two inputs, more if a multiplication gate or a custom gate of the layer has more inputs.
+    // A summation gate only needs one block, whatever its fan-in
+    pub fn layer_arity(&self, layer_index: usize) -> usize {
+        let layer = &self.layers[layer_index];
+
+        layer
+            .multi_input_gates
+            .iter()
+            .filter(|gate| gate.operation == Operation::MUL)
+            .map(|gate| gate.inputs.len())
+            .chain(layer.custom_gates.iter().map(|gate| gate.inputs.len()))
+            .max()
+            .unwrap_or(0)
+            .max(2)
+    }
+
+    // The number of variables needed to index the wires the layer at layer_index reads from:
+    // the widest of the layers it reads from, the narrower ones are padded with zeros up to this width
+    pub fn layer_input_num_vars(&self, layer_index: usize) -> usize {
+        self.source_layers(layer_index)
+            .into_iter()
+            .map(|source_layer| self.layer_num_vars(source_layer))
+            .max()
+            .unwrap_or(1)
+    }
+
+    // The add_i and mul_i wiring predicates are polynomials over the variables (a, b, c)
+    // a => the output gate index, b => the left input index, c => the right input index
+    // With multiplication gates of fan-in k > 2, there are k blocks of input variables instead of b and c
+    pub fn num_of_layer_variables(&self, layer_index: usize) -> usize {
+        self.layer_output_num_vars(layer_index)
+            + (self.layer_arity(layer_index) * self.layer_input_num_vars(layer_index))
+    }
+
+    // The add_i and mul_i predicates of a layer whose gates all read from the layer directly below it
     pub fn add_i_and_mul_i_mle(
         &mut self,
         layer_index: usize,
     ) -> (MultilinearPolynomialEV<F>, MultilinearPolynomialEV<F>) {
-        let number_of_layer_variables = num_of_layer_variables(layer_index);
+        assert!(
+            self.source_layers(layer_index)
+                .iter()
+                .all(|source_layer| *source_layer == layer_index + 1),
+            "the layer has skip connections, use wiring_predicates instead"
+        );
+        assert!(
+            self.layers[layer_index].multi_input_gates.is_empty()
+                && self.layers[layer_index].custom_gates.is_empty(),
+            "the layer has multi-input or custom gates, use multi_input_predicates and custom_gate_predicates instead"
+        );
+
+        let predicate = self.wiring_predicate(layer_index, layer_index + 1, layer_index + 1);
+
+        (predicate.add_i, predicate.mul_i)
+    }
+
+    pub fn layer_predicates(&self, layer_index: usize) -> LayerPredicates<F> {
+        LayerPredicates {
+            wiring: self.wiring_predicates(layer_index),
+            multi_input: self.multi_input_predicates(layer_index),
+            custom: self.custom_gate_predicates(layer_index),
+        }
+    }
+
+    // One pair of add_i and mul_i predicates for every (left layer, right layer) combination the gates of the layer read from.
+    // Each pair only holds the gates with those source layers, b and c are padded to layer_input_num_vars variables
+    pub fn wiring_predicates(&self, layer_index: usize) -> Vec<WiringPredicate<F>> {
+        let source_pairs: BTreeSet<(usize, usize)> = self.layers[layer_index]
+            .gates
+            .iter()
+            .map(|gate| gate.source_layers(layer_index))
+            .collect();
+
+        source_pairs
+            .into_iter()
+            .map(|(left_layer, right_layer)| {
+                self.wiring_predicate(layer_index, left_layer, right_layer)
+            })
+            .collect()
+    }
+
+    fn wiring_predicate(
+        &self,
+        layer_index: usize,
+        left_layer: usize,
+        right_layer: usize,
+    ) -> WiringPredicate<F> {
+        let output_bit_length = self.layer_output_num_vars(layer_index);
+        let input_bit_length = self.layer_input_num_vars(layer_index);
+        let arity = self.layer_arity(layer_index);
+        let number_of_layer_variables = self.num_of_layer_variables(layer_index);
         let boolean_hypercube_combinations = 1 << number_of_layer_variables; // 2 ^ number_of_layer_variables
 
         let mut add_i_values = vec![F::zero(); boolean_hypercube_combinations];
         let mut mul_i_values = vec![F::zero(); boolean_hypercube_combinations];
 
-        for gate in self.layers[layer_index].gates.iter() {
+        for gate in self.layers[layer_index]
+            .gates
+            .iter()
+            .filter(|gate| gate.source_layers(layer_index) == (left_layer, right_layer))
+        {
+            let position_index = wire_position(
+                output_bit_length,
+                input_bit_length,
+                arity,
+                gate.output,
+                &[gate.left, gate.right],
+            );
+
+            match gate.operation {
+                Operation::ADD => add_i_values[position_index] = F::one(),
+                Operation::MUL => mul_i_values[position_index] = F::one(),
+            }
+        }
+
+        WiringPredicate {
+            left_layer,
+            right_layer,
+            add_i: MultilinearPolynomialEV::new(&add_i_values),
+            mul_i: MultilinearPolynomialEV::new(&mul_i_values),
+        }
+    }
+
+    // The wiring predicates of the multi-input gates of a layer, they all read from the layer directly below:
+    // one predicate for all the summation gates, and one for the multiplication gates of every fan-in.
+    // The summation predicate counts how many times the wire b is summed into the gate a, so it only uses the first block of inputs
+    pub fn multi_input_predicates(&self, layer_index: usize) -> Vec<MultiInputPredicate<F>> {
+        let output_bit_length = self.layer_output_num_vars(layer_index);
+        let input_bit_length = self.layer_input_num_vars(layer_index);
+        let arity = self.layer_arity(layer_index);
+        let boolean_hypercube_combinations = 1 << self.num_of_layer_variables(layer_index);
+
+        let new_values = || vec![F::zero(); boolean_hypercube_combinations];
+        let mut sum_values: Option<Vec<F>> = None;
+        let mut product_values: BTreeMap<usize, Vec<F>> = BTreeMap::new();
+
+        for gate in self.layers[layer_index].multi_input_gates.iter() {
             match gate.operation {
                 Operation::ADD => {
-                    let position_index = convert_to_binary_and_to_decimal(
-                        layer_index,
-                        gate.output,
-                        gate.left,
-                        gate.right,
-                    );
-                    add_i_values[position_index] = F::one();
+                    let values = sum_values.get_or_insert_with(new_values);
+
+                    for input in gate.inputs.iter() {
+                        let position_index = wire_position(
+                            output_bit_length,
+                            input_bit_length,
+                            arity,
+                            gate.output,
+                            &[*input],
+                        );
+                        values[position_index] += F::one();
+                    }
                 }
                 Operation::MUL => {
-                    let position_index = convert_to_binary_and_to_decimal(
-                        layer_index,
+                    let values = product_values
+                        .entry(gate.inputs.len())
+                        .or_insert_with(new_values);
+
+                    let position_index = wire_position(
+                        output_bit_length,
+                        input_bit_length,
+                        arity,
                         gate.output,
-                        gate.left,
-                        gate.right,
+                        &gate.inputs,
                     );
-                    mul_i_values[position_index] = F::one();
+                    values[position_index] += F::one();
                 }
             }
         }
 
-        let add_i_polynomial = MultilinearPolynomialEV::new(&add_i_values);
-        let mul_i_polynomial = MultilinearPolynomialEV::new(&mul_i_values);
+        let sum_predicate = sum_values.map(|values| MultiInputPredicate {
+            operation: Operation::ADD,
+            fan_in: 1,
+            predicate: MultilinearPolynomialEV::new(&values),
+        });
 
-        (add_i_polynomial, mul_i_polynomial)
+        let product_predicates =
+            product_values
+                .into_iter()
+                .map(|(fan_in, values)| MultiInputPredicate {
+                    operation: Operation::MUL,
+                    fan_in,
+                    predicate: MultilinearPolynomialEV::new(&values),
+                });
+
+        sum_predicate
+            .into_iter()
+            .chain(product_predicates)
+            .collect()
     }
-}
 
-pub fn num_of_layer_variables(layer_index: usize) -> usize {
-    if layer_index == 0 {
-        return 3;
+    // The wiring predicates of the custom gates of a layer: one for every distinct gate polynomial,
+    // a polynomial over (a, b_1, ..., b_arity) that is 1 at (output, inputs...) for every gate with that polynomial
+    pub fn custom_gate_predicates(&self, layer_index: usize) -> Vec<CustomGatePredicate<F>> {
+        let output_bit_length = self.layer_output_num_vars(layer_index);
+        let input_bit_length = self.layer_input_num_vars(layer_index);
+        let arity = self.layer_arity(layer_index);
+        let boolean_hypercube_combinations = 1 << self.num_of_layer_variables(layer_index);
+
+        let mut predicates: BTreeMap<Vec<CustomGateTerm>, Vec<F>> = BTreeMap::new();
+
+        for gate in self.layers[layer_index].custom_gates.iter() {
+            let values = predicates
+                .entry(gate.gate.terms())
+                .or_insert_with(|| vec![F::zero(); boolean_hypercube_combinations]);
+
+            let position_index = wire_position(
+                output_bit_length,
+                input_bit_length,
+                arity,
+                gate.output,
+                &gate.inputs,
+            );
+            values[position_index] += F::one();
+        }
+
+        predicates
+            .into_iter()
+            .map(|(terms, values)| CustomGatePredicate {
+                terms,
+                predicate: MultilinearPolynomialEV::new(&values),
+            })
+            .collect()
     }
+}
+
+// The number of variables of the smallest boolean hypercube holding `size` values
+// We always use at least one variable, so that a single value is represented as a proper polynomial
+pub fn num_vars_for_size(size: usize) -> usize {
+    size.next_power_of_two().ilog2().max(1) as usize
+}
+
+pub fn pad_to_boolean_hypercube<F: PrimeField>(values: &[F]) -> MultilinearPolynomialEV<F> {
+    pad_to_num_vars(values, num_vars_for_size(values.len()))
+}
 
-    let var_a_length = layer_index;
-    let var_b_length = var_a_length + 1;
-    let var_c_length = var_a_length + 1;
+// Pads the values with zeros to 2 ^ num_vars values
+pub fn pad_to_num_vars<F: PrimeField>(values: &[F], num_vars: usize) -> MultilinearPolynomialEV<F> {
+    assert!(
+        values.len() <= 1 << num_vars,
+        "too many values for the number of variables"
+    );
+
+    let mut padded_values = values.to_vec();
+    padded_values.resize(1 << num_vars, F::zero());
+
+    MultilinearPolynomialEV::new(&padded_values)
+}
+
+// The index of (a, b_1, ..., b_arity) on the boolean hypercube of the wiring predicates of a layer:
+// the inputs fill the first blocks, the remaining blocks are 0.
+// With two inputs and an arity of 2, this is the same as convert_to_binary_and_to_decimal
+pub fn wire_position(
+    output_bit_length: usize,
+    input_bit_length: usize,
+    arity: usize,
+    output: usize,
+    inputs: &[usize],
+) -> usize {
+    assert!(inputs.len() <= arity, "more inputs than input blocks");
+    assert!(
+        output < 1 << output_bit_length
+            && inputs.iter().all(|input| *input < 1 << input_bit_length),
+        "wire index out of bounds"
+    );
 
-    var_a_length + var_b_length + var_c_length
+    (0..arity).fold(output, |position, block| {
+        (position << input_bit_length) | inputs.get(block).copied().unwrap_or(0)
+    })
 }
 
+#[deprecated(note = "use wire_position, which also handles layers with more than two input blocks")]
 pub fn convert_to_binary_and_to_decimal(
-    layer_index: usize,
+    output_bit_length: usize,
+    input_bit_length: usize,
     variable_a: usize,
     variable_b: usize,
     variable_c: usize,
 ) -> usize {
     // convert decimal to binary
-    let a_in_binary = convert_decimal_to_padded_binary(variable_a, layer_index);
-    let b_in_binary = convert_decimal_to_padded_binary(variable_b, layer_index + 1);
-    let c_in_binary = convert_decimal_to_padded_binary(variable_c, layer_index + 1);
+    let a_in_binary = convert_decimal_to_padded_binary(variable_a, output_bit_length);
+    let b_in_binary = convert_decimal_to_padded_binary(variable_b, input_bit_length);
+    let c_in_binary = convert_decimal_to_padded_binary(variable_c, input_bit_length);
 
     // combine a, b and c binaries
     let combined_binary = a_in_binary + &b_in_binary + &c_in_binary;
@@ -0,0 +1,374 @@
+use crate::circuits::circuit::Circuit;
+use crate::circuits::gate::{
+    BooleanityGate, CustomGate, CustomGateInstance, Gate, LinearCombinationGate, MultiInputGate,
+    Operation,
+};
+use crate::circuits::layer::Layer;
+use ark_ff::{BigInteger, PrimeField};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// Layered sub-circuits for the checks most circuits need: booleanity, bit decomposition, range checks and comparisons.
+// The outputs of a gadget are constraints, they are all 0 exactly when the witness in the input layer is valid
+// (the less-than gadget also outputs the result of the comparison).
+// The witness helpers build the input layer a gadget expects from the values being checked.
+
+// The comparison adds 2^num_bits as a gate coefficient, so it must fit in an i64
+pub const MAX_GADGET_BITS: usize = 62;
+
+pub struct Gadget {
+    // output layer first, like Circuit::layers
+    pub layers: Vec<Layer>,
+    pub num_inputs: usize,
+    pub num_outputs: usize,
+}
+
+impl Gadget {
+    pub fn circuit<F: PrimeField>(self) -> Circuit<F> {
+        Circuit::new(self.layers)
+    }
+}
+
+// inputs: [x_0, ..., x_{n-1}]
+// outputs: [x_0 - x_0^2, ..., x_{n-1} - x_{n-1}^2]
+pub fn booleanity(num_values: usize) -> Gadget {
+    assert!(num_values > 0, "a gadget needs at least one input");
+
+    let mut builder = GadgetBuilder::new(num_values);
+    let outputs: Vec<Wire> = (0..num_values)
+        .map(|index| {
+            let value = builder.input(index);
+            builder.booleanity(value)
+        })
+        .collect();
+
+    builder.build(&outputs)
+}
+
+// inputs: [x, b_0, ..., b_{num_bits-1}], the bits of x from the least significant one
+// outputs: [b_0 - b_0^2, ..., b_{num_bits-1} - b_{num_bits-1}^2, sum(2^i * b_i) - x]
+pub fn bit_decomposition(num_bits: usize) -> Gadget {
+    assert_bit_length(num_bits);
+
+    let mut builder = GadgetBuilder::new(num_bits + 1);
+    let value = builder.input(0);
+    let bits: Vec<Wire> = (1..=num_bits).map(|index| builder.input(index)).collect();
+    let outputs = decomposition_constraints(&mut builder, value, &bits);
+
+    builder.build(&outputs)
+}
+
+// Checks that every value is in [0, 2^num_bits) by decomposing it into num_bits bits
+// inputs: the bit_decomposition inputs of every value, one after the other
+// outputs: the bit_decomposition outputs of every value, one after the other
+pub fn range_check(num_values: usize, num_bits: usize) -> Gadget {
+    assert!(num_values > 0, "a gadget needs at least one input");
+    assert_bit_length(num_bits);
+
+    let mut builder = GadgetBuilder::new(num_values * (num_bits + 1));
+    let mut outputs = Vec::new();
+
+    for value_index in 0..num_values {
+        let offset = value_index * (num_bits + 1);
+        let value = builder.input(offset);
+        let bits: Vec<Wire> = (1..=num_bits)
+            .map(|index| builder.input(offset + index))
+            .collect();
+
+        outputs.extend(decomposition_constraints(&mut builder, value, &bits));
+    }
+
+    builder.build(&outputs)
+}
+
+// a < b for a and b in [0, 2^num_bits): a - b + 2^num_bits is decomposed into num_bits + 1 bits d_i,
+// the top bit is 0 exactly when a < b.
+// a and b are not range checked here, combine with range_check when they are not already known to fit.
+// inputs: [a, b, d_0, ..., d_{num_bits}]
+// outputs: [the bit_decomposition outputs of a - b + 2^num_bits, 1 - d_{num_bits}]
+// The last output is the result of the comparison (1 when a < b), the others are constraints
+pub fn less_than(num_bits: usize) -> Gadget {
+    assert_bit_length(num_bits);
+
+    let mut builder = GadgetBuilder::new(num_bits + 3);
+    let a = builder.input(0);
+    let b = builder.input(1);
+    let bits: Vec<Wire> = (2..num_bits + 3)
+        .map(|index| builder.input(index))
+        .collect();
+
+    let shifted_difference = builder.linear_combination(&[(1, a), (-1, b)], 1 << num_bits);
+    let mut outputs = decomposition_constraints(&mut builder, shifted_difference, &bits);
+    outputs.push(builder.linear_combination(&[(-1, bits[num_bits])], 1));
+
+    builder.build(&outputs)
+}
+
+// The num_bits least significant bits of value, from the least significant one
+pub fn bits_of<F: PrimeField>(value: F, num_bits: usize) -> Vec<F> {
+    let value = value.into_bigint();
+
+    (0..num_bits)
+        .map(|index| {
+            if value.get_bit(index) {
+                F::one()
+            } else {
+                F::zero()
+            }
+        })
+        .collect()
+}
+
+// The input layer of bit_decomposition for value
+pub fn bit_decomposition_witness<F: PrimeField>(value: F, num_bits: usize) -> Vec<F> {
+    let mut witness = vec![value];
+    witness.extend(bits_of(value, num_bits));
+
+    witness
+}
+
+// The input layer of range_check for values
+pub fn range_check_witness<F: PrimeField>(values: &[F], num_bits: usize) -> Vec<F> {
+    values
+        .iter()
+        .flat_map(|value| bit_decomposition_witness(*value, num_bits))
+        .collect()
+}
+
+// The input layer of less_than for a and b
+pub fn less_than_witness<F: PrimeField>(a: F, b: F, num_bits: usize) -> Vec<F> {
+    let shifted_difference = a - b + F::from(1u64 << num_bits);
+
+    let mut witness = vec![a, b];
+    witness.extend(bits_of(shifted_difference, num_bits + 1));
+
+    witness
+}
+
+fn assert_bit_length(num_bits: usize) {
+    assert!(
+        num_bits > 0 && num_bits <= MAX_GADGET_BITS,
+        "the number of bits must be between 1 and MAX_GADGET_BITS"
+    );
+}
+
+// The booleanity of every bit, then the difference between the recomposed bits and value
+fn decomposition_constraints(builder: &mut GadgetBuilder, value: Wire, bits: &[Wire]) -> Vec<Wire> {
+    let mut constraints: Vec<Wire> = bits.iter().map(|bit| builder.booleanity(*bit)).collect();
+
+    let recomposed = builder.recompose(bits);
+    constraints.push(builder.linear_combination(&[(1, recomposed), (-1, value)], 0));
+
+    constraints
+}
+
+// A wire of the gadget being built: level 0 is the input layer, a gate at level l reads from the levels below l
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Wire {
+    level: usize,
+    index: usize,
+}
+
+#[derive(Clone, Debug)]
+enum WireGate {
+    // the sum of any number of wires of the level below, a single wire is passed up unchanged
+    Sum(Vec<usize>),
+    Custom(Arc<dyn CustomGate>, Vec<usize>),
+    // reads its inputs from any levels below, through skip connections
+    Binary(Wire, Wire, Operation),
+}
+
+// Builds the layers of a gadget from the input layer up.
+// A gate is placed just above the highest of its inputs. Gates with two inputs read the lower one through a skip connection,
+// the lower inputs of the custom gates and the sums are carried up to them one layer at a time
+pub(crate) struct GadgetBuilder {
+    num_inputs: usize,
+    // levels[l - 1] holds the gates of level l, the output of a gate is its position
+    levels: Vec<Vec<WireGate>>,
+    // the position of the carry gate of a wire at the level above it
+    carried: HashMap<Wire, usize>,
+}
+
+impl GadgetBuilder {
+    pub(crate) fn new(num_inputs: usize) -> Self {
+        Self {
+            num_inputs,
+            levels: Vec::new(),
+            carried: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn input(&self, index: usize) -> Wire {
+        assert!(index < self.num_inputs, "input index out of bounds");
+
+        Wire { level: 0, index }
+    }
+
+    pub(crate) fn add(&mut self, left: Wire, right: Wire) -> Wire {
+        self.binary(left, right, Operation::ADD)
+    }
+
+    pub(crate) fn mul(&mut self, left: Wire, right: Wire) -> Wire {
+        self.binary(left, right, Operation::MUL)
+    }
+
+    fn binary(&mut self, left: Wire, right: Wire, operation: Operation) -> Wire {
+        let level = left.level.max(right.level);
+
+        self.push(level + 1, WireGate::Binary(left, right, operation))
+    }
+
+    pub(crate) fn gate(&mut self, gate: Arc<dyn CustomGate>, inputs: &[Wire]) -> Wire {
+        let level = inputs.iter().map(|wire| wire.level).max().unwrap_or(0);
+        let inputs = inputs
+            .iter()
+            .map(|wire| self.lift(*wire, level).index)
+            .collect();
+
+        self.push(level + 1, WireGate::Custom(gate, inputs))
+    }
+
+    pub(crate) fn sum(&mut self, inputs: &[Wire]) -> Wire {
+        let level = inputs.iter().map(|wire| wire.level).max().unwrap_or(0);
+        let inputs = inputs
+            .iter()
+            .map(|wire| self.lift(*wire, level).index)
+            .collect();
+
+        self.push(level + 1, WireGate::Sum(inputs))
+    }
+
+    pub(crate) fn booleanity(&mut self, wire: Wire) -> Wire {
+        self.gate(Arc::new(BooleanityGate), &[wire])
+    }
+
+    pub(crate) fn linear_combination(&mut self, terms: &[(i64, Wire)], constant: i64) -> Wire {
+        let gate = LinearCombinationGate {
+            coefficients: terms.iter().map(|(coefficient, _)| *coefficient).collect(),
+            constant,
+        };
+        let inputs: Vec<Wire> = terms.iter().map(|(_, wire)| *wire).collect();
+
+        self.gate(Arc::new(gate), &inputs)
+    }
+
+    // sum(2^i * bits[i]): neighbouring chunks of bits are added up two at a time,
+    // so the sum only takes log2(number of bits) layers
+    pub(crate) fn recompose(&mut self, bits: &[Wire]) -> Wire {
+        // every chunk is (its value, the number of bits it covers)
+        let mut chunks: Vec<(Wire, usize)> = bits.iter().map(|bit| (*bit, 1)).collect();
+
+        while chunks.len() > 1 {
+            chunks = chunks
+                .chunks(2)
+                .map(|pair| match pair {
+                    [(low, low_bits), (high, high_bits)] => (
+                        self.linear_combination(&[(1, *low), (1 << low_bits, *high)], 0),
+                        low_bits + high_bits,
+                    ),
+                    _ => pair[0],
+                })
+                .collect();
+        }
+
+        chunks[0].0
+    }
+
+    fn push(&mut self, level: usize, gate: WireGate) -> Wire {
+        if self.levels.len() < level {
+            self.levels.resize_with(level, Vec::new);
+        }
+
+        let gates = &mut self.levels[level - 1];
+        gates.push(gate);
+
+        Wire {
+            level,
+            index: gates.len() - 1,
+        }
+    }
+
+    // The same value as wire at a higher level
+    fn lift(&mut self, mut wire: Wire, level: usize) -> Wire {
+        while wire.level < level {
+            let index = match self.carried.get(&wire) {
+                Some(index) => *index,
+                None => {
+                    let index = self
+                        .push(wire.level + 1, WireGate::Sum(vec![wire.index]))
+                        .index;
+                    self.carried.insert(wire, index);
+                    index
+                }
+            };
+
+            wire = Wire {
+                level: wire.level + 1,
+                index,
+            };
+        }
+
+        wire
+    }
+
+    // The output layer only holds the outputs, in the order they are given
+    pub(crate) fn build(mut self, outputs: &[Wire]) -> Gadget {
+        let top_level = outputs
+            .iter()
+            .map(|wire| wire.level)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+
+        let outputs: Vec<Wire> = outputs
+            .iter()
+            .map(|wire| self.lift(*wire, top_level))
+            .collect();
+        let output_gates: Vec<WireGate> = outputs
+            .iter()
+            .map(|wire| self.levels[top_level - 1][wire.index].clone())
+            .collect();
+
+        self.levels.truncate(top_level);
+        self.levels[top_level - 1] = output_gates;
+
+        // the gates at level l are in the layer at index top_level - l
+        let layers =
+            self.levels
+                .into_iter()
+                .rev()
+                .map(|gates| {
+                    let mut layer = Layer::new(Vec::new());
+
+                    for (output, gate) in gates.into_iter().enumerate() {
+                        match gate {
+                            WireGate::Sum(inputs) => layer.add_multi_input_gate(
+                                MultiInputGate::new(inputs, output, Operation::ADD),
+                            ),
+                            WireGate::Custom(gate, inputs) => {
+                                layer.add_custom_gate(CustomGateInstance::new(gate, inputs, output))
+                            }
+                            WireGate::Binary(left, right, operation) => {
+                                layer.update_layer(Gate::new_with_layers(
+                                    left.index,
+                                    top_level - left.level,
+                                    right.index,
+                                    top_level - right.level,
+                                    output,
+                                    operation,
+                                ))
+                            }
+                        }
+                    }
+
+                    layer
+                })
+                .collect();
+
+        Gadget {
+            layers,
+            num_inputs: self.num_inputs,
+            num_outputs: outputs.len(),
+        }
+    }
+}
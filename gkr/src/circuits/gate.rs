@@ -1,4 +1,6 @@
 use ark_ff::PrimeField;
+use std::fmt::Debug;
+use std::sync::Arc;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Gate {
@@ -6,6 +8,11 @@ pub struct Gate {
     pub right: usize,
     pub output: usize,
     pub operation: Operation,
+    // The layers the left and right inputs are read from, indexed like the circuit evaluations:
+    // layer_evaluations[circuit.layers.len()] is the input layer.
+    // None means the layer directly below the gate's layer
+    pub left_layer: Option<usize>,
+    pub right_layer: Option<usize>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -28,6 +35,267 @@ impl Gate {
             right,
             output,
             operation,
+            left_layer: None,
+            right_layer: None,
         }
     }
+
+    // A gate with skip connections: its inputs can come from any layer below its own layer, including the input layer
+    pub fn new_with_layers(
+        left: usize,
+        left_layer: usize,
+        right: usize,
+        right_layer: usize,
+        output: usize,
+        operation: Operation,
+    ) -> Self {
+        Self {
+            left,
+            right,
+            output,
+            operation,
+            left_layer: Some(left_layer),
+            right_layer: Some(right_layer),
+        }
+    }
+
+    // The layers the gate reads from, for a gate placed in the layer at layer_index
+    pub fn source_layers(&self, layer_index: usize) -> (usize, usize) {
+        (
+            self.left_layer.unwrap_or(layer_index + 1),
+            self.right_layer.unwrap_or(layer_index + 1),
+        )
+    }
+}
+
+// Multiplication gates can't have an arbitrary fan-in: the wiring predicate of a multiplication with fan-in k
+// is a polynomial over the output and k blocks of input variables, so its size grows exponentially with k
+pub const MAX_MULTIPLICATION_FAN_IN: usize = 4;
+
+// A gate reading any number of wires from the layer directly below it:
+// ADD sums all of its inputs (any fan-in), MUL multiplies them (fan-in up to MAX_MULTIPLICATION_FAN_IN)
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultiInputGate {
+    pub inputs: Vec<usize>,
+    pub output: usize,
+    pub operation: Operation,
+}
+
+impl MultiInputGate {
+    pub fn new(inputs: Vec<usize>, output: usize, operation: Operation) -> Self {
+        assert!(!inputs.is_empty(), "a gate needs at least one input");
+        assert!(
+            operation == Operation::ADD || inputs.len() <= MAX_MULTIPLICATION_FAN_IN,
+            "multiplication fan-in is bounded by MAX_MULTIPLICATION_FAN_IN"
+        );
+
+        Self {
+            inputs,
+            output,
+            operation,
+        }
+    }
+
+    pub fn evaluate<F: PrimeField>(&self, layer_below: &[F]) -> F {
+        let input_values = self.inputs.iter().map(|input| layer_below[*input]);
+
+        match self.operation {
+            Operation::ADD => input_values.sum(),
+            Operation::MUL => input_values.product(),
+        }
+    }
+}
+
+// The largest number of inputs of a custom gate, for the same reason as MAX_MULTIPLICATION_FAN_IN
+pub const MAX_CUSTOM_GATE_ARITY: usize = MAX_MULTIPLICATION_FAN_IN;
+
+// A gate whose output is any polynomial in its input wire values, eg: x^5 for the Poseidon S-boxes, or a * b + c.
+// The polynomial is given as a sum of terms: coefficient * x_0^e_0 * x_1^e_1 * ... (one exponent per input).
+// The prover and the verifier build the sumcheck terms of the layer from these terms,
+// every input of the gate is on its own block of variables, so x_j^e makes the sumcheck of the layer degree e + 1
+pub trait CustomGate: Debug + Send + Sync {
+    // The number of input wires of the gate
+    fn arity(&self) -> usize;
+
+    // The largest total degree of the terms
+    fn degree(&self) -> usize;
+
+    fn terms(&self) -> Vec<CustomGateTerm>;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CustomGateTerm {
+    pub coefficient: i64,
+    pub exponents: Vec<usize>,
+}
+
+impl CustomGateTerm {
+    pub fn new(coefficient: i64, exponents: Vec<usize>) -> Self {
+        Self {
+            coefficient,
+            exponents,
+        }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.exponents.iter().sum()
+    }
+
+    pub fn evaluate<F: PrimeField>(&self, inputs: &[F]) -> F {
+        self.exponents
+            .iter()
+            .zip(inputs.iter())
+            .fold(F::from(self.coefficient), |result, (exponent, input)| {
+                result * input.pow([*exponent as u64])
+            })
+    }
+}
+
+// A custom gate placed in a layer: it reads its inputs from the layer directly below and writes to output
+#[derive(Clone, Debug)]
+pub struct CustomGateInstance {
+    pub gate: Arc<dyn CustomGate>,
+    pub inputs: Vec<usize>,
+    pub output: usize,
+}
+
+impl CustomGateInstance {
+    pub fn new(gate: Arc<dyn CustomGate>, inputs: Vec<usize>, output: usize) -> Self {
+        assert_eq!(
+            inputs.len(),
+            gate.arity(),
+            "the number of inputs must be the arity of the gate"
+        );
+        assert!(!gate.terms().is_empty(), "the gate needs at least one term");
+        assert!(
+            gate.arity() <= MAX_CUSTOM_GATE_ARITY,
+            "custom gate arity is bounded by MAX_CUSTOM_GATE_ARITY"
+        );
+        assert!(
+            gate.terms().iter().all(|term| {
+                term.exponents.len() == gate.arity() && term.degree() <= gate.degree()
+            }),
+            "every term needs one exponent per input and a degree of at most the gate degree"
+        );
+
+        Self {
+            gate,
+            inputs,
+            output,
+        }
+    }
+
+    pub fn evaluate<F: PrimeField>(&self, layer_below: &[F]) -> F {
+        let input_values: Vec<F> = self
+            .inputs
+            .iter()
+            .map(|input| layer_below[*input])
+            .collect();
+
+        self.gate
+            .terms()
+            .iter()
+            .map(|term| term.evaluate(&input_values))
+            .sum()
+    }
+}
+
+// x^exponent, eg: the x^5 S-box of Poseidon
+#[derive(Clone, Debug)]
+pub struct PowerGate {
+    pub exponent: usize,
+}
+
+impl CustomGate for PowerGate {
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn degree(&self) -> usize {
+        self.exponent
+    }
+
+    fn terms(&self) -> Vec<CustomGateTerm> {
+        vec![CustomGateTerm::new(1, vec![self.exponent])]
+    }
+}
+
+// a * b + c
+#[derive(Clone, Debug)]
+pub struct MulAddGate;
+
+impl CustomGate for MulAddGate {
+    fn arity(&self) -> usize {
+        3
+    }
+
+    fn degree(&self) -> usize {
+        2
+    }
+
+    fn terms(&self) -> Vec<CustomGateTerm> {
+        vec![
+            CustomGateTerm::new(1, vec![1, 1, 0]),
+            CustomGateTerm::new(1, vec![0, 0, 1]),
+        ]
+    }
+}
+
+// x - x^2, which is 0 exactly when x is 0 or 1
+#[derive(Clone, Debug)]
+pub struct BooleanityGate;
+
+impl CustomGate for BooleanityGate {
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn degree(&self) -> usize {
+        2
+    }
+
+    fn terms(&self) -> Vec<CustomGateTerm> {
+        vec![
+            CustomGateTerm::new(1, vec![1]),
+            CustomGateTerm::new(-1, vec![2]),
+        ]
+    }
+}
+
+// c_0 * x_0 + c_1 * x_1 + ... + constant, eg: lo + 2^k * hi to recompose a number from its bits
+#[derive(Clone, Debug)]
+pub struct LinearCombinationGate {
+    pub coefficients: Vec<i64>,
+    pub constant: i64,
+}
+
+impl CustomGate for LinearCombinationGate {
+    fn arity(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    fn degree(&self) -> usize {
+        1
+    }
+
+    fn terms(&self) -> Vec<CustomGateTerm> {
+        let arity = self.arity();
+
+        let mut terms: Vec<CustomGateTerm> = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .map(|(input, coefficient)| {
+                let mut exponents = vec![0; arity];
+                exponents[input] = 1;
+                CustomGateTerm::new(*coefficient, exponents)
+            })
+            .collect();
+
+        if self.constant != 0 {
+            terms.push(CustomGateTerm::new(self.constant, vec![0; arity]));
+        }
+
+        terms
+    }
 }
@@ -0,0 +1,582 @@
+use crate::circuits::gadgets::{Gadget, GadgetBuilder, Wire};
+use crate::circuits::gate::{CustomGate, CustomGateTerm, PowerGate};
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use sha3::{Digest, Keccak256};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+// Circuits for the MiMC and Poseidon permutations over the BN254 scalar field, the hashes built from them,
+// The round constants (and the Poseidon MDS matrix) are part of the input layer (the gates only have small integer coefficients),
+// The round constants are part of the input layer (the gates only have small integer coefficients),
+// the witness helpers append them after the values being hashed.
+
+// MiMC-7: x -> (x + k + c_i)^7 for every round i, then + k.
+// 7 is the smallest exponent with gcd(e, r - 1) = 1 for the BN254 scalar field r, so that x^7 is a permutation
+pub const MIMC_EXPONENT: usize = 7;
+const MIMC_SEED: &[u8] = b"mimc";
+
+// Poseidon with a state of 3 elements: 1 capacity element (state[0]) and 2 rate elements
+pub const POSEIDON_WIDTH: usize = 3;
+pub const POSEIDON_RATE: usize = 2;
+pub const POSEIDON_EXPONENT: usize = 5;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MimcParams<F: PrimeField> {
+    // one constant per round, the first one is 0
+    pub round_constants: Vec<F>,
+}
+
+impl<F: PrimeField> MimcParams<F> {
+    pub fn new(num_rounds: usize) -> Self {
+        assert!(num_rounds > 0, "MiMC needs at least one round");
+
+        let mut round_constants = vec![F::zero()];
+        round_constants.extend(keccak_constants::<F>(MIMC_SEED, num_rounds - 1));
+
+        Self { round_constants }
+    }
+}
+
+// 91 rounds: ceil(log_7(r)) for the BN254 scalar field
+impl Default for MimcParams<Fr> {
+    fn default() -> Self {
+        Self::new(91)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoseidonParams<F: PrimeField> {
+    // half of the full rounds are before the partial rounds, the other half after them
+    pub full_rounds: usize,
+    pub partial_rounds: usize,
+    // POSEIDON_WIDTH constants per round, round after round
+    pub round_constants: Vec<F>,
+    // the linear layer: state[i] = sum(mds[i][j] * state[j])
+    pub mds: [[F; POSEIDON_WIDTH]; POSEIDON_WIDTH],
+}
+
+impl<F: PrimeField> PoseidonParams<F> {
+    // The round constants and the MDS matrix are generated like the reference implementation of the Poseidon paper
+    // (generate_parameters_grain.sage): the Grain LFSR is seeded with the parameters, the constants are sampled
+    // by rejection, then the MDS matrix is the Cauchy matrix 1 / (x_i + y_j) of the next 2 * POSEIDON_WIDTH samples.
+    // For the BN254 scalar field with 8 full rounds and 57 partial rounds, these are the circomlib constants
+    pub fn new(full_rounds: usize, partial_rounds: usize) -> Self {
+        assert!(
+            full_rounds.is_multiple_of(2),
+            "the full rounds are split evenly around the partial rounds"
+        );
+
+        let mut lfsr = GrainLfsr::new::<F>(full_rounds, partial_rounds);
+
+        let round_constants = (0..(full_rounds + partial_rounds) * POSEIDON_WIDTH)
+            .map(|_| lfsr.field_element())
+            .collect();
+
+        let mds = loop {
+            let samples: Vec<F> = (0..2 * POSEIDON_WIDTH)
+                .map(|_| lfsr.field_element_mod_order())
+                .collect();
+            let (xs, ys) = samples.split_at(POSEIDON_WIDTH);
+
+            let distinct = samples
+                .iter()
+                .enumerate()
+                .all(|(i, sample)| !samples[..i].contains(sample));
+            let invertible = xs.iter().all(|x| ys.iter().all(|y| !(*x + y).is_zero()));
+
+            if distinct && invertible {
+                break [0, 1, 2].map(|i| [0, 1, 2].map(|j| (xs[i] + ys[j]).inverse().unwrap()));
+            }
+        };
+
+        Self {
+            full_rounds,
+            partial_rounds,
+            round_constants,
+            mds,
+        }
+    }
+
+    // The constants the circuits read from the input layer: the round constants, then the MDS matrix row by row
+    pub fn constants(&self) -> Vec<F> {
+        let mut constants = self.round_constants.clone();
+        constants.extend(self.mds.iter().flatten());
+
+        constants
+    }
+
+    pub fn num_rounds(&self) -> usize {
+        self.full_rounds + self.partial_rounds
+    }
+
+    pub fn is_full_round(&self, round: usize) -> bool {
+        round < self.full_rounds / 2 || round >= self.full_rounds / 2 + self.partial_rounds
+    }
+}
+
+// The number of rounds of the Poseidon paper for a 254 bit field with a state of 3 elements and x^5
+impl Default for PoseidonParams<Fr> {
+    fn default() -> Self {
+        Self::new(8, 57)
+    }
+}
+
+// A hash function that has a circuit, used to build the Merkle path circuits with either hash
+#[derive(Clone, Debug)]
+pub enum AlgebraicHash<F: PrimeField> {
+    // Miyaguchi-Preneel on MiMC-7: h_0 = 0, h_{i+1} = h_i + m_i + E_{h_i}(m_i)
+    Mimc(MimcParams<F>),
+    // sponge on the Poseidon permutation, the output is state[0] like circomlib's Poseidon:
+    // with at most POSEIDON_RATE inputs, this is circomlib's poseidon(inputs)
+    Poseidon(PoseidonParams<F>),
+}
+
+impl<F: PrimeField> AlgebraicHash<F> {
+    pub fn hash(&self, inputs: &[F]) -> F {
+        assert!(!inputs.is_empty(), "nothing to hash");
+
+        match self {
+            AlgebraicHash::Mimc(params) => mimc_hash(inputs, params),
+            AlgebraicHash::Poseidon(params) => poseidon_hash(inputs, params),
+        }
+    }
+
+    // The constants the circuits of the hash read from the input layer
+    pub fn constants(&self) -> Vec<F> {
+        match self {
+            AlgebraicHash::Mimc(params) => params.round_constants.clone(),
+            AlgebraicHash::Poseidon(params) => params.constants(),
+        }
+    }
+
+    fn hash_wires(&self, builder: &mut GadgetBuilder, constants: &[Wire], inputs: &[Wire]) -> Wire {
+        match self {
+            AlgebraicHash::Mimc(_) => mimc_hash_wires(builder, constants, inputs),
+            AlgebraicHash::Poseidon(params) => {
+                poseidon_hash_wires(builder, params, constants, inputs)
+            }
+        }
+    }
+}
+
+pub fn mimc_encrypt<F: PrimeField>(x: F, key: F, params: &MimcParams<F>) -> F {
+    params.round_constants.iter().fold(x, |x, constant| {
+        (x + key + constant).pow([MIMC_EXPONENT as u64])
+    }) + key
+}
+
+pub fn mimc_hash<F: PrimeField>(inputs: &[F], params: &MimcParams<F>) -> F {
+    inputs.iter().fold(F::zero(), |state, input| {
+        state + input + mimc_encrypt(*input, state, params)
+    })
+}
+
+pub fn poseidon_permutation<F: PrimeField>(
+    mut state: [F; POSEIDON_WIDTH],
+    params: &PoseidonParams<F>,
+) -> [F; POSEIDON_WIDTH] {
+    for round in 0..params.num_rounds() {
+        for (j, value) in state.iter_mut().enumerate() {
+            *value += params.round_constants[round * POSEIDON_WIDTH + j];
+        }
+
+        // the S-boxes are applied to the whole state in the full rounds, and to state[0] only in the partial rounds
+        for (j, value) in state.iter_mut().enumerate() {
+            if j == 0 || params.is_full_round(round) {
+                *value = value.pow([POSEIDON_EXPONENT as u64]);
+            }
+        }
+
+        state = params.mds.map(|row| {
+            row.iter()
+                .zip(state.iter())
+                .map(|(coefficient, value)| *coefficient * value)
+                .sum()
+        });
+    }
+
+    state
+}
+
+pub fn poseidon_hash<F: PrimeField>(inputs: &[F], params: &PoseidonParams<F>) -> F {
+    let mut state = [F::zero(); POSEIDON_WIDTH];
+
+    for chunk in inputs.chunks(POSEIDON_RATE) {
+        for (j, input) in chunk.iter().enumerate() {
+            state[1 + j] += input;
+        }
+
+        state = poseidon_permutation(state, params);
+    }
+
+    state[0]
+}
+
+// The root of the Merkle tree a leaf belongs to: path holds the sibling of every node from the leaf up,
+// and whether the node is the right child of its parent
+pub fn merkle_root<F: PrimeField>(hash: &AlgebraicHash<F>, leaf: F, path: &[(F, bool)]) -> F {
+    path.iter().fold(leaf, |node, (sibling, is_right)| {
+        if *is_right {
+            hash.hash(&[*sibling, node])
+        } else {
+            hash.hash(&[node, *sibling])
+        }
+    })
+}
+
+// E_k(x)
+// inputs: [x, k, c_0, ..., c_{rounds-1}]
+// outputs: [E_k(x)]
+pub fn mimc_circuit<F: PrimeField>(params: &MimcParams<F>) -> Gadget {
+    let mut builder = GadgetBuilder::new(2 + params.round_constants.len());
+    let x = builder.input(0);
+    let key = builder.input(1);
+    let constants = constant_wires(&builder, 2, params.round_constants.len());
+
+    let output = mimc_encrypt_wires(&mut builder, &constants, x, Some(key));
+
+    builder.build(&[output])
+}
+
+pub fn mimc_witness<F: PrimeField>(x: F, key: F, params: &MimcParams<F>) -> Vec<F> {
+    let mut witness = vec![x, key];
+    witness.extend(params.round_constants.iter());
+
+    witness
+}
+
+// inputs: [s_0, s_1, s_2, the round constants, the MDS matrix row by row]
+// outputs: the permuted state
+pub fn poseidon_permutation_circuit<F: PrimeField>(params: &PoseidonParams<F>) -> Gadget {
+    let num_constants = params.constants().len();
+    let mut builder = GadgetBuilder::new(POSEIDON_WIDTH + num_constants);
+    let state = [0, 1, 2].map(|index| Some(builder.input(index)));
+    let constants = constant_wires(&builder, POSEIDON_WIDTH, num_constants);
+
+    let output = poseidon_permutation_wires(
+        &mut builder,
+        params,
+        &constants,
+        state,
+        [None; POSEIDON_WIDTH],
+    );
+
+    builder.build(&output)
+}
+
+pub fn poseidon_permutation_witness<F: PrimeField>(
+    state: [F; POSEIDON_WIDTH],
+    params: &PoseidonParams<F>,
+) -> Vec<F> {
+    let mut witness = state.to_vec();
+    witness.extend(params.constants());
+
+    witness
+}
+
+// inputs: [m_0, ..., m_{num_inputs-1}, the constants of the hash]
+// outputs: [hash(m_0, ..., m_{num_inputs-1})]
+pub fn hash_circuit<F: PrimeField>(hash: &AlgebraicHash<F>, num_inputs: usize) -> Gadget {
+    assert!(num_inputs > 0, "nothing to hash");
+
+    let mut builder = GadgetBuilder::new(num_inputs + hash.constants().len());
+    let inputs: Vec<Wire> = (0..num_inputs).map(|index| builder.input(index)).collect();
+    let constants = constant_wires(&builder, num_inputs, hash.constants().len());
+
+    let output = hash.hash_wires(&mut builder, &constants, &inputs);
+
+    builder.build(&[output])
+}
+
+pub fn hash_witness<F: PrimeField>(hash: &AlgebraicHash<F>, inputs: &[F]) -> Vec<F> {
+    let mut witness = inputs.to_vec();
+    witness.extend(hash.constants());
+
+    witness
+}
+
+// Recomputes the root from a leaf and its path, the verifier compares the output with the expected root.
+// The direction bits are not constrained: they are in the input layer, which the verifier reads in full.
+// inputs: [leaf, sibling_0, is_right_0, ..., sibling_{depth-1}, is_right_{depth-1}, the constants of the hash]
+// outputs: [root]
+pub fn merkle_path_circuit<F: PrimeField>(hash: &AlgebraicHash<F>, depth: usize) -> Gadget {
+    let path_length = 1 + 2 * depth;
+    let mut builder = GadgetBuilder::new(path_length + hash.constants().len());
+    let constants = constant_wires(&builder, path_length, hash.constants().len());
+
+    let mut node = builder.input(0);
+
+    for level in 0..depth {
+        let sibling = builder.input(1 + 2 * level);
+        let is_right = builder.input(2 + 2 * level);
+
+        // left = node + b * (sibling - node), right = sibling + b * (node - sibling)
+        // the terms that don't depend on the node only read the input layer, they are computed in the first layers
+        let b_sibling = builder.mul(is_right, sibling);
+        let minus_b = builder.linear_combination(&[(-1, is_right)], 0);
+        let sibling_minus_b_sibling =
+            builder.linear_combination(&[(1, sibling), (-1, b_sibling)], 0);
+
+        let node_plus_b_sibling = builder.add(node, b_sibling);
+        let minus_b_node = builder.mul(node, minus_b);
+        let left = builder.add(node_plus_b_sibling, minus_b_node);
+
+        let b_node = builder.mul(node, is_right);
+        let right = builder.add(b_node, sibling_minus_b_sibling);
+
+        node = hash.hash_wires(&mut builder, &constants, &[left, right]);
+    }
+
+    builder.build(&[node])
+}
+
+pub fn merkle_path_witness<F: PrimeField>(
+    hash: &AlgebraicHash<F>,
+    leaf: F,
+    path: &[(F, bool)],
+) -> Vec<F> {
+    let mut witness = vec![leaf];
+
+    for (sibling, is_right) in path.iter() {
+        witness.push(*sibling);
+        witness.push(F::from(*is_right));
+    }
+
+    witness.extend(hash.constants());
+
+    witness
+}
+
+// A hash chain from the seed, every hash is reduced modulo the field order
+fn keccak_constants<F: PrimeField>(seed: &[u8], count: usize) -> Vec<F> {
+    let mut hash = Keccak256::digest(seed);
+
+    (0..count)
+        .map(|_| {
+            hash = Keccak256::digest(hash);
+            F::from_be_bytes_mod_order(&hash)
+        })
+        .collect()
+}
+
+// The Grain LFSR of the Poseidon reference implementation, for a prime field and the x^5 S-box
+struct GrainLfsr {
+    state: VecDeque<bool>,
+    field_size: usize,
+}
+
+impl GrainLfsr {
+    fn new<F: PrimeField>(full_rounds: usize, partial_rounds: usize) -> Self {
+        let field_size = F::MODULUS_BIT_SIZE as usize;
+
+        // 2 bits for the field (1: prime field), 4 for the S-box (0: x^alpha), 12 for the field size,
+        // 12 for the width, 10 for each number of rounds, then 30 bits set to 1
+        let mut state = VecDeque::with_capacity(80);
+        for (value, num_bits) in [
+            (1, 2),
+            (0, 4),
+            (field_size, 12),
+            (POSEIDON_WIDTH, 12),
+            (full_rounds, 10),
+            (partial_rounds, 10),
+        ] {
+            state.extend((0..num_bits).rev().map(|bit| (value >> bit) & 1 == 1));
+        }
+        state.extend([true; 30]);
+
+        let mut lfsr = Self { state, field_size };
+        for _ in 0..160 {
+            lfsr.next_bit();
+        }
+
+        lfsr
+    }
+
+    fn next_bit(&mut self) -> bool {
+        let bit = [62, 51, 38, 23, 13, 0]
+            .iter()
+            .fold(false, |bit, position| bit ^ self.state[*position]);
+
+        self.state.pop_front();
+        self.state.push_back(bit);
+
+        bit
+    }
+
+    // The bits are read in pairs, the second bit of a pair is kept when the first one is set
+    fn random_bit(&mut self) -> bool {
+        loop {
+            let keep = self.next_bit();
+            let bit = self.next_bit();
+
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    fn random_integer<F: PrimeField>(&mut self) -> F::BigInt {
+        let bits: Vec<bool> = (0..self.field_size).map(|_| self.random_bit()).collect();
+
+        F::BigInt::from_bits_be(&bits)
+    }
+
+    // Samples until the integer is smaller than the field order
+    fn field_element<F: PrimeField>(&mut self) -> F {
+        loop {
+            if let Some(element) = F::from_bigint(self.random_integer::<F>()) {
+                return element;
+            }
+        }
+    }
+
+    fn field_element_mod_order<F: PrimeField>(&mut self) -> F {
+        F::from_be_bytes_mod_order(&self.random_integer::<F>().to_bytes_be())
+    }
+}
+
+fn constant_wires(builder: &GadgetBuilder, offset: usize, count: usize) -> Vec<Wire> {
+    (offset..offset + count)
+        .map(|index| builder.input(index))
+        .collect()
+}
+
+// E_key(x), a missing key is 0
+fn mimc_encrypt_wires(
+    builder: &mut GadgetBuilder,
+    constants: &[Wire],
+    x: Wire,
+    key: Option<Wire>,
+) -> Wire {
+    // k + c_i for every round, computed once right above the key: every round is then a single addition and the S-box
+    let round_keys: Vec<Wire> = match key {
+        Some(key) => constants
+            .iter()
+            .map(|constant| builder.add(key, *constant))
+            .collect(),
+        None => constants.to_vec(),
+    };
+    let sbox: Arc<dyn CustomGate> = Arc::new(PowerGate {
+        exponent: MIMC_EXPONENT,
+    });
+
+    let output = round_keys.into_iter().fold(x, |x, round_key| {
+        let sum = builder.add(x, round_key);
+        builder.gate(sbox.clone(), &[sum])
+    });
+
+    match key {
+        Some(key) => builder.add(output, key),
+        None => output,
+    }
+}
+
+fn mimc_hash_wires(builder: &mut GadgetBuilder, constants: &[Wire], inputs: &[Wire]) -> Wire {
+    let mut state: Option<Wire> = None;
+
+    for input in inputs.iter() {
+        let encrypted = mimc_encrypt_wires(builder, constants, *input, state);
+
+        state = Some(match state {
+            Some(state) => {
+                let sum = builder.add(encrypted, state);
+                builder.add(sum, *input)
+            }
+            None => builder.add(encrypted, *input),
+        });
+    }
+
+    state.expect("nothing to hash")
+}
+
+// An S-box followed by one coefficient of the MDS matrix: m * x^5
+#[derive(Clone, Debug)]
+struct PoseidonSboxGate;
+
+impl CustomGate for PoseidonSboxGate {
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn degree(&self) -> usize {
+        POSEIDON_EXPONENT + 1
+    }
+
+    fn terms(&self) -> Vec<CustomGateTerm> {
+        vec![CustomGateTerm::new(1, vec![1, POSEIDON_EXPONENT])]
+    }
+}
+
+// Every round is three layers: the round constants are added, then every mds[i][j] * S(state[j]) is a gate
+// (a multiplication for the state elements without an S-box in the partial rounds), and the products of each row are summed.
+// The MDS matrix is read from the input layer, after the round constants.
+// A missing state element is 0, absorbed holds the inputs added to the state before the first round
+fn poseidon_permutation_wires<F: PrimeField>(
+    builder: &mut GadgetBuilder,
+    params: &PoseidonParams<F>,
+    constants: &[Wire],
+    state: [Option<Wire>; POSEIDON_WIDTH],
+    absorbed: [Option<Wire>; POSEIDON_WIDTH],
+) -> [Wire; POSEIDON_WIDTH] {
+    let mut state = state;
+
+    for round in 0..params.num_rounds() {
+        let mut round_state = Vec::with_capacity(POSEIDON_WIDTH);
+
+        for j in 0..POSEIDON_WIDTH {
+            let constant = constants[round * POSEIDON_WIDTH + j];
+            // the input is added to the constant first, it only depends on the input layer
+            let addend = match absorbed[j] {
+                Some(input) if round == 0 => builder.add(input, constant),
+                _ => constant,
+            };
+
+            round_state.push(match state[j] {
+                Some(value) => builder.add(value, addend),
+                None => addend,
+            });
+        }
+
+        let full_round = params.is_full_round(round);
+        let mds = &constants[params.round_constants.len()..];
+
+        state = [0, 1, 2].map(|i| {
+            let products: Vec<Wire> = (0..POSEIDON_WIDTH)
+                .map(|j| {
+                    let coefficient = mds[i * POSEIDON_WIDTH + j];
+
+                    if j == 0 || full_round {
+                        builder.gate(Arc::new(PoseidonSboxGate), &[coefficient, round_state[j]])
+                    } else {
+                        builder.mul(coefficient, round_state[j])
+                    }
+                })
+                .collect();
+
+            Some(builder.sum(&products))
+        });
+    }
+
+    state.map(|value| value.expect("the state is set by the first round"))
+}
+
+fn poseidon_hash_wires<F: PrimeField>(
+    builder: &mut GadgetBuilder,
+    params: &PoseidonParams<F>,
+    constants: &[Wire],
+    inputs: &[Wire],
+) -> Wire {
+    let mut state = [None; POSEIDON_WIDTH];
+
+    for chunk in inputs.chunks(POSEIDON_RATE) {
+        let mut absorbed = [None; POSEIDON_WIDTH];
+        for (j, input) in chunk.iter().enumerate() {
+            absorbed[1 + j] = Some(*input);
+        }
+
+        state = poseidon_permutation_wires(builder, params, constants, state, absorbed).map(Some);
+    }
+
+    state[0].expect("nothing to hash")
+}
@@ -1,15 +1,52 @@
-use crate::circuits::gate::Gate;
+use crate::circuits::gate::{CustomGateInstance, Gate, MultiInputGate};
 
 pub struct Layer {
     pub gates: Vec<Gate>,
+    pub multi_input_gates: Vec<MultiInputGate>,
+    pub custom_gates: Vec<CustomGateInstance>,
 }
 
 impl Layer {
     pub fn new(gates: Vec<Gate>) -> Self {
-        Self { gates }
+        Self {
+            gates,
+            multi_input_gates: Vec::new(),
+            custom_gates: Vec::new(),
+        }
+    }
+
+    // A layer can mix gates with two inputs and gates with any number of inputs, they write to the same outputs
+    pub fn new_with_multi_input_gates(
+        gates: Vec<Gate>,
+        multi_input_gates: Vec<MultiInputGate>,
+    ) -> Self {
+        Self {
+            gates,
+            multi_input_gates,
+            custom_gates: Vec::new(),
+        }
     }
 
     pub fn update_layer(&mut self, _layer_gates: Gate) {
         self.gates.push(_layer_gates);
     }
+
+    pub fn add_multi_input_gate(&mut self, gate: MultiInputGate) {
+        self.multi_input_gates.push(gate);
+    }
+
+    pub fn add_custom_gate(&mut self, gate: CustomGateInstance) {
+        self.custom_gates.push(gate);
+    }
+
+    // The largest output index written by any gate of the layer
+    pub fn max_output_index(&self) -> usize {
+        self.gates
+            .iter()
+            .map(|gate| gate.output)
+            .chain(self.multi_input_gates.iter().map(|gate| gate.output))
+            .chain(self.custom_gates.iter().map(|gate| gate.output))
+            .max()
+            .unwrap_or(0)
+    }
 }
@@ -0,0 +1,186 @@
+use crate::circuits::circuit::Circuit;
+use crate::circuits::gadgets::{GadgetBuilder, Wire};
+use ark_ff::PrimeField;
+
+// Parametrized circuits for common verifiable computations, to be used as benchmarks and as templates.
+// Every generator returns the circuit and the layout of its input layer: the named segments of inputs, in order.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputSegment {
+    pub name: &'static str,
+    pub offset: usize,
+    pub len: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputLayout {
+    pub segments: Vec<InputSegment>,
+}
+
+impl InputLayout {
+    // The segments are placed one after the other, in the order they are given
+    pub fn new(segments: &[(&'static str, usize)]) -> Self {
+        let mut offset = 0;
+
+        let segments = segments
+            .iter()
+            .map(|(name, len)| {
+                let segment = InputSegment {
+                    name,
+                    offset,
+                    len: *len,
+                };
+                offset += len;
+                segment
+            })
+            .collect();
+
+        Self { segments }
+    }
+
+    pub fn num_inputs(&self) -> usize {
+        self.segments.iter().map(|segment| segment.len).sum()
+    }
+
+    pub fn segment(&self, name: &str) -> &InputSegment {
+        self.segments
+            .iter()
+            .find(|segment| segment.name == name)
+            .expect("no input segment with this name")
+    }
+
+    // The input layer holding values[i] in the i-th segment
+    pub fn assign<F: PrimeField>(&self, values: &[&[F]]) -> Vec<F> {
+        assert_eq!(
+            values.len(),
+            self.segments.len(),
+            "one slice of values is needed per segment"
+        );
+        assert!(
+            self.segments
+                .iter()
+                .zip(values.iter())
+                .all(|(segment, values)| segment.len == values.len()),
+            "the values must have the length of their segment"
+        );
+
+        values.concat()
+    }
+
+    fn wires(&self, builder: &GadgetBuilder, name: &str) -> Vec<Wire> {
+        let segment = self.segment(name);
+
+        (segment.offset..segment.offset + segment.len)
+            .map(|index| builder.input(index))
+            .collect()
+    }
+}
+
+// <a, b> for two vectors of length n: a layer of products and a single summation gate
+// inputs: a, b
+// outputs: [<a, b>]
+pub fn dot_product<F: PrimeField>(n: usize) -> (Circuit<F>, InputLayout) {
+    assert!(n > 0, "the vectors can't be empty");
+
+    let layout = InputLayout::new(&[("a", n), ("b", n)]);
+    let mut builder = GadgetBuilder::new(layout.num_inputs());
+    let a = layout.wires(&builder, "a");
+    let b = layout.wires(&builder, "b");
+
+    let products: Vec<Wire> = a
+        .iter()
+        .zip(b.iter())
+        .map(|(a, b)| builder.mul(*a, *b))
+        .collect();
+    let output = builder.sum(&products);
+
+    (builder.build(&[output]).circuit(), layout)
+}
+
+// C = A * B for A with rows x inner entries and B with inner x columns entries, all stored row by row:
+// a layer with all the products A[i][k] * B[k][j], and a summation gate for every entry of C
+// inputs: A, B
+// outputs: C
+pub fn matrix_multiplication<F: PrimeField>(
+    rows: usize,
+    inner: usize,
+    columns: usize,
+) -> (Circuit<F>, InputLayout) {
+    assert!(
+        rows > 0 && inner > 0 && columns > 0,
+        "the matrices can't be empty"
+    );
+
+    let layout = InputLayout::new(&[("A", rows * inner), ("B", inner * columns)]);
+    let mut builder = GadgetBuilder::new(layout.num_inputs());
+    let a = layout.wires(&builder, "A");
+    let b = layout.wires(&builder, "B");
+
+    let mut outputs = Vec::with_capacity(rows * columns);
+
+    for i in 0..rows {
+        for j in 0..columns {
+            let products: Vec<Wire> = (0..inner)
+                .map(|k| builder.mul(a[i * inner + k], b[k * columns + j]))
+                .collect();
+            outputs.push(builder.sum(&products));
+        }
+    }
+
+    (builder.build(&outputs).circuit(), layout)
+}
+
+// p(x) = c_0 + c_1 * x + ... + c_degree * x^degree at every point, by Horner's method:
+// p = c_degree, then p = p * x + c_i for i from degree - 1 down to 0, two layers per coefficient
+// inputs: coefficients (c_0 first), points
+// outputs: p(x) for every point
+pub fn polynomial_evaluation<F: PrimeField>(
+    degree: usize,
+    num_points: usize,
+) -> (Circuit<F>, InputLayout) {
+    assert!(
+        degree > 0,
+        "the polynomial must have a degree of at least 1"
+    );
+    assert!(num_points > 0, "at least one point is needed");
+
+    let layout = InputLayout::new(&[("coefficients", degree + 1), ("points", num_points)]);
+    let mut builder = GadgetBuilder::new(layout.num_inputs());
+    let coefficients = layout.wires(&builder, "coefficients");
+    let points = layout.wires(&builder, "points");
+
+    let outputs: Vec<Wire> = points
+        .iter()
+        .map(|point| {
+            coefficients[..degree]
+                .iter()
+                .rev()
+                .fold(coefficients[degree], |result, coefficient| {
+                    let product = builder.mul(result, *point);
+                    builder.add(product, *coefficient)
+                })
+        })
+        .collect();
+
+    (builder.build(&outputs).circuit(), layout)
+}
+
+// f_n for f_{k+1} = f_k + f_{k-1}, one layer per step: every addition reads the two layers below it
+// inputs: [f_0, f_1]
+// outputs: [f_n]
+pub fn fibonacci<F: PrimeField>(n: usize) -> (Circuit<F>, InputLayout) {
+    assert!(n >= 2, "f_0 and f_1 are inputs, n must be at least 2");
+
+    let layout = InputLayout::new(&[("f_0", 1), ("f_1", 1)]);
+    let mut builder = GadgetBuilder::new(layout.num_inputs());
+    let mut previous = builder.input(0);
+    let mut current = builder.input(1);
+
+    for _ in 1..n {
+        let next = builder.add(previous, current);
+        previous = current;
+        current = next;
+    }
+
+    (builder.build(&[current]).circuit(), layout)
+}
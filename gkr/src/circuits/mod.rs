@@ -1,3 +1,6 @@
 pub mod circuit;
+pub mod gadgets;
 pub mod gate;
+pub mod hashes;
 pub mod layer;
+pub mod library;
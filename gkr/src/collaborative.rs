@@ -0,0 +1,410 @@
+use crate::circuits::circuit::{pad_to_num_vars, Circuit};
+use crate::circuits::gate::{Gate, Operation};
+use crate::evaluation::MultilinearPolynomialEV;
+use crate::gkr_p::{combine_layer_claims, output_claim, push_layer_claims, CombinedClaim, Proof};
+use crate::gkr_sumcheck_dependencies::transcript::Transcript;
+use crate::interactive::{
+    invalid_message_error, receive_field_elements, send_field_elements, Channel, InMemoryChannel,
+};
+use crate::layer_rounds::{absorb_round, fold_products, round_evaluations};
+use crate::sum_check::{field_element_to_bytes, SumcheckProverProof};
+use crate::wb_bc_utility::compute_combined_add_i_mul_i;
+use ark_ff::PrimeField;
+use sharmir_secret_sharing::shamir_secret_sharing::{recover_secret, shares};
+use std::io;
+
+// Collaborative GKR proving: several parties produce the gkr_p proof of a circuit together,
+// each one holding Shamir shares of the inputs (sharmir_secret_sharing, polynomials of degree threshold - 1),
+// so that fewer than threshold of them learn nothing about the inputs beyond what the proof reveals.
+// The parties evaluate the circuit and run the sumcheck of every layer on their shares:
+// additions and products by public values (the wiring predicates, the challenges) are local,
+// a product of two shared values is a sharing of degree 2 * (threshold - 1) that every party reshares,
+// the new shares being combined with the Lagrange coefficients at 0 of the parties (degree reduction),
+// which needs at least 2 * threshold - 1 parties.
+// Only the messages of the proof are opened: the output, the round polynomials and the evaluations of the layers,
+// everything else (the transcript, the wiring predicates) is public, and the proof is the one of gkr_p::prove.
+// Every party runs prove_party with its own shares and a Channel to each of the other parties
+// (eg: InMemoryChannel between threads, TcpChannel between machines), it never sees the shares of another party.
+// The parties are honest but curious.
+// As for distributed, only the two input gates of gkr_p reading the layer directly below are supported.
+
+// The shares of the inputs held by a party, at its x coordinate
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartyShares<F: PrimeField> {
+    pub x: F,
+    pub inputs: Vec<F>,
+}
+
+// Shares every input with sharmir_secret_sharing: any threshold of the parties can recover the inputs.
+// The party i holds the shares at x = i + 1
+pub fn share_inputs<F: PrimeField>(
+    inputs: &[F],
+    threshold: u64,
+    number_of_parties: u64,
+) -> Vec<PartyShares<F>> {
+    let input_shares: Vec<Vec<(F, F)>> = inputs
+        .iter()
+        .map(|input| shares(*input, threshold, number_of_parties))
+        .collect();
+
+    (0..number_of_parties as usize)
+        .map(|party| PartyShares {
+            x: F::from(party as u64 + 1),
+            inputs: input_shares
+                .iter()
+                .map(|input_shares| input_shares[party].1)
+                .collect(),
+        })
+        .collect()
+}
+
+/// The gkr_p proof of the circuit, as computed by one of the parties from its own shares (as given by share_inputs).
+/// channels holds a channel to every other party, in the order of the parties: every party runs prove_party
+/// at the same time, and they all get the same proof. Fails with InvalidInput when there are too few parties
+/// for the threshold, the shares are not at the x of one of the parties, there are more inputs than the circuit reads
+/// or the circuit is not supported, and when a party can't be reached or sends an invalid message
+pub fn prove_party<F: PrimeField, C: Channel>(
+    circuit: &Circuit<F>,
+    shares: &PartyShares<F>,
+    threshold: u64,
+    channels: Vec<C>,
+) -> io::Result<Proof<F>> {
+    let number_of_parties = channels.len() + 1;
+    if threshold == 0 || (number_of_parties as u64) < 2 * threshold - 1 {
+        return Err(invalid_input_error(
+            "the degree reduction needs at least 2 * threshold - 1 parties",
+        ));
+    }
+    let index = (0..number_of_parties)
+        .position(|index| shares.x == F::from(index as u64 + 1))
+        .ok_or_else(|| {
+            invalid_input_error("the party i must hold the shares of every input at x = i + 1")
+        })?;
+    if shares.inputs.len() > 1 << circuit.input_num_vars() {
+        return Err(invalid_input_error("more inputs than the circuit reads"));
+    }
+    let is_supported = circuit
+        .layers
+        .iter()
+        .enumerate()
+        .all(|(layer_index, layer)| {
+            layer.multi_input_gates.is_empty()
+                && layer.custom_gates.is_empty()
+                && layer.gates.iter().all(|gate| {
+                    gate.source_layers(layer_index) == (layer_index + 1, layer_index + 1)
+                })
+        });
+    if !is_supported {
+        return Err(invalid_input_error(
+            "the collaborative prover only supports two input gates reading the layer below",
+        ));
+    }
+
+    let mut network = Network::new(threshold, index, channels);
+    let layer_shares = evaluate_on_shares(circuit, &shares.inputs, &mut network)?;
+    let number_of_layers = circuit.layers.len();
+
+    let circuit_output = network.open(&layer_shares[0])?;
+
+    let mut transcript = Transcript::new();
+    let output_claim = output_claim(&circuit_output, &mut transcript);
+
+    let mut sumcheck_proofs = Vec::with_capacity(number_of_layers);
+    let mut wb_evaluations = Vec::with_capacity(number_of_layers);
+    let mut wc_evaluations = Vec::with_capacity(number_of_layers);
+    let mut layer_claims = vec![Vec::new(); number_of_layers + 1];
+    let mut combined_claim = CombinedClaim::from(&output_claim);
+
+    // The same steps as gkr_p::prove, with the layers below known as shares
+    for layer_index in 0..number_of_layers {
+        if layer_index > 0 {
+            if layer_claims[layer_index].is_empty() {
+                return Err(invalid_input_error(
+                    "every layer must be read by a layer above it",
+                ));
+            }
+            combined_claim = combine_layer_claims(&layer_claims[layer_index], &mut transcript);
+        }
+
+        let input_num_vars = circuit.layer_input_num_vars(layer_index);
+        let layer_below =
+            pad_to_num_vars(&layer_shares[layer_index + 1], input_num_vars).evaluated_values;
+
+        let sumcheck_proof = prove_layer_sumcheck(
+            circuit,
+            layer_index,
+            &combined_claim,
+            &layer_below,
+            &mut network,
+            &mut transcript,
+        )?;
+
+        // W(rb) and W(rc) are linear in the values of the layer below
+        let (rb, rc) = sumcheck_proof.random_challenges.split_at(input_num_vars);
+        let polynomial = MultilinearPolynomialEV::new(&layer_below);
+        let block_evaluations: Vec<Vec<F>> = network
+            .open(&[polynomial.evaluate(rb), polynomial.evaluate(rc)])?
+            .into_iter()
+            .map(|evaluation| vec![evaluation])
+            .collect();
+
+        for evaluation in block_evaluations.iter().flatten() {
+            transcript.absorb(&field_element_to_bytes(*evaluation));
+        }
+        push_layer_claims(
+            circuit,
+            &sumcheck_proof.random_challenges,
+            &[vec![layer_index + 1], vec![layer_index + 1]],
+            &block_evaluations,
+            &mut layer_claims,
+        );
+
+        sumcheck_proofs.push(sumcheck_proof);
+        wb_evaluations.push(block_evaluations[0][0]);
+        wc_evaluations.push(block_evaluations[1][0]);
+    }
+
+    Ok(Proof {
+        circuit_output,
+        claimed_sum: output_claim.value,
+        sumcheck_proofs,
+        wb_evaluations,
+        wc_evaluations,
+        source_evaluations: Vec::new(),
+        multi_input_evaluations: vec![Vec::new(); number_of_layers],
+        line_restrictions: Vec::new(),
+    })
+}
+
+// The channels of the parties running in this process, eg: on threads:
+// channels[i] are the channels of the party i to the other parties, as prove_party takes them
+pub fn in_memory_channels(number_of_parties: usize) -> Vec<Vec<InMemoryChannel>> {
+    let mut channels: Vec<Vec<InMemoryChannel>> = (0..number_of_parties)
+        .map(|_| Vec::with_capacity(number_of_parties - 1))
+        .collect();
+
+    // The pairs are created in the order of the parties, so that every party gets its channels in that order
+    for party in 0..number_of_parties {
+        for other in party + 1..number_of_parties {
+            let (party_end, other_end) = InMemoryChannel::pair();
+            channels[party].push(party_end);
+            channels[other].push(other_end);
+        }
+    }
+
+    channels
+}
+
+// What a party knows of the others: their x coordinates and a channel to every one of them.
+// The shares of a list of values held by the party are a Vec with one share per value
+struct Network<F: PrimeField, C: Channel> {
+    threshold: u64,
+    index: usize,
+    x_values: Vec<F>,
+    // The Lagrange coefficients at 0 of the x coordinates of the parties
+    recombination: Vec<F>,
+    channels: Vec<C>,
+}
+
+impl<F: PrimeField, C: Channel> Network<F, C> {
+    fn new(threshold: u64, index: usize, channels: Vec<C>) -> Self {
+        let x_values: Vec<F> = (1..=channels.len() as u64 + 1).map(F::from).collect();
+
+        let recombination = x_values
+            .iter()
+            .map(|x| {
+                let (numerator, denominator) = x_values.iter().filter(|other| *other != x).fold(
+                    (F::one(), F::one()),
+                    |(numerator, denominator), other| {
+                        (numerator * other, denominator * (*other - x))
+                    },
+                );
+
+                numerator * denominator.inverse().unwrap()
+            })
+            .collect();
+
+        Self {
+            threshold,
+            index,
+            x_values,
+            recombination,
+            channels,
+        }
+    }
+
+    // Shares of left[i] * right[i]: the party reshares the products of its shares,
+    // and combines the shares it receives from the other parties
+    fn multiply(&mut self, left: &[F], right: &[F]) -> io::Result<Vec<F>> {
+        let number_of_parties = self.x_values.len();
+        let mut reshares = vec![Vec::with_capacity(left.len()); number_of_parties];
+
+        for (left, right) in left.iter().zip(right.iter()) {
+            let product_shares = shares(*left * right, self.threshold, number_of_parties as u64);
+
+            for (reshares, (_, share)) in reshares.iter_mut().zip(product_shares) {
+                reshares.push(share);
+            }
+        }
+
+        let received = self.exchange(reshares)?;
+
+        Ok((0..left.len())
+            .map(|index| {
+                received
+                    .iter()
+                    .zip(self.recombination.iter())
+                    .map(|(shares, coefficient)| *coefficient * shares[index])
+                    .sum()
+            })
+            .collect())
+    }
+
+    // The party sends its shares to every other party, the values are recovered from the shares of all of them
+    fn open(&mut self, shares: &[F]) -> io::Result<Vec<F>> {
+        let received = self.exchange(vec![shares.to_vec(); self.x_values.len()])?;
+
+        Ok((0..shares.len())
+            .map(|index| {
+                recover_secret(
+                    self.x_values
+                        .iter()
+                        .zip(received.iter())
+                        .map(|(x, shares)| (*x, shares[index]))
+                        .collect(),
+                )
+            })
+            .collect())
+    }
+
+    // Sends messages[j] to the party j and returns what every party sent to this one (messages[index] for itself).
+    // Every pair of parties exchanges in the order of the parties, the one with the lower index sending first,
+    // so that no two parties wait for each other
+    fn exchange(&mut self, mut messages: Vec<Vec<F>>) -> io::Result<Vec<Vec<F>>> {
+        let mut received = vec![Vec::new(); messages.len()];
+        received[self.index] = std::mem::take(&mut messages[self.index]);
+
+        let others = (0..messages.len()).filter(|party| *party != self.index);
+        for (party, channel) in others.zip(self.channels.iter_mut()) {
+            if party < self.index {
+                received[party] = receive_field_elements(channel)?;
+                send_field_elements(channel, &messages[party])?;
+            } else {
+                send_field_elements(channel, &messages[party])?;
+                received[party] = receive_field_elements(channel)?;
+            }
+
+            if received[party].len() != messages[party].len() {
+                return Err(invalid_message_error());
+            }
+        }
+
+        Ok(received)
+    }
+}
+
+// The shares of the party of every layer of the circuit, indexed like the circuit evaluations:
+// the additions are local, the products of the multiplication gates of a layer are computed together
+fn evaluate_on_shares<F: PrimeField, C: Channel>(
+    circuit: &Circuit<F>,
+    inputs: &[F],
+    network: &mut Network<F, C>,
+) -> io::Result<Vec<Vec<F>>> {
+    let number_of_layers = circuit.layers.len();
+    let mut layer_shares = vec![Vec::new(); number_of_layers + 1];
+    layer_shares[number_of_layers] = inputs.to_vec();
+
+    for layer_index in (0..number_of_layers).rev() {
+        let layer = &circuit.layers[layer_index];
+        let layer_below = &layer_shares[layer_index + 1];
+
+        let multiplications: Vec<&Gate> = layer
+            .gates
+            .iter()
+            .filter(|gate| gate.operation == Operation::MUL)
+            .collect();
+        let inputs = |input: fn(&Gate) -> usize| -> Vec<F> {
+            multiplications
+                .iter()
+                .map(|gate| layer_below[input(gate)])
+                .collect()
+        };
+        let products = network.multiply(&inputs(|gate| gate.left), &inputs(|gate| gate.right))?;
+
+        let mut outputs = vec![F::zero(); layer.max_output_index() + 1];
+        for gate in layer.gates.iter() {
+            if gate.operation == Operation::ADD {
+                outputs[gate.output] += layer_below[gate.left] + layer_below[gate.right];
+            }
+        }
+        for (gate, product) in multiplications.iter().zip(products.iter()) {
+            outputs[gate.output] += product;
+        }
+
+        layer_shares[layer_index] = outputs;
+    }
+
+    Ok(layer_shares)
+}
+
+// The sumcheck of gkr_p on add_i(b, c) * (W(b) + W(c)) + mul_i(b, c) * W(b) * W(c):
+// add_i and mul_i are public, W(b) + W(c) is local and W(b) * W(c) takes a multiplication for every (b, c).
+// The party then computes its shares of the round polynomials, which are opened
+fn prove_layer_sumcheck<F: PrimeField, C: Channel>(
+    circuit: &Circuit<F>,
+    layer_index: usize,
+    combined_claim: &CombinedClaim<F>,
+    layer_below: &[F],
+    network: &mut Network<F, C>,
+    transcript: &mut Transcript,
+) -> io::Result<SumcheckProverProof<F>> {
+    let wiring = circuit.wiring_predicates(layer_index);
+    let (add_i_bc, mul_i_bc) = compute_combined_add_i_mul_i(
+        &wiring[0].add_i,
+        &wiring[0].mul_i,
+        &combined_claim.weighted_points,
+    );
+
+    let layer_size = layer_below.len();
+    let (w_b, w_c): (Vec<F>, Vec<F>) = (0..layer_size * layer_size)
+        .map(|position| {
+            (
+                layer_below[position / layer_size],
+                layer_below[position % layer_size],
+            )
+        })
+        .unzip();
+    let add_w = w_b.iter().zip(w_c.iter()).map(|(b, c)| *b + c).collect();
+    let mul_w = network.multiply(&w_b, &w_c)?;
+
+    // The tables of the party: [add_i, W(b) + W(c)] and [mul_i, W(b) * W(c)]
+    let mut products = vec![
+        vec![add_i_bc.evaluated_values.clone(), add_w],
+        vec![mul_i_bc.evaluated_values.clone(), mul_w],
+    ];
+
+    let number_of_variables = add_i_bc.number_of_variables() as usize;
+    let mut round_univariate_polynomials = Vec::with_capacity(number_of_variables);
+    let mut random_challenges = Vec::with_capacity(number_of_variables);
+
+    transcript.absorb(&field_element_to_bytes(combined_claim.claimed_sum));
+
+    for _round in 0..number_of_variables {
+        // Every product has a public factor, so the shares of the round evaluations are shares of the same degree
+        let evaluations = network.open(&round_evaluations(&products))?;
+        let challenge = absorb_round(&evaluations, transcript, &mut round_univariate_polynomials);
+
+        fold_products(&mut products, challenge);
+        random_challenges.push(challenge);
+    }
+
+    Ok(SumcheckProverProof {
+        claimed_sum: combined_claim.claimed_sum,
+        round_univariate_polynomials,
+        random_challenges,
+    })
+}
+
+fn invalid_input_error(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
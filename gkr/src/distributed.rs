@@ -0,0 +1,1074 @@
+use crate::circuits::circuit::Circuit;
+use crate::circuits::gate::Operation;
+use crate::evaluation::MultilinearPolynomialEV;
+use crate::gkr_p::{
+    combine_layer_claims, output_claim, push_layer_claims, Claim, CombinedClaim, Proof,
+};
+use crate::gkr_sumcheck_dependencies::transcript::Transcript;
+use crate::interactive::{
+    invalid_message_error, receive_field_elements, send_field_elements, Channel, TcpChannel,
+};
+use crate::layer_rounds::{absorb_round, fold_products, round_evaluations};
+use crate::sum_check::{field_element_to_bytes, SumcheckProverProof};
+use ark_ff::{BigInteger, PrimeField};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::marker::PhantomData;
+use std::net::{TcpListener, ToSocketAddrs};
+use std::ops::Range;
+
+// GKR prover split between a coordinator and 2^k workers, for circuits whose layers and sumcheck tables don't fit in one machine.
+// Every layer is split by index prefix: worker w holds the values of every layer whose index starts with the k bits of w.
+// A worker only ever holds its own slices: the values of the layer below it reads and doesn't hold are routed to it
+// by the coordinator, which fetches them from the workers holding them.
+// The workers evaluate the circuit from the bottom up: every worker gets the values of the layer below
+// read by the gates whose outputs are in its slice, then evaluates these gates.
+// The sumcheck of a layer runs over the tables add_i(b, c), W(b) + W(c), mul_i(b, c) and W(b) * W(c), 2^(2n) values each
+// for a layer below of 2^n values: worker w holds the part of every table whose index starts with the k bits of w,
+// built from the gates in the part, the claims on the layer and the values W(b), W(c) of the layer below
+// for the b and c of the part.
+// The sumcheck binds the first variable (the most significant bit) first, which is a bit of the prefix:
+// in every round, the workers whose prefixes only differ in the first bit swap half of their parts,
+// so that each one holds both ends of the pairs it sums over, and compute their part of the round polynomial.
+// The coordinator adds them up, absorbs the round polynomial and sends the challenge back, then the workers fold
+// their pairs: the new part of a worker starts with the rest of its prefix followed by the half it kept.
+// Once every part is down to a single value, the coordinator gathers them and proves the last k rounds itself,
+// then every worker evaluates its slice of the layer below at rb and rc, and the coordinator adds them up.
+// The coordinator drives the transcript exactly as gkr_p::prove does, so the proof is the same.
+// It only holds the circuit, the output and the messages of a round: the routed values and the halves
+// the workers swap are forwarded in chunks of at most CHUNK_SIZE values, as soon as they arrive.
+// Only the two input gates of gkr_p reading the layer directly below are supported (as in gkr_data_parallel).
+// The messages are lists of field elements, starting with the command.
+
+const INPUTS: usize = 0;
+const FETCH: usize = 1;
+const WIRES: usize = 2;
+const EVALUATE: usize = 3;
+const SLICE: usize = 4;
+const LOAD: usize = 5;
+const EXCHANGE: usize = 6;
+const CHALLENGE: usize = 7;
+const GATHER: usize = 8;
+const OPEN: usize = 9;
+const SHUTDOWN: usize = 10;
+
+// The tables of a layer are two products of two polynomials
+const DEGREE: usize = 2;
+const NUMBER_OF_TABLES: usize = 4;
+
+// The number of values between the command and the claims in a LOAD message
+const LOAD_HEADER_SIZE: usize = 6;
+
+// The largest number of values forwarded by the coordinator in one message
+const CHUNK_SIZE: usize = 1 << 10;
+
+pub struct Coordinator<F: PrimeField, C: Channel> {
+    workers: Vec<C>,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: PrimeField, C: Channel> Coordinator<F, C> {
+    pub fn new(workers: Vec<C>) -> Self {
+        assert!(
+            workers.len() >= 2 && workers.len().is_power_of_two(),
+            "the number of workers must be a power of two, at least 2"
+        );
+
+        Self {
+            workers,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn number_of_workers(&self) -> usize {
+        self.workers.len()
+    }
+
+    // The same proof as gkr_p::prove, with the circuit evaluated and the sumcheck of every layer run by the workers.
+    // Fails with InvalidInput on a circuit it doesn't support or more inputs than the circuit reads,
+    // and when a worker can't be reached or sends an invalid message
+    pub fn prove(&mut self, circuit: &mut Circuit<F>, inputs: &[F]) -> io::Result<Proof<F>> {
+        if inputs.len() > 1 << circuit.input_num_vars() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "more inputs than the circuit reads",
+            ));
+        }
+        let is_supported = circuit
+            .layers
+            .iter()
+            .enumerate()
+            .all(|(layer_index, layer)| {
+                layer.multi_input_gates.is_empty()
+                    && layer.custom_gates.is_empty()
+                    && layer.gates.iter().all(|gate| {
+                        gate.source_layers(layer_index) == (layer_index + 1, layer_index + 1)
+                    })
+            });
+        if !is_supported {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the distributed prover only supports two input gates reading the layer below",
+            ));
+        }
+
+        let number_of_layers = circuit.layers.len();
+        self.evaluate(circuit, inputs)?;
+
+        let mut circuit_output = self.collect_layer(0, circuit.layer_num_vars(0))?;
+        circuit_output.truncate(circuit.layers[0].max_output_index() + 1);
+
+        let mut transcript = Transcript::new();
+        let output_claim = output_claim(&circuit_output, &mut transcript);
+        let mut combined_claim = CombinedClaim::from(&output_claim);
+
+        // layer_claims[j] collects the claims W(rb) = wb and W(rc) = wc made on the layer j by the layer above it
+        let mut layer_claims: Vec<Vec<Claim<F>>> = vec![Vec::new(); number_of_layers + 1];
+        let mut sumcheck_proofs = Vec::with_capacity(number_of_layers);
+        let mut wb_evaluations = Vec::with_capacity(number_of_layers);
+        let mut wc_evaluations = Vec::with_capacity(number_of_layers);
+
+        for layer_index in 0..number_of_layers {
+            if layer_index > 0 {
+                combined_claim = combine_layer_claims(&layer_claims[layer_index], &mut transcript);
+            }
+
+            let sumcheck_proof =
+                self.prove_layer(circuit, layer_index, &combined_claim, &mut transcript)?;
+
+            let (rb, rc) = sumcheck_proof
+                .random_challenges
+                .split_at(circuit.layer_input_num_vars(layer_index));
+            let evaluations = self.open(layer_index + 1, &[rb, rc])?;
+            for evaluation in evaluations.iter() {
+                transcript.absorb(&field_element_to_bytes(*evaluation));
+            }
+
+            let (wb, wc) = (evaluations[0], evaluations[1]);
+            push_layer_claims(
+                circuit,
+                &sumcheck_proof.random_challenges,
+                &[vec![layer_index + 1], vec![layer_index + 1]],
+                &[vec![wb], vec![wc]],
+                &mut layer_claims,
+            );
+
+            sumcheck_proofs.push(sumcheck_proof);
+            wb_evaluations.push(wb);
+            wc_evaluations.push(wc);
+        }
+
+        Ok(Proof {
+            circuit_output,
+            claimed_sum: output_claim.value,
+            sumcheck_proofs,
+            wb_evaluations,
+            wc_evaluations,
+            source_evaluations: Vec::new(),
+            multi_input_evaluations: vec![Vec::new(); number_of_layers],
+            line_restrictions: Vec::new(),
+        })
+    }
+
+    // Stops the workers: run_worker returns Ok on their side
+    pub fn shutdown(mut self) -> io::Result<()> {
+        for worker in self.workers.iter_mut() {
+            send_field_elements(worker, &[command::<F>(SHUTDOWN)])?;
+        }
+
+        Ok(())
+    }
+
+    // Sends every worker its slice of the inputs, then has the workers evaluate the layers from the bottom up:
+    // every worker gets the gates whose outputs are in its slice of the layer and the values they read
+    fn evaluate(&mut self, circuit: &Circuit<F>, inputs: &[F]) -> io::Result<()> {
+        let number_of_layers = circuit.layers.len();
+        let number_of_workers = self.workers.len();
+        let input_num_vars = circuit.input_num_vars();
+
+        for (worker_index, worker) in self.workers.iter_mut().enumerate() {
+            let header = [
+                INPUTS,
+                worker_index,
+                number_of_workers,
+                number_of_layers,
+                input_num_vars,
+            ];
+
+            let mut message: Vec<F> = header.into_iter().map(command).collect();
+            message.extend(
+                slice_range(worker_index, number_of_workers, input_num_vars)
+                    .map(|index| inputs.get(index).copied().unwrap_or(F::zero())),
+            );
+
+            send_field_elements(worker, &message)?;
+        }
+
+        for layer_index in (0..number_of_layers).rev() {
+            let num_vars = circuit.layer_num_vars(layer_index);
+            let gates = &circuit.layers[layer_index].gates;
+
+            let reads = (0..number_of_workers)
+                .map(|worker_index| {
+                    let slice = slice_range(worker_index, number_of_workers, num_vars);
+
+                    gates
+                        .iter()
+                        .filter(|gate| slice.contains(&gate.output))
+                        .flat_map(|gate| [gate.left, gate.right])
+                        .collect()
+                })
+                .collect();
+            self.route(
+                layer_index + 1,
+                circuit.layer_num_vars(layer_index + 1),
+                reads,
+            )?;
+
+            for (worker_index, worker) in self.workers.iter_mut().enumerate() {
+                let slice = slice_range(worker_index, number_of_workers, num_vars);
+
+                let mut message: Vec<F> = [EVALUATE, layer_index, num_vars]
+                    .into_iter()
+                    .map(command)
+                    .collect();
+                for gate in gates.iter() {
+                    if slice.contains(&gate.output) {
+                        let is_mul = gate.operation == Operation::MUL;
+                        message.extend(
+                            [gate.left, gate.right, gate.output, is_mul as usize].map(command::<F>),
+                        );
+                    }
+                }
+
+                send_field_elements(worker, &message)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn prove_layer(
+        &mut self,
+        circuit: &Circuit<F>,
+        layer_index: usize,
+        combined_claim: &CombinedClaim<F>,
+        transcript: &mut Transcript,
+    ) -> io::Result<SumcheckProverProof<F>> {
+        let input_num_vars = circuit.layer_input_num_vars(layer_index);
+        let number_of_variables = 2 * input_num_vars;
+        let prefix_num_vars = self.workers.len().ilog2() as usize;
+        let wires = layer_wires(circuit, layer_index);
+
+        transcript.absorb(&field_element_to_bytes(combined_claim.claimed_sum));
+
+        let mut round_univariate_polynomials = Vec::with_capacity(number_of_variables);
+        let mut random_challenges = Vec::with_capacity(number_of_variables);
+
+        let mut products = if number_of_variables < prefix_num_vars {
+            // A layer with fewer (b, c) pairs than workers is proven by the coordinator
+            let layer_below = self.collect_layer(layer_index + 1, input_num_vars)?;
+            let weighted_wires = wires.iter().map(|(left, right, output, is_mul)| {
+                let weight = combined_claim
+                    .weighted_points
+                    .iter()
+                    .map(|(weight, point)| *weight * eq_at_index(point, *output))
+                    .sum();
+
+                ((left << input_num_vars) | right, weight, *is_mul)
+            });
+
+            part_products(
+                weighted_wires,
+                input_num_vars,
+                0..1 << number_of_variables,
+                |index| Ok(layer_below[index]),
+            )?
+        } else {
+            let part_size = (1 << number_of_variables) / self.workers.len();
+            let reads = (0..self.workers.len())
+                .map(|worker_index| {
+                    part_reads(worker_index * part_size, part_size, input_num_vars).collect()
+                })
+                .collect();
+            self.route(layer_index + 1, input_num_vars, reads)?;
+            self.load_layer(
+                layer_index + 1,
+                &wires,
+                input_num_vars,
+                part_size,
+                combined_claim,
+            )?;
+
+            // prefixes[w] is the prefix of the indices of the part of the tables held by the worker w
+            let mut prefixes: Vec<usize> = (0..self.workers.len()).collect();
+            let high_bit = self.workers.len() / 2;
+            let mut part_size = part_size;
+
+            for _round in prefix_num_vars..number_of_variables {
+                let evaluations = self.exchange_round(&prefixes, part_size)?;
+                let challenge =
+                    absorb_round(&evaluations, transcript, &mut round_univariate_polynomials);
+
+                for worker in self.workers.iter_mut() {
+                    send_field_elements(worker, &[command(CHALLENGE), challenge])?;
+                }
+                random_challenges.push(challenge);
+
+                for prefix in prefixes.iter_mut() {
+                    *prefix = ((*prefix << 1) & (self.workers.len() - 1)) | (*prefix / high_bit);
+                }
+                part_size /= 2;
+            }
+
+            self.gather(&prefixes)?
+        };
+
+        for _round in random_challenges.len()..number_of_variables {
+            let evaluations = round_evaluations(&products);
+            let challenge =
+                absorb_round(&evaluations, transcript, &mut round_univariate_polynomials);
+
+            fold_products(&mut products, challenge);
+            random_challenges.push(challenge);
+        }
+
+        Ok(SumcheckProverProof {
+            claimed_sum: combined_claim.claimed_sum,
+            round_univariate_polynomials,
+            random_challenges,
+        })
+    }
+
+    // Sends every worker the values of a layer at the indices it reads (reads[w] for the worker w) and doesn't hold:
+    // the coordinator fetches them from the workers holding them and forwards them as (index, value) pairs,
+    // at most CHUNK_SIZE / 2 pairs per message
+    fn route(
+        &mut self,
+        layer: usize,
+        num_vars: usize,
+        reads: Vec<BTreeSet<usize>>,
+    ) -> io::Result<()> {
+        let number_of_workers = self.workers.len();
+
+        for (worker_index, indices) in reads.into_iter().enumerate() {
+            let slice = slice_range(worker_index, number_of_workers, num_vars);
+
+            let mut indices_by_holder = vec![Vec::new(); number_of_workers];
+            for index in indices.into_iter().filter(|index| !slice.contains(index)) {
+                indices_by_holder[slice_holder(index, number_of_workers, num_vars)].push(index);
+            }
+
+            for (holder, indices) in indices_by_holder.iter().enumerate() {
+                for chunk in indices.chunks(CHUNK_SIZE / 2) {
+                    let request: Vec<F> = [FETCH, layer]
+                        .into_iter()
+                        .chain(chunk.iter().copied())
+                        .map(command)
+                        .collect();
+                    send_field_elements(&mut self.workers[holder], &request)?;
+
+                    let values: Vec<F> = receive_field_elements(&mut self.workers[holder])?;
+                    if values.len() != chunk.len() {
+                        return Err(invalid_message_error());
+                    }
+
+                    let mut message = vec![command(WIRES), command(layer)];
+                    for (index, value) in chunk.iter().zip(values) {
+                        message.extend([command(*index), value]);
+                    }
+                    send_field_elements(&mut self.workers[worker_index], &message)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Collects the slices of a layer, for the output and the layers too small to be split
+    fn collect_layer(&mut self, layer: usize, num_vars: usize) -> io::Result<Vec<F>> {
+        let number_of_workers = self.workers.len();
+        let mut values = Vec::with_capacity(1 << num_vars);
+
+        for (worker_index, worker) in self.workers.iter_mut().enumerate() {
+            send_field_elements(worker, &[command::<F>(SLICE), command(layer)])?;
+
+            let slice: Vec<F> = receive_field_elements(worker)?;
+            if slice.len() != slice_range(worker_index, number_of_workers, num_vars).len() {
+                return Err(invalid_message_error());
+            }
+            values.extend(slice);
+        }
+
+        Ok(values)
+    }
+
+    // Sends every worker the claims on the layer and the gates (b, c, a) in its part of the tables:
+    // it builds add_i(b, c) = sum over the addition gates (a, b, c) of weight_k * eq(point_k, a), the same for mul_i,
+    // and W(b) + W(c), W(b) * W(c) from the values of the layer below it holds or was routed
+    fn load_layer(
+        &mut self,
+        layer_below: usize,
+        wires: &BTreeSet<(usize, usize, usize, bool)>,
+        input_num_vars: usize,
+        part_size: usize,
+        combined_claim: &CombinedClaim<F>,
+    ) -> io::Result<()> {
+        let output_num_vars = combined_claim.weighted_points[0].1.len();
+
+        // parts[w] holds the (position, output, is_mul) of the gates in the part of the worker w
+        let mut parts: Vec<Vec<F>> = vec![Vec::new(); self.workers.len()];
+        for (left, right, output, is_mul) in wires.iter() {
+            let position = (left << input_num_vars) | right;
+            parts[position / part_size]
+                .extend([position, *output, *is_mul as usize].map(command::<F>));
+        }
+
+        for (worker_index, (worker, part)) in self.workers.iter_mut().zip(parts).enumerate() {
+            let header: [usize; LOAD_HEADER_SIZE] = [
+                layer_below,
+                worker_index * part_size,
+                part_size,
+                output_num_vars,
+                combined_claim.weighted_points.len(),
+                part.len() / 3,
+            ];
+
+            let mut message: Vec<F> = [LOAD].into_iter().chain(header).map(command).collect();
+            for (weight, point) in combined_claim.weighted_points.iter() {
+                message.push(*weight);
+                message.extend_from_slice(point);
+            }
+            message.extend(part);
+
+            send_field_elements(worker, &message)?;
+        }
+
+        Ok(())
+    }
+
+    // A round where the variable bound is a bit of the prefixes: every worker swaps the half of its part
+    // that its partner (the prefix with the other first bit) needs for the other one, chunk by chunk,
+    // and returns its part of the round polynomial
+    fn exchange_round(&mut self, prefixes: &[usize], part_size: usize) -> io::Result<Vec<F>> {
+        let high_bit = self.workers.len() / 2;
+
+        for (worker, prefix) in self.workers.iter_mut().zip(prefixes.iter()) {
+            let is_high = prefix & high_bit != 0;
+            send_field_elements::<F, C>(worker, &[command(EXCHANGE), command(is_high as usize)])?;
+        }
+
+        let mut worker_with_prefix = vec![0; self.workers.len()];
+        for (worker_index, prefix) in prefixes.iter().enumerate() {
+            worker_with_prefix[*prefix] = worker_index;
+        }
+
+        for low_prefix in 0..high_bit {
+            let low = worker_with_prefix[low_prefix];
+            let high = worker_with_prefix[low_prefix | high_bit];
+
+            for chunk_size in chunk_sizes(NUMBER_OF_TABLES * part_size / 2) {
+                let low_chunk: Vec<F> = receive_field_elements(&mut self.workers[low])?;
+                let high_chunk: Vec<F> = receive_field_elements(&mut self.workers[high])?;
+                if low_chunk.len() != chunk_size || high_chunk.len() != chunk_size {
+                    return Err(invalid_message_error());
+                }
+
+                send_field_elements(&mut self.workers[low], &high_chunk)?;
+                send_field_elements(&mut self.workers[high], &low_chunk)?;
+            }
+        }
+
+        let mut evaluations = vec![F::zero(); DEGREE + 1];
+        for worker in self.workers.iter_mut() {
+            let worker_evaluations: Vec<F> = receive_field_elements(worker)?;
+            if worker_evaluations.len() != evaluations.len() {
+                return Err(invalid_message_error());
+            }
+
+            for (evaluation, worker_evaluation) in
+                evaluations.iter_mut().zip(worker_evaluations.iter())
+            {
+                *evaluation += worker_evaluation;
+            }
+        }
+
+        Ok(evaluations)
+    }
+
+    // Collects the single value left in every table of every worker, in the order of the prefixes
+    fn gather(&mut self, prefixes: &[usize]) -> io::Result<Vec<Vec<Vec<F>>>> {
+        let mut tables = vec![vec![F::zero(); self.workers.len()]; NUMBER_OF_TABLES];
+
+        for (worker, prefix) in self.workers.iter_mut().zip(prefixes.iter()) {
+            send_field_elements(worker, &[command::<F>(GATHER)])?;
+
+            let values: Vec<F> = receive_field_elements(worker)?;
+            if values.len() != NUMBER_OF_TABLES {
+                return Err(invalid_message_error());
+            }
+
+            for (table, value) in tables.iter_mut().zip(values) {
+                table[*prefix] = value;
+            }
+        }
+
+        Ok(into_products(tables))
+    }
+
+    // The evaluations of a layer at the points: the sum of the evaluations of the slices held by the workers
+    fn open(&mut self, layer: usize, points: &[&[F]]) -> io::Result<Vec<F>> {
+        let mut message: Vec<F> = [OPEN, layer, points.len()]
+            .into_iter()
+            .map(command)
+            .collect();
+        for point in points.iter() {
+            message.extend_from_slice(point);
+        }
+
+        let mut evaluations = vec![F::zero(); points.len()];
+        for worker in self.workers.iter_mut() {
+            send_field_elements(worker, &message)?;
+
+            let worker_evaluations: Vec<F> = receive_field_elements(worker)?;
+            if worker_evaluations.len() != evaluations.len() {
+                return Err(invalid_message_error());
+            }
+
+            for (evaluation, worker_evaluation) in
+                evaluations.iter_mut().zip(worker_evaluations.iter())
+            {
+                *evaluation += worker_evaluation;
+            }
+        }
+
+        Ok(evaluations)
+    }
+}
+
+impl<F: PrimeField> Coordinator<F, TcpChannel> {
+    // Waits for the workers to connect, the slices and parts of a worker depend on the order of the connections
+    pub fn accept(listener: &TcpListener, number_of_workers: usize) -> io::Result<Self> {
+        let workers = (0..number_of_workers)
+            .map(|_| TcpChannel::accept(listener))
+            .collect::<io::Result<_>>()?;
+
+        Ok(Self::new(workers))
+    }
+}
+
+// A worker in its own process: connects to the coordinator at address and answers it until it is shut down,
+// eg: the gkr_worker binary
+pub fn connect_worker<F: PrimeField, A: ToSocketAddrs>(address: A) -> io::Result<()> {
+    let mut channel = TcpChannel::connect(address)?;
+
+    run_worker::<F, _>(&mut channel)
+}
+
+// The worker side: answers the coordinator until it shuts the worker down
+pub fn run_worker<F: PrimeField, C: Channel>(channel: &mut C) -> io::Result<()> {
+    let mut worker = Worker::default();
+
+    loop {
+        let message: Vec<F> = receive_field_elements(channel)?;
+        let (command, arguments) = message.split_first().ok_or_else(invalid_message_error)?;
+
+        match to_usize(*command)? {
+            INPUTS => worker.load_inputs(arguments)?,
+            FETCH => {
+                let values = worker.fetch(arguments)?;
+                send_field_elements(channel, &values)?;
+            }
+            WIRES => worker.receive_wires(arguments)?,
+            EVALUATE => worker.evaluate(arguments)?,
+            SLICE => {
+                let slice = worker.slice(arguments)?;
+                send_field_elements(channel, &slice.values)?;
+            }
+            LOAD => worker.load_tables(arguments)?,
+            EXCHANGE => worker.exchange(channel, arguments)?,
+            CHALLENGE => {
+                if arguments.len() != 1
+                    || worker.products.is_empty()
+                    || worker.products[0][0].len() < 2
+                {
+                    return Err(invalid_message_error());
+                }
+
+                fold_products(&mut worker.products, arguments[0]);
+            }
+            GATHER => {
+                if worker.products.is_empty() || worker.products[0][0].len() != 1 {
+                    return Err(invalid_message_error());
+                }
+
+                let values: Vec<F> = worker
+                    .products
+                    .iter()
+                    .flatten()
+                    .map(|table| table[0])
+                    .collect();
+                send_field_elements(channel, &values)?;
+            }
+            OPEN => {
+                let evaluations = worker.open(arguments)?;
+                send_field_elements(channel, &evaluations)?;
+            }
+            SHUTDOWN => return Ok(()),
+            _ => return Err(invalid_message_error()),
+        }
+    }
+}
+
+// The values of a layer held by a worker: the indices of slice_range(index, number_of_workers, num_vars)
+struct LayerSlice<F: PrimeField> {
+    num_vars: usize,
+    values: Vec<F>,
+}
+
+#[derive(Default)]
+struct Worker<F: PrimeField> {
+    index: usize,
+    number_of_workers: usize,
+    // The slices of the layers evaluated so far, by layer index
+    slices: BTreeMap<usize, LayerSlice<F>>,
+    // The values routed to the worker for its next EVALUATE or LOAD, by (layer, index)
+    wires: BTreeMap<(usize, usize), F>,
+    // products[i][j] is the part held by the worker of the j-th table of the i-th product
+    products: Vec<Vec<Vec<F>>>,
+}
+
+impl<F: PrimeField> Worker<F> {
+    // A new proof: the index of the worker, the number of workers, the index and number of variables
+    // of the input layer, then the slice of the inputs
+    fn load_inputs(&mut self, arguments: &[F]) -> io::Result<()> {
+        let header = to_usizes(arguments, 4)?;
+        let [index, number_of_workers, input_layer, input_num_vars] = header[..] else {
+            return Err(invalid_message_error());
+        };
+        if number_of_workers < 2
+            || !number_of_workers.is_power_of_two()
+            || index >= number_of_workers
+        {
+            return Err(invalid_message_error());
+        }
+
+        *self = Self {
+            index,
+            number_of_workers,
+            ..Self::default()
+        };
+        self.insert_slice(input_layer, input_num_vars, arguments[4..].to_vec())
+    }
+
+    // The values of the slice of a layer at indices: the layer index, then the indices
+    fn fetch(&self, arguments: &[F]) -> io::Result<Vec<F>> {
+        let (layer, indices) = arguments.split_first().ok_or_else(invalid_message_error)?;
+        let slice = self.slice(&[*layer])?;
+        let range = self.slice_range(slice.num_vars)?;
+
+        indices
+            .iter()
+            .map(|index| {
+                let index = to_usize(*index)?;
+                if !range.contains(&index) {
+                    return Err(invalid_message_error());
+                }
+
+                Ok(slice.values[index - range.start])
+            })
+            .collect()
+    }
+
+    // Keeps the values of a layer routed to the worker: the layer index, then (index, value) pairs
+    fn receive_wires(&mut self, arguments: &[F]) -> io::Result<()> {
+        let (layer, pairs) = arguments.split_first().ok_or_else(invalid_message_error)?;
+        if !pairs.len().is_multiple_of(2) {
+            return Err(invalid_message_error());
+        }
+        let layer = to_usize(*layer)?;
+
+        for pair in pairs.chunks(2) {
+            self.wires.insert((layer, to_usize(pair[0])?), pair[1]);
+        }
+
+        Ok(())
+    }
+
+    // The value of a layer at index, from the slice of the worker or the values routed to it
+    fn wire(&self, layer: usize, index: usize) -> io::Result<F> {
+        let slice = self.slices.get(&layer).ok_or_else(invalid_message_error)?;
+        let range = self.slice_range(slice.num_vars)?;
+
+        if range.contains(&index) {
+            Ok(slice.values[index - range.start])
+        } else {
+            self.wires
+                .get(&(layer, index))
+                .copied()
+                .ok_or_else(invalid_message_error)
+        }
+    }
+
+    // Evaluates the slice of a layer from the layer below: the index and number of variables of the layer,
+    // then (left, right, output, is_mul) for the gates whose outputs are in the slice
+    fn evaluate(&mut self, arguments: &[F]) -> io::Result<()> {
+        if arguments.len() < 2 || !(arguments.len() - 2).is_multiple_of(4) {
+            return Err(invalid_message_error());
+        }
+        let (layer, num_vars) = (to_usize(arguments[0])?, to_usize(arguments[1])?);
+        let slice = self.slice_range(num_vars)?;
+
+        let mut values = vec![F::zero(); slice.len()];
+        for gate in arguments[2..].chunks(4) {
+            let [left, right, output, is_mul] = to_usizes(gate, 4)?[..] else {
+                return Err(invalid_message_error());
+            };
+            if !slice.contains(&output) || is_mul > 1 {
+                return Err(invalid_message_error());
+            }
+            let (left, right) = (self.wire(layer + 1, left)?, self.wire(layer + 1, right)?);
+
+            values[output - slice.start] += if is_mul == 1 {
+                left * right
+            } else {
+                left + right
+            };
+        }
+
+        self.wires.clear();
+        self.insert_slice(layer, num_vars, values)
+    }
+
+    // Builds the part of the tables of the worker from a LOAD message: the header, then (weight, point) for every claim
+    // on the layer, then (position, output, is_mul) for the gates in the part
+    fn load_tables(&mut self, arguments: &[F]) -> io::Result<()> {
+        let header = to_usizes(arguments, LOAD_HEADER_SIZE)?;
+        let [layer_below, offset, part_size, output_num_vars, number_of_points, number_of_gates] =
+            header[..]
+        else {
+            return Err(invalid_message_error());
+        };
+
+        let input_num_vars = self
+            .slices
+            .get(&layer_below)
+            .ok_or_else(invalid_message_error)?
+            .num_vars;
+        let layer_size = 1 << input_num_vars;
+        if input_num_vars == 0
+            || output_num_vars >= usize::BITS as usize / 2
+            || !part_size.is_power_of_two()
+            || !offset.is_multiple_of(part_size)
+            || offset
+                .checked_add(part_size)
+                .is_none_or(|end| end > layer_size * layer_size)
+        {
+            return Err(invalid_message_error());
+        }
+
+        let values = &arguments[LOAD_HEADER_SIZE..];
+        let message_size = number_of_points
+            .checked_mul(output_num_vars + 1)
+            .zip(number_of_gates.checked_mul(3))
+            .and_then(|(claims_size, gates_size)| claims_size.checked_add(gates_size));
+        if message_size != Some(values.len()) {
+            return Err(invalid_message_error());
+        }
+        let (claims, gates) = values.split_at(number_of_points * (output_num_vars + 1));
+
+        let mut output_weights = vec![F::zero(); 1 << output_num_vars];
+        for claim in claims.chunks(output_num_vars + 1) {
+            let eq_point = MultilinearPolynomialEV::eq_polynomial(&claim[1..]);
+
+            for (output_weight, eq_value) in output_weights
+                .iter_mut()
+                .zip(eq_point.evaluated_values.iter())
+            {
+                *output_weight += claim[0] * eq_value;
+            }
+        }
+
+        let weighted_wires = gates
+            .chunks(3)
+            .map(|gate| {
+                let [position, output, is_mul] = to_usizes(gate, 3)?[..] else {
+                    return Err(invalid_message_error());
+                };
+                if !(offset..offset + part_size).contains(&position) || is_mul > 1 {
+                    return Err(invalid_message_error());
+                }
+
+                let weight = output_weights
+                    .get(output)
+                    .ok_or_else(invalid_message_error)?;
+                Ok((position, *weight, is_mul == 1))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        self.products = part_products(
+            weighted_wires,
+            input_num_vars,
+            offset..offset + part_size,
+            |index| self.wire(layer_below, index),
+        )?;
+        self.wires.clear();
+        Ok(())
+    }
+
+    // Sends the half of the part its partner needs chunk by chunk, receiving the partner's half in between,
+    // then sends its part of the round polynomial
+    fn exchange<C: Channel>(&mut self, channel: &mut C, arguments: &[F]) -> io::Result<()> {
+        let part_size = self.products.first().map_or(0, |factors| factors[0].len());
+        if part_size < 2 || arguments.len() != 1 {
+            return Err(invalid_message_error());
+        }
+        let is_high = to_usize(arguments[0])? == 1;
+
+        // The worker keeps the half of the pairs that ends up in its next part
+        let mut sent_half = Vec::with_capacity(NUMBER_OF_TABLES * part_size / 2);
+        let mut kept = Vec::with_capacity(NUMBER_OF_TABLES);
+        for table in self.products.iter().flatten() {
+            let (low, high) = table.split_at(part_size / 2);
+            let (kept_half, other_half) = if is_high { (high, low) } else { (low, high) };
+
+            sent_half.extend_from_slice(other_half);
+            kept.push(kept_half.to_vec());
+        }
+
+        let mut received = Vec::with_capacity(sent_half.len());
+        for chunk in sent_half.chunks(CHUNK_SIZE) {
+            send_field_elements(channel, chunk)?;
+
+            let partner_chunk: Vec<F> = receive_field_elements(channel)?;
+            if partner_chunk.len() != chunk.len() {
+                return Err(invalid_message_error());
+            }
+            received.extend(partner_chunk);
+        }
+
+        // The pairs are (low, high): the worker with the prefix starting with 0 holds the low ends
+        let tables = kept
+            .into_iter()
+            .zip(received.chunks(part_size / 2))
+            .map(|(kept, received)| {
+                if is_high {
+                    [received, &kept[..]].concat()
+                } else {
+                    [&kept[..], received].concat()
+                }
+            })
+            .collect();
+        self.products = into_products(tables);
+
+        send_field_elements(channel, &round_evaluations(&self.products))
+    }
+
+    // The evaluations of the slice of a layer at points: the layer index, the number of points, then the points
+    fn open(&self, arguments: &[F]) -> io::Result<Vec<F>> {
+        if arguments.len() < 2 {
+            return Err(invalid_message_error());
+        }
+        let slice = self.slice(&arguments[..1])?;
+        let number_of_points = to_usize(arguments[1])?;
+        let points = &arguments[2..];
+        if slice.num_vars == 0 || Some(points.len()) != number_of_points.checked_mul(slice.num_vars)
+        {
+            return Err(invalid_message_error());
+        }
+
+        let start = self.slice_range(slice.num_vars)?.start;
+        Ok(points
+            .chunks(slice.num_vars)
+            .map(|point| slice_evaluation(&slice.values, start, point))
+            .collect())
+    }
+
+    fn slice(&self, arguments: &[F]) -> io::Result<&LayerSlice<F>> {
+        let [layer] = arguments else {
+            return Err(invalid_message_error());
+        };
+
+        self.slices
+            .get(&to_usize(*layer)?)
+            .ok_or_else(invalid_message_error)
+    }
+
+    fn slice_range(&self, num_vars: usize) -> io::Result<Range<usize>> {
+        if self.number_of_workers == 0 || num_vars >= usize::BITS as usize / 2 {
+            return Err(invalid_message_error());
+        }
+
+        Ok(slice_range(self.index, self.number_of_workers, num_vars))
+    }
+
+    fn insert_slice(&mut self, layer: usize, num_vars: usize, values: Vec<F>) -> io::Result<()> {
+        if values.len() != self.slice_range(num_vars)?.len() {
+            return Err(invalid_message_error());
+        }
+
+        self.slices.insert(layer, LayerSlice { num_vars, values });
+        Ok(())
+    }
+}
+
+// The indices of a layer of 2^num_vars values held by a worker: the ones starting with the bits of its index.
+// With fewer values than workers, every value is held by one of the workers sharing its prefix, the others hold nothing
+fn slice_range(worker_index: usize, number_of_workers: usize, num_vars: usize) -> Range<usize> {
+    let layer_size = 1 << num_vars;
+
+    (worker_index * layer_size / number_of_workers)
+        ..((worker_index + 1) * layer_size / number_of_workers)
+}
+
+// The worker holding the index of a layer: the last one whose slice starts at or before it
+fn slice_holder(index: usize, number_of_workers: usize, num_vars: usize) -> usize {
+    ((index + 1) * number_of_workers - 1) >> num_vars
+}
+
+// The indices of the layer below read by the part [offset, offset + part_size) of the tables of a layer:
+// the b and the c of the positions (b, c) in the part
+fn part_reads(
+    offset: usize,
+    part_size: usize,
+    input_num_vars: usize,
+) -> impl Iterator<Item = usize> {
+    let layer_size = 1 << input_num_vars;
+    let b_values = (offset >> input_num_vars)..=((offset + part_size - 1) >> input_num_vars);
+    let c_values = if part_size >= layer_size {
+        0..layer_size
+    } else {
+        (offset & (layer_size - 1))..(offset & (layer_size - 1)) + part_size
+    };
+
+    b_values.chain(c_values)
+}
+
+// The sizes of the chunks a list of values is forwarded in
+fn chunk_sizes(size: usize) -> impl Iterator<Item = usize> {
+    (0..size)
+        .step_by(CHUNK_SIZE)
+        .map(move |start| CHUNK_SIZE.min(size - start))
+}
+
+// Like the wiring predicates, the same gate twice only counts once
+fn layer_wires<F: PrimeField>(
+    circuit: &Circuit<F>,
+    layer_index: usize,
+) -> BTreeSet<(usize, usize, usize, bool)> {
+    circuit.layers[layer_index]
+        .gates
+        .iter()
+        .map(|gate| {
+            (
+                gate.left,
+                gate.right,
+                gate.output,
+                gate.operation == Operation::MUL,
+            )
+        })
+        .collect()
+}
+
+// The part of the tables add_i * (W(b) + W(c)) + mul_i * W(b) * W(c) at the positions of part,
+// from the weights of the gates (position of (b, c), weight, is_mul) in the part and the values W of the layer below
+fn part_products<F: PrimeField>(
+    weighted_wires: impl IntoIterator<Item = (usize, F, bool)>,
+    input_num_vars: usize,
+    part: Range<usize>,
+    layer_below: impl Fn(usize) -> io::Result<F>,
+) -> io::Result<Vec<Vec<Vec<F>>>> {
+    let mut add_i = vec![F::zero(); part.len()];
+    let mut mul_i = vec![F::zero(); part.len()];
+    for (position, weight, is_mul) in weighted_wires {
+        let table = if is_mul { &mut mul_i } else { &mut add_i };
+        table[position - part.start] += weight;
+    }
+
+    let (add_w, mul_w) = part
+        .map(|position| {
+            let w_b = layer_below(position >> input_num_vars)?;
+            let w_c = layer_below(position & ((1 << input_num_vars) - 1))?;
+
+            Ok((w_b + w_c, w_b * w_c))
+        })
+        .collect::<io::Result<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+
+    Ok(into_products(vec![add_i, add_w, mul_i, mul_w]))
+}
+
+// The part of W(point) from a slice of the layer: the sum over the x of the slice of eq(point, x) * W(x).
+// The indices of a slice share a prefix, so eq(point, x) is eq at the prefix times eq at the rest of x
+fn slice_evaluation<F: PrimeField>(slice: &[F], start: usize, point: &[F]) -> F {
+    if slice.is_empty() {
+        return F::zero();
+    }
+
+    let suffix_num_vars = slice.len().ilog2() as usize;
+    let (prefix_point, suffix_point) = point.split_at(point.len() - suffix_num_vars);
+
+    let suffix_evaluation: F = MultilinearPolynomialEV::eq_polynomial(suffix_point)
+        .evaluated_values
+        .iter()
+        .zip(slice.iter())
+        .map(|(eq_value, value)| *eq_value * value)
+        .sum();
+
+    eq_at_index(prefix_point, start >> suffix_num_vars) * suffix_evaluation
+}
+
+// eq(point, index), the bits of index being read from the most significant one, as point[0] is
+fn eq_at_index<F: PrimeField>(point: &[F], index: usize) -> F {
+    point
+        .iter()
+        .enumerate()
+        .map(|(i, coordinate)| {
+            if (index >> (point.len() - 1 - i)) & 1 == 1 {
+                *coordinate
+            } else {
+                F::one() - coordinate
+            }
+        })
+        .product()
+}
+
+// [add_i, add_w, mul_i, mul_w] as the products add_i * add_w + mul_i * mul_w
+fn into_products<F: PrimeField>(tables: Vec<Vec<F>>) -> Vec<Vec<Vec<F>>> {
+    let mut tables = tables.into_iter();
+
+    (0..NUMBER_OF_TABLES / DEGREE)
+        .map(|_| tables.by_ref().take(DEGREE).collect())
+        .collect()
+}
+
+// Commands and sizes are sent as field elements
+fn command<F: PrimeField>(value: usize) -> F {
+    F::from(value as u64)
+}
+
+// The first count arguments as sizes
+fn to_usizes<F: PrimeField>(arguments: &[F], count: usize) -> io::Result<Vec<usize>> {
+    if arguments.len() < count {
+        return Err(invalid_message_error());
+    }
+
+    arguments[..count]
+        .iter()
+        .map(|argument| to_usize(*argument))
+        .collect()
+}
+
+fn to_usize<F: PrimeField>(element: F) -> io::Result<usize> {
+    element
+        .into_bigint()
+        .to_bytes_le()
+        .iter()
+        .enumerate()
+        .try_fold(0usize, |value, (index, byte)| match *byte {
+            0 => Ok(value),
+            byte if index < size_of::<usize>() => Ok(value | (byte as usize) << (8 * index)),
+            _ => Err(invalid_message_error()),
+        })
+}
@@ -54,6 +54,26 @@ impl<F: PrimeField> MultilinearPolynomialEV<F> {
         MultilinearPolynomialEV::new(&scaled_values)
     }
 
+    // This function builds the eq(point, x) polynomial over the boolean hypercube
+    // eq(point, x) = product of (point_i * x_i + (1 - point_i) * (1 - x_i)), so that
+    // f(point) = sum over x of eq(point, x) * f(x) for any multilinear f with the same variable ordering
+    pub fn eq_polynomial(point: &[F]) -> Self {
+        let mut eq_values = vec![F::one()];
+
+        for value in point {
+            let mut next_values = Vec::with_capacity(eq_values.len() * 2);
+
+            for eq_value in eq_values.iter() {
+                next_values.push(*eq_value * (F::one() - value));
+                next_values.push(*eq_value * value);
+            }
+
+            eq_values = next_values;
+        }
+
+        MultilinearPolynomialEV::new(&eq_values)
+    }
+
     // This function will receive a polynomial in it's evaluated form
     // That means the polynomial it will receive has already been evaluated over a boolean hypercube
     pub fn partial_evaluate(polynomial: &[F], evaluating_variable: usize, value: F) -> Self {
@@ -0,0 +1,296 @@
+use crate::circuits::circuit::{pad_to_num_vars, Circuit};
+use crate::circuits::gate::Operation;
+use crate::extension_sum_check::{
+    evaluate_at_extension_point, extension_element_to_bytes, prove_extension_tables,
+    verify as sumcheck_verify, ExtensionSumcheckProof,
+};
+use crate::gkr_sumcheck_dependencies::transcript::Transcript;
+use crate::sum_check::field_element_to_bytes;
+use ark_ff::{Field, PrimeField};
+
+// GKR over a small base field F (eg: Goldilocks) with the challenges drawn from an extension E of F.
+// The circuit is evaluated over F, everything that depends on a challenge is over E:
+// the point of the output claim, the wiring predicates combined with the claims, the layer sum-checks
+// (extension_sum_check) and the evaluations W(rb), W(rc) of the layers below.
+// The reduction is the one of gkr_p with ClaimCombination::RandomLinearCombination:
+// the claims at rb and rc are combined with two random weights of E, and the last claims are checked against the inputs.
+// As for distributed and collaborative, only the two input gates of gkr_p reading the layer directly below are supported.
+
+#[derive(Clone, Debug)]
+pub struct ExtensionGkrProof<F: PrimeField, E: Field<BasePrimeField = F>> {
+    pub circuit_output: Vec<F>,
+    pub sumcheck_proofs: Vec<ExtensionSumcheckProof<E>>,
+    pub wb_evaluations: Vec<E>,
+    pub wc_evaluations: Vec<E>,
+}
+
+// A claim that the sum of weight * W(point) over the weighted points is claimed_sum
+struct WeightedClaim<E: Field> {
+    weighted_points: Vec<(E, Vec<E>)>,
+    claimed_sum: E,
+}
+
+pub fn prove<F: PrimeField, E: Field<BasePrimeField = F>>(
+    circuit: &mut Circuit<F>,
+    inputs: &[F],
+) -> ExtensionGkrProof<F, E> {
+    assert_supported_circuit(circuit);
+
+    let circuit_evaluation = circuit.evaluate(inputs.to_vec());
+    let number_of_layers = circuit.layers.len();
+
+    let mut transcript = Transcript::new();
+    let output = pad_to_num_vars(&circuit_evaluation.output, circuit.layer_num_vars(0));
+    let point = output_point::<F, E>(&output.evaluated_values, &mut transcript);
+    let mut claim = WeightedClaim {
+        claimed_sum: evaluate_at_extension_point(&output, &point),
+        weighted_points: vec![(E::ONE, point)],
+    };
+
+    let mut sumcheck_proofs = Vec::with_capacity(number_of_layers);
+    let mut wb_evaluations = Vec::with_capacity(number_of_layers);
+    let mut wc_evaluations = Vec::with_capacity(number_of_layers);
+
+    for layer_index in 0..number_of_layers {
+        let input_num_vars = circuit.layer_input_num_vars(layer_index);
+        let layer_below = pad_to_num_vars(
+            &circuit_evaluation.layer_evaluations[layer_index + 1],
+            input_num_vars,
+        );
+
+        // The tables are indexed by (b, c), b being the most significant half
+        let [add_i_bc, mul_i_bc] = wiring_tables(circuit, layer_index, &claim, input_num_vars);
+        let values: Vec<E> = layer_below
+            .evaluated_values
+            .iter()
+            .map(|value| E::from_base_prime_field(*value))
+            .collect();
+        let mask = values.len() - 1;
+        let w_b: Vec<E> = (0..values.len() * values.len())
+            .map(|index| values[index >> input_num_vars])
+            .collect();
+        let w_c: Vec<E> = (0..values.len() * values.len())
+            .map(|index| values[index & mask])
+            .collect();
+
+        // add_i(b, c) * (W(b) + W(c)) + mul_i(b, c) * W(b) * W(c)
+        let sumcheck_proof = prove_extension_tables(
+            vec![
+                vec![add_i_bc.clone(), w_b.clone()],
+                vec![add_i_bc, w_c.clone()],
+                vec![mul_i_bc, w_b, w_c],
+            ],
+            claim.claimed_sum,
+            &mut transcript,
+        );
+
+        let (rb, rc) = sumcheck_proof.random_challenges.split_at(input_num_vars);
+        let wb = evaluate_at_extension_point(&layer_below, rb);
+        let wc = evaluate_at_extension_point(&layer_below, rc);
+
+        claim = next_claim(rb, rc, wb, wc, &mut transcript);
+
+        sumcheck_proofs.push(sumcheck_proof);
+        wb_evaluations.push(wb);
+        wc_evaluations.push(wc);
+    }
+
+    ExtensionGkrProof {
+        circuit_output: circuit_evaluation.output,
+        sumcheck_proofs,
+        wb_evaluations,
+        wc_evaluations,
+    }
+}
+
+pub fn verify<F: PrimeField, E: Field<BasePrimeField = F>>(
+    circuit: &Circuit<F>,
+    proof: &ExtensionGkrProof<F, E>,
+    inputs: &[F],
+) -> bool {
+    assert_supported_circuit(circuit);
+
+    let number_of_layers = circuit.layers.len();
+    let output_num_vars = circuit.layer_num_vars(0);
+    let input_num_vars = circuit.layer_input_num_vars(number_of_layers - 1);
+
+    if proof.sumcheck_proofs.len() != number_of_layers
+        || proof.wb_evaluations.len() != number_of_layers
+        || proof.wc_evaluations.len() != number_of_layers
+        || proof.circuit_output.len() > 1 << output_num_vars
+        || inputs.len() > 1 << input_num_vars
+    {
+        return false;
+    }
+
+    let mut transcript = Transcript::new();
+    let output = pad_to_num_vars(&proof.circuit_output, output_num_vars);
+    let point = output_point::<F, E>(&output.evaluated_values, &mut transcript);
+    let mut claim = WeightedClaim {
+        claimed_sum: evaluate_at_extension_point(&output, &point),
+        weighted_points: vec![(E::ONE, point)],
+    };
+
+    for layer_index in 0..number_of_layers {
+        let sumcheck_proof = &proof.sumcheck_proofs[layer_index];
+        let layer_input_num_vars = circuit.layer_input_num_vars(layer_index);
+
+        if sumcheck_proof.claimed_sum != claim.claimed_sum {
+            return false;
+        }
+
+        // add_i(b, c) * W(b) is of degree 2, mul_i(b, c) * W(b) * W(c) of degree 3
+        let verification = sumcheck_verify(sumcheck_proof, 3, &mut transcript);
+        if !verification.is_proof_valid
+            || verification.random_challenges.len() != 2 * layer_input_num_vars
+        {
+            return false;
+        }
+
+        let (rb, rc) = verification
+            .random_challenges
+            .split_at(layer_input_num_vars);
+        let (wb, wc) = (
+            proof.wb_evaluations[layer_index],
+            proof.wc_evaluations[layer_index],
+        );
+        let [add_i, mul_i] = wiring_evaluations(circuit, layer_index, &claim, rb, rc);
+
+        if verification.last_claimed_sum != add_i * (wb + wc) + mul_i * wb * wc {
+            return false;
+        }
+
+        claim = next_claim(rb, rc, wb, wc, &mut transcript);
+    }
+
+    // The last claims are on the input layer, which the verifier evaluates itself
+    let inputs = pad_to_num_vars(inputs, input_num_vars);
+    let expected_sum: E = claim
+        .weighted_points
+        .iter()
+        .map(|(weight, point)| *weight * evaluate_at_extension_point(&inputs, point))
+        .sum();
+
+    expected_sum == claim.claimed_sum
+}
+
+fn assert_supported_circuit<F: PrimeField>(circuit: &Circuit<F>) {
+    assert!(
+        circuit
+            .layers
+            .iter()
+            .enumerate()
+            .all(|(layer_index, layer)| {
+                layer.multi_input_gates.is_empty()
+                    && layer.custom_gates.is_empty()
+                    && layer.gates.iter().all(|gate| {
+                        gate.source_layers(layer_index) == (layer_index + 1, layer_index + 1)
+                    })
+            }),
+        "extension GKR only supports two input gates reading the layer below"
+    );
+}
+
+fn output_point<F: PrimeField, E: Field<BasePrimeField = F>>(
+    output: &[F],
+    transcript: &mut Transcript,
+) -> Vec<E> {
+    for value in output.iter() {
+        transcript.absorb(&field_element_to_bytes(*value));
+    }
+
+    (0..output.len().ilog2())
+        .map(|_| transcript.random_challenge_as_extension_element())
+        .collect()
+}
+
+// Absorbs W(rb) and W(rc), and combines them into the claim on the layer below: alpha * W(rb) + beta * W(rc)
+fn next_claim<E: Field>(
+    rb: &[E],
+    rc: &[E],
+    wb: E,
+    wc: E,
+    transcript: &mut Transcript,
+) -> WeightedClaim<E> {
+    transcript.absorb(&extension_element_to_bytes(wb));
+    transcript.absorb(&extension_element_to_bytes(wc));
+
+    let alpha: E = transcript.random_challenge_as_extension_element();
+    let beta: E = transcript.random_challenge_as_extension_element();
+
+    WeightedClaim {
+        weighted_points: vec![(alpha, rb.to_vec()), (beta, rc.to_vec())],
+        claimed_sum: alpha * wb + beta * wc,
+    }
+}
+
+// sum over the claims of weight * eq(point, a), for every output a of the layer
+fn combined_eq_table<E: Field>(claim: &WeightedClaim<E>) -> Vec<E> {
+    let num_vars = claim.weighted_points[0].1.len();
+    let mut table = vec![E::ZERO; 1 << num_vars];
+
+    for (weight, point) in claim.weighted_points.iter() {
+        for (value, eq) in table.iter_mut().zip(eq_table(point)) {
+            *value += *weight * eq;
+        }
+    }
+
+    table
+}
+
+// eq(point, x) for every x of the boolean hypercube, point[0] being the most significant bit
+fn eq_table<E: Field>(point: &[E]) -> Vec<E> {
+    point.iter().fold(vec![E::ONE], |table, coordinate| {
+        table
+            .iter()
+            .flat_map(|value| [*value * (E::ONE - coordinate), *value * coordinate])
+            .collect()
+    })
+}
+
+// add_i(b, c) and mul_i(b, c) combined with the claims on the outputs, as dense tables over (b, c)
+fn wiring_tables<F: PrimeField, E: Field<BasePrimeField = F>>(
+    circuit: &Circuit<F>,
+    layer_index: usize,
+    claim: &WeightedClaim<E>,
+    input_num_vars: usize,
+) -> [Vec<E>; 2] {
+    let output_weights = combined_eq_table(claim);
+    let mut tables = [
+        vec![E::ZERO; 1 << (2 * input_num_vars)],
+        vec![E::ZERO; 1 << (2 * input_num_vars)],
+    ];
+
+    for gate in circuit.layers[layer_index].gates.iter() {
+        let table = match gate.operation {
+            Operation::ADD => &mut tables[0],
+            Operation::MUL => &mut tables[1],
+        };
+        table[(gate.left << input_num_vars) + gate.right] += output_weights[gate.output];
+    }
+
+    tables
+}
+
+// add_i(rb, rc) and mul_i(rb, rc) combined with the claims on the outputs, from the gates of the layer
+fn wiring_evaluations<F: PrimeField, E: Field<BasePrimeField = F>>(
+    circuit: &Circuit<F>,
+    layer_index: usize,
+    claim: &WeightedClaim<E>,
+    rb: &[E],
+    rc: &[E],
+) -> [E; 2] {
+    let output_weights = combined_eq_table(claim);
+    let (eq_b, eq_c) = (eq_table(rb), eq_table(rc));
+    let mut evaluations = [E::ZERO; 2];
+
+    for gate in circuit.layers[layer_index].gates.iter() {
+        let evaluation = match gate.operation {
+            Operation::ADD => &mut evaluations[0],
+            Operation::MUL => &mut evaluations[1],
+        };
+        *evaluation += output_weights[gate.output] * eq_b[gate.left] * eq_c[gate.right];
+    }
+
+    evaluations
+}
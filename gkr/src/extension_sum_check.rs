@@ -0,0 +1,251 @@
+use crate::evaluation::MultilinearPolynomialEV;
+use crate::gkr_sumcheck_dependencies::poly_sum::SumPolynomial;
+use crate::gkr_sumcheck_dependencies::transcript::Transcript;
+use crate::sum_check::{field_element_to_bytes, generate_round_univariate};
+use crate::sum_check_rounds::{absorb_round, fold, interpolate_at};
+use ark_ff::{Field, PrimeField};
+
+// Sum-check of a SumPolynomial over a small base field F, with the challenges drawn from an extension E of F.
+// The first round only involves the base field values, so it runs entirely in F.
+// Once the first variable is bound to a challenge, the polynomials are folded into tables of E
+// and the remaining rounds run over E.
+// The round polynomials are sent as their evaluations at 0, 1, ..., degree, all of them in E.
+// The other protocols of the crate draw their challenges from the field of the polynomials,
+// which is only sound for large fields (eg: BN254): this is the building block for small fields,
+// used by extension_gkr for the layer sum-checks of GKR.
+
+#[derive(Clone, Debug)]
+pub struct ExtensionSumcheckProof<E: Field> {
+    pub claimed_sum: E,
+    pub round_evaluations: Vec<Vec<E>>,
+    pub random_challenges: Vec<E>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExtensionSumcheckVerifierProof<E: Field> {
+    pub is_proof_valid: bool,
+    pub random_challenges: Vec<E>,
+    pub last_claimed_sum: E,
+}
+
+pub fn prove<F: PrimeField, E: Field<BasePrimeField = F>>(
+    sum_polynomial: SumPolynomial<F>,
+    claimed_sum: F,
+    transcript: &mut Transcript,
+) -> ExtensionSumcheckProof<E> {
+    let number_of_variables = sum_polynomial.number_of_variables() as usize;
+    let degree = sum_polynomial.degree();
+    let claimed_sum = E::from_base_prime_field(claimed_sum);
+
+    let mut round_evaluations = Vec::with_capacity(number_of_variables);
+    let mut random_challenges = Vec::with_capacity(number_of_variables);
+
+    transcript.absorb(&extension_element_to_bytes(claimed_sum));
+
+    if number_of_variables == 0 {
+        return ExtensionSumcheckProof {
+            claimed_sum,
+            round_evaluations,
+            random_challenges,
+        };
+    }
+
+    // First round over the base field
+    let evaluations: Vec<E> = generate_round_univariate(&sum_polynomial)
+        .into_iter()
+        .map(E::from_base_prime_field)
+        .collect();
+    let challenge = absorb_round(&evaluations, transcript);
+
+    // products[i][j] is the table of the j-th factor of the i-th product, with the first variable bound
+    let mut products: Vec<Vec<Vec<E>>> = sum_polynomial
+        .product_polynomials
+        .iter()
+        .map(|product_polynomial| {
+            product_polynomial
+                .polynomials
+                .iter()
+                .map(|polynomial| fold_base(&polynomial.evaluated_values, challenge))
+                .collect()
+        })
+        .collect();
+
+    round_evaluations.push(evaluations);
+    random_challenges.push(challenge);
+
+    prove_remaining_rounds(
+        &mut products,
+        degree,
+        &mut round_evaluations,
+        &mut random_challenges,
+        transcript,
+    );
+
+    ExtensionSumcheckProof {
+        claimed_sum,
+        round_evaluations,
+        random_challenges,
+    }
+}
+
+// Sum-check of a sum of products of tables that are already in the extension,
+// eg: tables built from the challenges of a previous sum-check
+pub fn prove_extension_tables<E: Field>(
+    mut products: Vec<Vec<Vec<E>>>,
+    claimed_sum: E,
+    transcript: &mut Transcript,
+) -> ExtensionSumcheckProof<E> {
+    assert!(
+        products
+            .iter()
+            .flatten()
+            .all(|table| { table.len().is_power_of_two() && table.len() == products[0][0].len() }),
+        "the tables must all have the same power of two size"
+    );
+
+    let number_of_variables = products[0][0].len().ilog2() as usize;
+    let degree = products.iter().map(|factors| factors.len()).max().unwrap();
+
+    let mut round_evaluations = Vec::with_capacity(number_of_variables);
+    let mut random_challenges = Vec::with_capacity(number_of_variables);
+
+    transcript.absorb(&extension_element_to_bytes(claimed_sum));
+
+    prove_remaining_rounds(
+        &mut products,
+        degree,
+        &mut round_evaluations,
+        &mut random_challenges,
+        transcript,
+    );
+
+    ExtensionSumcheckProof {
+        claimed_sum,
+        round_evaluations,
+        random_challenges,
+    }
+}
+
+// The rounds over the extension: every round binds the first variable of the tables to the challenge
+fn prove_remaining_rounds<E: Field>(
+    products: &mut [Vec<Vec<E>>],
+    degree: usize,
+    round_evaluations: &mut Vec<Vec<E>>,
+    random_challenges: &mut Vec<E>,
+    transcript: &mut Transcript,
+) {
+    while products[0][0].len() > 1 {
+        let evaluations = extension_round_evaluations(products, degree);
+        let challenge = absorb_round(&evaluations, transcript);
+
+        for factors in products.iter_mut() {
+            for table in factors.iter_mut() {
+                *table = fold(table, challenge);
+            }
+        }
+
+        round_evaluations.push(evaluations);
+        random_challenges.push(challenge);
+    }
+}
+
+// The degree is the verifier's own, the number of factors of the largest product:
+// a round with any other number of evaluations is rejected, so the prover can't send a polynomial of higher degree
+pub fn verify<E: Field>(
+    proof: &ExtensionSumcheckProof<E>,
+    degree: usize,
+    transcript: &mut Transcript,
+) -> ExtensionSumcheckVerifierProof<E> {
+    transcript.absorb(&extension_element_to_bytes(proof.claimed_sum));
+
+    let mut current_sum = proof.claimed_sum;
+    let mut random_challenges = Vec::with_capacity(proof.round_evaluations.len());
+
+    for evaluations in proof.round_evaluations.iter() {
+        if evaluations.len() != degree + 1 || evaluations[0] + evaluations[1] != current_sum {
+            return ExtensionSumcheckVerifierProof {
+                is_proof_valid: false,
+                random_challenges: vec![],
+                last_claimed_sum: current_sum,
+            };
+        }
+
+        let challenge = absorb_round(evaluations, transcript);
+        current_sum = interpolate_at(evaluations, challenge);
+
+        random_challenges.push(challenge);
+    }
+
+    ExtensionSumcheckVerifierProof {
+        is_proof_valid: true,
+        random_challenges,
+        last_claimed_sum: current_sum,
+    }
+}
+
+// The evaluation of a multilinear polynomial over the base field at a point of the extension
+pub fn evaluate_at_extension_point<F: PrimeField, E: Field<BasePrimeField = F>>(
+    polynomial: &MultilinearPolynomialEV<F>,
+    point: &[E],
+) -> E {
+    assert_eq!(
+        polynomial.number_of_variables() as usize,
+        point.len(),
+        "the point has the wrong number of variables"
+    );
+
+    match point.split_first() {
+        None => E::from_base_prime_field(polynomial.evaluated_values[0]),
+        Some((first, rest)) => rest.iter().fold(
+            fold_base(&polynomial.evaluated_values, *first),
+            |table, challenge| fold(&table, *challenge),
+        )[0],
+    }
+}
+
+pub fn extension_element_to_bytes<E: Field>(element: E) -> Vec<u8> {
+    element
+        .to_base_prime_field_elements()
+        .flat_map(field_element_to_bytes)
+        .collect()
+}
+
+// Binds the first variable (the most significant bit) of a table of the base field to the challenge
+fn fold_base<F: PrimeField, E: Field<BasePrimeField = F>>(values: &[F], challenge: E) -> Vec<E> {
+    let (low, high) = values.split_at(values.len() / 2);
+
+    low.iter()
+        .zip(high.iter())
+        .map(|(low, high)| {
+            E::from_base_prime_field(*low) + challenge * E::from_base_prime_field(*high - low)
+        })
+        .collect()
+}
+
+// The evaluations at 0, 1, ..., degree of the round polynomial: the sum over the remaining variables
+// with the first one set to each point
+fn extension_round_evaluations<E: Field>(products: &[Vec<Vec<E>>], degree: usize) -> Vec<E> {
+    (0..=degree)
+        .map(|point| {
+            let point = E::from(point as u64);
+
+            products
+                .iter()
+                .map(|factors| {
+                    let half = factors[0].len() / 2;
+
+                    (0..half)
+                        .map(|index| {
+                            factors
+                                .iter()
+                                .map(|table| {
+                                    table[index] + point * (table[index + half] - table[index])
+                                })
+                                .product::<E>()
+                        })
+                        .sum::<E>()
+                })
+                .sum()
+        })
+        .collect()
+}
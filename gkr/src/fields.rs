@@ -0,0 +1,66 @@
+use ark_ff::fields::{Fp2, Fp2Config, Fp4, Fp4Config, Fp64, MontBackend, MontConfig, MontFp};
+
+// Small prime fields, for witnesses that fit in a machine word, with the extension fields
+// the verifier draws its challenges from: a sum-check over a 64 bits field has a soundness error of about
+// degree * num_vars / 2^64 per proof, far too large. The challenges of GoldilocksExt2 have about 128 bits,
+// the ones of BabyBearExt4 about 124 bits, BabyBearExt2 only has about 62 bits.
+
+// Goldilocks: p = 2^64 - 2^32 + 1
+#[derive(MontConfig)]
+#[modulus = "18446744069414584321"]
+#[generator = "7"]
+pub struct GoldilocksConfig;
+pub type Goldilocks = Fp64<MontBackend<GoldilocksConfig, 1>>;
+
+// Goldilocks[u] / (u^2 - 7)
+pub struct GoldilocksExt2Config;
+
+impl Fp2Config for GoldilocksExt2Config {
+    type Fp = Goldilocks;
+
+    const NONRESIDUE: Goldilocks = MontFp!("7");
+
+    // NONRESIDUE^((p^i - 1) / 2) for i = 0, 1
+    const FROBENIUS_COEFF_FP2_C1: &'static [Goldilocks] = &[MontFp!("1"), MontFp!("-1")];
+}
+
+pub type GoldilocksExt2 = Fp2<GoldilocksExt2Config>;
+
+// BabyBear: p = 2^31 - 2^27 + 1
+#[derive(MontConfig)]
+#[modulus = "2013265921"]
+#[generator = "31"]
+pub struct BabyBearConfig;
+pub type BabyBear = Fp64<MontBackend<BabyBearConfig, 1>>;
+
+// BabyBear[u] / (u^2 - 11)
+pub struct BabyBearExt2Config;
+
+impl Fp2Config for BabyBearExt2Config {
+    type Fp = BabyBear;
+
+    const NONRESIDUE: BabyBear = MontFp!("11");
+
+    const FROBENIUS_COEFF_FP2_C1: &'static [BabyBear] = &[MontFp!("1"), MontFp!("-1")];
+}
+
+pub type BabyBearExt2 = Fp2<BabyBearExt2Config>;
+
+// BabyBearExt2[v] / (v^2 - u), the same field as BabyBear[x] / (x^4 - 11)
+pub struct BabyBearExt4Config;
+
+impl Fp4Config for BabyBearExt4Config {
+    type Fp2Config = BabyBearExt2Config;
+
+    const NONRESIDUE: BabyBearExt2 = BabyBearExt2::new(MontFp!("0"), MontFp!("1"));
+
+    // 11^((p^i - 1) / 4) for i = 0, 1, 2, 3
+    const FROBENIUS_COEFF_FP4_C1: &'static [BabyBear] = &[
+        MontFp!("1"),
+        MontFp!("1728404513"),
+        MontFp!("2013265920"),
+        MontFp!("284861408"),
+    ];
+}
+
+pub type BabyBearExt4 = Fp4<BabyBearExt4Config>;
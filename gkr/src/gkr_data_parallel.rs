@@ -0,0 +1,341 @@
+use crate::circuits::circuit::Circuit;
+use crate::evaluation::MultilinearPolynomialEV;
+use crate::gkr_sumcheck_dependencies::poly_sum::SumPolynomial;
+use crate::gkr_sumcheck_dependencies::transcript::Transcript;
+use crate::product_poly::ProductPolynomial;
+use crate::sum_check::{
+    field_element_to_bytes, prove as sumcheck_prove, verify as sumcheck_verify, SumcheckProverProof,
+};
+use crate::utility::utils::eq_evaluation;
+use crate::wb_bc_utility::{compute_combined_add_i_mul_i, compute_verifier_combined_claim};
+use ark_ff::PrimeField;
+
+// Data-parallel GKR: the same circuit is evaluated on N input vectors,
+// and the N copies are proven together as one wide circuit.
+// Every layer of the wide circuit is indexed by (z, a) where z picks the copy (instance) and a picks the gate in that copy.
+// Since every copy has the same wiring, the wide wiring predicate is eq(z, z') * add_i(a, b, c),
+// so the sumcheck for a layer runs over (z, b, c) and the proof only grows with log(N).
+
+#[derive(Clone, Debug)]
+pub struct DataParallelProof<F: PrimeField> {
+    pub circuit_outputs: Vec<Vec<F>>,
+    pub sumcheck_proofs: Vec<SumcheckProverProof<F>>,
+    pub wb_evaluations: Vec<F>,
+    pub wc_evaluations: Vec<F>,
+}
+
+pub fn prove<F: PrimeField>(circuit: &mut Circuit<F>, inputs: &[Vec<F>]) -> DataParallelProof<F> {
+    assert!(!inputs.is_empty(), "at least one input vector is required");
+    assert!(
+        !circuit.has_skip_connections()
+            && !circuit.has_multi_input_gates()
+            && !circuit.has_custom_gates(),
+        "data-parallel proving only supports circuits of gates with two inputs from the layer below"
+    );
+
+    let number_of_layers = circuit.layers.len();
+    let instance_num_vars = instance_num_vars(inputs.len());
+
+    assert!(
+        inputs
+            .iter()
+            .all(|input| input.len() <= 1 << circuit.layer_num_vars(number_of_layers)),
+        "more inputs than the circuit reads"
+    );
+
+    let circuit_evaluations: Vec<Vec<Vec<F>>> = inputs
+        .iter()
+        .map(|input| circuit.evaluate(input.clone()).layer_evaluations)
+        .collect();
+
+    let mut transcript = Transcript::new();
+    let mut layer_proofs = Vec::with_capacity(number_of_layers);
+    let mut wb_evaluations = Vec::new();
+    let mut wc_evaluations = Vec::new();
+
+    let w0_polynomial = wide_layer_polynomial(
+        circuit_evaluations.iter().map(|evaluation| &evaluation[0]),
+        instance_num_vars,
+        circuit.layer_num_vars(0),
+    );
+
+    transcript.absorb(&w0_polynomial.convert_to_bytes());
+    let output_challenges: Vec<F> = (0..w0_polynomial.number_of_variables())
+        .map(|_| transcript.random_challenge_as_field_element())
+        .collect();
+    let mut claimed_sum = w0_polynomial.evaluate(&output_challenges);
+
+    let (rz_values, random_challenges_a) = output_challenges.split_at(instance_num_vars);
+    let mut rz_values = rz_values.to_vec();
+    let mut weighted_points = vec![(F::one(), random_challenges_a.to_vec())];
+
+    for layer_index in 0..number_of_layers {
+        let (add_i_abc, mul_i_abc) = circuit.add_i_and_mul_i_mle(layer_index);
+
+        let (add_i_bc, mul_i_bc) =
+            compute_combined_add_i_mul_i(&add_i_abc, &mul_i_abc, &weighted_points);
+
+        // The layer below, for every copy of the circuit: a polynomial over (z, b)
+        let input_num_vars = circuit.layer_num_vars(layer_index + 1);
+        let w_next_polynomial = wide_layer_polynomial(
+            circuit_evaluations
+                .iter()
+                .map(|evaluation| &evaluation[layer_index + 1]),
+            instance_num_vars,
+            input_num_vars,
+        );
+
+        let layer_polynomial = compute_data_parallel_fbc_polynomial(
+            &rz_values,
+            &add_i_bc,
+            &mul_i_bc,
+            &w_next_polynomial,
+            input_num_vars,
+        );
+
+        let sumcheck_proof = sumcheck_prove(layer_polynomial, claimed_sum, &mut transcript);
+        layer_proofs.push(sumcheck_proof.clone());
+
+        // Just like gkr_p, the verifier evaluates the input layer itself,
+        // so only the evaluations of the layers in between are sent
+        if layer_index < number_of_layers - 1 {
+            let (current_rz_values, rb, rc) =
+                split_challenges(&sumcheck_proof.random_challenges, instance_num_vars);
+
+            let wb_evaluation =
+                w_next_polynomial.evaluate(&[current_rz_values.clone(), rb.clone()].concat());
+            let wc_evaluation =
+                w_next_polynomial.evaluate(&[current_rz_values.clone(), rc.clone()].concat());
+
+            wb_evaluations.push(wb_evaluation);
+            wc_evaluations.push(wc_evaluation);
+
+            transcript.absorb(&field_element_to_bytes(wb_evaluation));
+            let alpha: F = transcript.random_challenge_as_field_element();
+
+            transcript.absorb(&field_element_to_bytes(wc_evaluation));
+            let beta: F = transcript.random_challenge_as_field_element();
+
+            rz_values = current_rz_values;
+            weighted_points = vec![(alpha, rb), (beta, rc)];
+            claimed_sum = (alpha * wb_evaluation) + (beta * wc_evaluation);
+        }
+    }
+
+    DataParallelProof {
+        circuit_outputs: circuit_evaluations
+            .into_iter()
+            .map(|mut evaluation| evaluation.swap_remove(0))
+            .collect(),
+        sumcheck_proofs: layer_proofs,
+        wb_evaluations,
+        wc_evaluations,
+    }
+}
+
+pub fn verify<F: PrimeField>(
+    circuit: &mut Circuit<F>,
+    proof: &DataParallelProof<F>,
+    inputs: &[Vec<F>],
+) -> bool {
+    let number_of_layers = circuit.layers.len();
+
+    if inputs.is_empty()
+        || circuit.has_skip_connections()
+        || circuit.has_multi_input_gates()
+        || circuit.has_custom_gates()
+        || proof.circuit_outputs.len() != inputs.len()
+        || proof.sumcheck_proofs.len() != number_of_layers
+        || proof.wb_evaluations.len() + 1 != number_of_layers
+        || proof.wc_evaluations.len() + 1 != number_of_layers
+    {
+        return false;
+    }
+
+    let instance_num_vars = instance_num_vars(inputs.len());
+    let output_num_vars = circuit.layer_num_vars(0);
+    let input_num_vars = circuit.layer_num_vars(number_of_layers);
+
+    if proof
+        .circuit_outputs
+        .iter()
+        .any(|output| output.len() > 1 << output_num_vars)
+        || inputs.iter().any(|input| input.len() > 1 << input_num_vars)
+    {
+        return false;
+    }
+
+    let mut transcript = Transcript::new();
+
+    let w0_polynomial = wide_layer_polynomial(
+        proof.circuit_outputs.iter(),
+        instance_num_vars,
+        output_num_vars,
+    );
+
+    transcript.absorb(&w0_polynomial.convert_to_bytes());
+    let output_challenges: Vec<F> = (0..w0_polynomial.number_of_variables())
+        .map(|_| transcript.random_challenge_as_field_element())
+        .collect();
+    let mut claimed_sum = w0_polynomial.evaluate(&output_challenges);
+
+    let (rz_values, random_challenges_a) = output_challenges.split_at(instance_num_vars);
+    let mut rz_values = rz_values.to_vec();
+    let mut weighted_points = vec![(F::one(), random_challenges_a.to_vec())];
+
+    for layer_index in 0..number_of_layers {
+        let layer_proof = &proof.sumcheck_proofs[layer_index];
+
+        if claimed_sum != layer_proof.claimed_sum {
+            return false;
+        }
+
+        let verify_result = sumcheck_verify(layer_proof, &mut transcript);
+        if !verify_result.is_proof_valid
+            || verify_result.random_challenges.len()
+                != instance_num_vars + 2 * circuit.layer_num_vars(layer_index + 1)
+        {
+            return false;
+        }
+
+        let (current_rz_values, rb_values, rc_values) =
+            split_challenges(&verify_result.random_challenges, instance_num_vars);
+        let bc_challenges = [rb_values.clone(), rc_values.clone()].concat();
+
+        let (wb_evaluation, wc_evaluation) = if layer_index < number_of_layers - 1 {
+            (
+                proof.wb_evaluations[layer_index],
+                proof.wc_evaluations[layer_index],
+            )
+        } else {
+            let input_polynomial =
+                wide_layer_polynomial(inputs.iter(), instance_num_vars, input_num_vars);
+
+            (
+                input_polynomial.evaluate(&[current_rz_values.clone(), rb_values.clone()].concat()),
+                input_polynomial.evaluate(&[current_rz_values.clone(), rc_values.clone()].concat()),
+            )
+        };
+
+        // The wiring of a copy of the circuit is checked exactly like in gkr_p,
+        // and eq(rz, rz') makes sure the claim is about the same copies of the circuit
+        let wiring_claim = compute_verifier_combined_claim(
+            circuit,
+            layer_index,
+            &weighted_points,
+            &bc_challenges,
+            wb_evaluation,
+            wc_evaluation,
+        );
+
+        let expected_claim = eq_evaluation(&rz_values, &current_rz_values) * wiring_claim;
+
+        if expected_claim != verify_result.last_claimed_sum {
+            return false;
+        }
+
+        transcript.absorb(&field_element_to_bytes(wb_evaluation));
+        let alpha: F = transcript.random_challenge_as_field_element();
+
+        transcript.absorb(&field_element_to_bytes(wc_evaluation));
+        let beta: F = transcript.random_challenge_as_field_element();
+
+        rz_values = current_rz_values;
+        weighted_points = vec![(alpha, rb_values), (beta, rc_values)];
+        claimed_sum = (alpha * wb_evaluation) + (beta * wc_evaluation);
+    }
+
+    true
+}
+
+// The f(z, b, c) polynomial of a data-parallel layer:
+// eq(rz, z) * add_i(b, c) * (W(z, b) + W(z, c)) + eq(rz, z) * mul_i(b, c) * W(z, b) * W(z, c)
+// Every factor is extended to all of the (z, b, c) variables, so that they can make up a SumPolynomial
+pub fn compute_data_parallel_fbc_polynomial<F: PrimeField>(
+    rz_values: &[F],
+    add_i_bc: &MultilinearPolynomialEV<F>,
+    mul_i_bc: &MultilinearPolynomialEV<F>,
+    w_polynomial: &MultilinearPolynomialEV<F>,
+    input_num_vars: usize,
+) -> SumPolynomial<F> {
+    let eq_z = MultilinearPolynomialEV::eq_polynomial(rz_values).evaluated_values;
+    let number_of_instances = eq_z.len();
+    let gate_combinations = 1 << (2 * input_num_vars);
+    let size = number_of_instances * gate_combinations;
+
+    let mut eq_values = Vec::with_capacity(size);
+    let mut add_values = Vec::with_capacity(size);
+    let mut mul_values = Vec::with_capacity(size);
+    let mut wb_values = Vec::with_capacity(size);
+    let mut wc_values = Vec::with_capacity(size);
+    let mut w_add_values = Vec::with_capacity(size);
+
+    for (z, eq_value) in eq_z.iter().enumerate() {
+        let instance_offset = z << input_num_vars;
+
+        for bc in 0..gate_combinations {
+            let b = bc >> input_num_vars;
+            let c = bc & ((1 << input_num_vars) - 1);
+
+            let wb = w_polynomial.evaluated_values[instance_offset + b];
+            let wc = w_polynomial.evaluated_values[instance_offset + c];
+
+            eq_values.push(*eq_value);
+            add_values.push(add_i_bc.evaluated_values[bc]);
+            mul_values.push(mul_i_bc.evaluated_values[bc]);
+            wb_values.push(wb);
+            wc_values.push(wc);
+            w_add_values.push(wb + wc);
+        }
+    }
+
+    let eq_polynomial = MultilinearPolynomialEV::new(&eq_values);
+
+    let add_i_term = ProductPolynomial::new(vec![
+        eq_polynomial.clone(),
+        MultilinearPolynomialEV::new(&add_values),
+        MultilinearPolynomialEV::new(&w_add_values),
+    ]);
+    let mul_i_term = ProductPolynomial::new(vec![
+        eq_polynomial,
+        MultilinearPolynomialEV::new(&mul_values),
+        MultilinearPolynomialEV::new(&wb_values),
+        MultilinearPolynomialEV::new(&wc_values),
+    ]);
+
+    SumPolynomial::new(vec![add_i_term, mul_i_term])
+}
+
+// The number of instance variables (z) needed for the given number of copies
+fn instance_num_vars(number_of_instances: usize) -> usize {
+    number_of_instances.next_power_of_two().ilog2() as usize
+}
+
+// Places the layer of copy z at the block z * 2^layer_num_vars of a single polynomial.
+// Every copy is padded with zeros to 2^layer_num_vars values and missing copies are all zeros:
+// the circuits have no constants, so a copy with zero inputs has zeros in every layer.
+fn wide_layer_polynomial<'a, F: PrimeField + 'a>(
+    layers: impl Iterator<Item = &'a Vec<F>>,
+    instance_num_vars: usize,
+    layer_num_vars: usize,
+) -> MultilinearPolynomialEV<F> {
+    let block_size = 1 << layer_num_vars;
+    let mut values = vec![F::zero(); block_size << instance_num_vars];
+
+    for (z, layer) in layers.enumerate() {
+        values[z * block_size..(z * block_size) + layer.len()].copy_from_slice(layer);
+    }
+
+    MultilinearPolynomialEV::new(&values)
+}
+
+// Splits the sumcheck challenges of a data-parallel layer into (rz, rb, rc)
+fn split_challenges<F: PrimeField>(
+    challenges: &[F],
+    instance_num_vars: usize,
+) -> (Vec<F>, Vec<F>, Vec<F>) {
+    let (rz_values, bc_values) = challenges.split_at(instance_num_vars);
+    let (rb_values, rc_values) = bc_values.split_at(bc_values.len() / 2);
+
+    (rz_values.to_vec(), rb_values.to_vec(), rc_values.to_vec())
+}
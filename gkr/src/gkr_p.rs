@@ -1,16 +1,22 @@
-use crate::circuits::circuit::Circuit;
+use crate::circuits::circuit::{
+    pad_to_boolean_hypercube, pad_to_num_vars, Circuit, CircuitEvaluationResult, LayerPredicates,
+};
 use crate::evaluation::MultilinearPolynomialEV;
 use crate::gkr_sumcheck_dependencies::transcript::Transcript;
 use crate::sum_check::{
-    field_element_to_bytes, prove as sumcheck_prove, verify as sumcheck_verify, SumcheckProverProof,
+    field_element_to_bytes, prove as sumcheck_prove, univariate_to_bytes,
+    verify as sumcheck_verify, SumcheckProverProof,
 };
 use ark_ff::PrimeField;
+use rand::rngs::OsRng;
 
 use crate::wb_bc_utility::{
-    compute_fbc_polynomial, compute_new_add_i_mul_i, compute_verifier_folded_claim,
-    compute_verifier_initial_claim, evaluate_wb_wc, Evaluations, SumcheckChallenges,
+    compute_layers_fbc_polynomial, compute_verifier_layers_claim, curve_through_points,
+    evaluate_restriction, restrict_to_curve, split_padded_point,
 };
+use std::collections::{BTreeMap, BTreeSet};
 
+// wb_evaluations, wc_evaluations and source_evaluations are laid out as in ReduceProof
 #[derive(Clone, Debug)]
 pub struct Proof<F: PrimeField> {
     pub circuit_output: Vec<F>,
@@ -18,208 +24,1064 @@ pub struct Proof<F: PrimeField> {
     pub sumcheck_proofs: Vec<SumcheckProverProof<F>>,
     pub wb_evaluations: Vec<F>,
     pub wc_evaluations: Vec<F>,
+    pub source_evaluations: Vec<(Vec<F>, Vec<F>)>,
+    pub multi_input_evaluations: Vec<Vec<F>>,
+    pub line_restrictions: Vec<Vec<F>>,
+}
+
+// How the claims left on a layer (W(rb) = wb and W(rc) = wc, plus the skip connection claims) are reduced to one:
+// RandomLinearCombination: the claims are combined as alpha * W(rb) + beta * W(rc), and the next sumcheck proves the combination.
+// LineRestriction: the original GKR reduction, the prover sends q(t) = W(l(t)) where l is the line through rb and rc
+// (the curve through all the points when there are more than two claims), and the verifier picks a random point on it.
+// The line restriction makes the proof bigger, but the next sumcheck only has to handle a single claim
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ClaimCombination {
+    #[default]
+    RandomLinearCombination,
+    LineRestriction,
+}
+
+// A claim that the multilinear extension of a layer evaluates to `value` at `point`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Claim<F: PrimeField> {
+    pub point: Vec<F>,
+    pub value: F,
+}
+
+// A random linear combination of claims on the same layer:
+// sum of weight_k * W(point_k) = claimed_sum
+// This is what the sumcheck of a layer starts from, and what it leaves behind for the layer below
+#[derive(Clone, Debug, PartialEq)]
+pub struct CombinedClaim<F: PrimeField> {
+    pub weighted_points: Vec<(F, Vec<F>)>,
+    pub claimed_sum: F,
+}
+
+impl<F: PrimeField> From<&Claim<F>> for CombinedClaim<F> {
+    fn from(claim: &Claim<F>) -> Self {
+        Self {
+            weighted_points: vec![(F::one(), claim.point.clone())],
+            claimed_sum: claim.value,
+        }
+    }
+}
+
+// The part of the GKR proof that reduces a claim on the output layer to claims on the input layer
+// wb_evaluations and wc_evaluations hold one evaluation per layer, the last ones are the claimed input layer evaluations:
+// that is when every layer reads its left inputs from a single layer and its right inputs from a single layer, eg: without skip connections.
+// Otherwise they are empty, and source_evaluations[i] holds the evaluations at rb of the layers read as left inputs by the layer i,
+// and at rc of the layers read as right inputs, in increasing layer order.
+// multi_input_evaluations[i] holds the evaluations of the layer below at the input blocks after rb and rc,
+// only used when the layer has multiplication gates or custom gates with more than two inputs
+// line_restrictions holds the restriction sent for every layer with several claims, only used with ClaimCombination::LineRestriction
+#[derive(Clone, Debug)]
+pub struct ReduceProof<F: PrimeField> {
+    pub sumcheck_proofs: Vec<SumcheckProverProof<F>>,
+    pub wb_evaluations: Vec<F>,
+    pub wc_evaluations: Vec<F>,
+    pub source_evaluations: Vec<(Vec<F>, Vec<F>)>,
+    pub multi_input_evaluations: Vec<Vec<F>>,
+    pub line_restrictions: Vec<Vec<F>>,
+}
+
+// A Proof or a ReduceProof, borrowed by the verifier of the layers
+#[derive(Clone, Copy)]
+struct LayerProofs<'a, F: PrimeField> {
+    sumcheck_proofs: &'a [SumcheckProverProof<F>],
+    wb_evaluations: &'a [F],
+    wc_evaluations: &'a [F],
+    source_evaluations: &'a [(Vec<F>, Vec<F>)],
+    multi_input_evaluations: &'a [Vec<F>],
+    line_restrictions: &'a [Vec<F>],
+}
+
+impl<'a, F: PrimeField> From<&'a Proof<F>> for LayerProofs<'a, F> {
+    fn from(proof: &'a Proof<F>) -> Self {
+        Self {
+            sumcheck_proofs: &proof.sumcheck_proofs,
+            wb_evaluations: &proof.wb_evaluations,
+            wc_evaluations: &proof.wc_evaluations,
+            source_evaluations: &proof.source_evaluations,
+            multi_input_evaluations: &proof.multi_input_evaluations,
+            line_restrictions: &proof.line_restrictions,
+        }
+    }
+}
+
+impl<'a, F: PrimeField> From<&'a ReduceProof<F>> for LayerProofs<'a, F> {
+    fn from(proof: &'a ReduceProof<F>) -> Self {
+        Self {
+            sumcheck_proofs: &proof.sumcheck_proofs,
+            wb_evaluations: &proof.wb_evaluations,
+            wc_evaluations: &proof.wc_evaluations,
+            source_evaluations: &proof.source_evaluations,
+            multi_input_evaluations: &proof.multi_input_evaluations,
+            line_restrictions: &proof.line_restrictions,
+        }
+    }
+}
+
+// Proof for a chain of circuits, where the outputs of circuits[k] are the inputs of circuits[k + 1]
+// Only the output of the last circuit is revealed, circuit_proofs[k] is the reduction proof of circuits[k]
+#[derive(Clone, Debug)]
+pub struct ChainProof<F: PrimeField> {
+    pub circuit_output: Vec<F>,
+    pub circuit_proofs: Vec<ReduceProof<F>>,
+}
+
+// The rejection of a batch of proofs
+#[derive(Clone, Debug, PartialEq)]
+pub enum BatchVerificationError {
+    // The batch needs one list of inputs per proof
+    LengthMismatch { proofs: usize, inputs: usize },
+    // The positions of the proofs that failed in the batch
+    InvalidProofs { failed_proofs: Vec<usize> },
+}
+
+impl std::fmt::Display for BatchVerificationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchVerificationError::LengthMismatch { proofs, inputs } => {
+                write!(f, "{} proofs for {} lists of inputs", proofs, inputs)
+            }
+            BatchVerificationError::InvalidProofs { failed_proofs } => {
+                write!(f, "invalid proofs at positions {:?}", failed_proofs)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BatchVerificationError {}
+
+// What the verifier derives from the circuit alone for a layer, the same for every proof:
+// the wiring predicates, the layers read at every block of input variables, and the shape of the sumcheck
+pub(crate) struct VerifierLayer<F: PrimeField> {
+    pub(crate) predicates: LayerPredicates<F>,
+    pub(crate) block_layers: Vec<Vec<usize>>,
+    pub(crate) input_num_vars: usize,
+    pub(crate) arity: usize,
+    pub(crate) layer_below: usize,
 }
 
 /// This function is called by the prover : It handles the proving part of the GKR protocol
 pub fn prove<F: PrimeField>(circuit: &mut Circuit<F>, inputs: &[F]) -> Proof<F> {
+    prove_with_combination(circuit, inputs, ClaimCombination::default())
+}
+
+pub fn verify<F: PrimeField>(circuit: &mut Circuit<F>, proof: Proof<F>, inputs: &[F]) -> bool {
+    verify_with_combination(circuit, proof, inputs, ClaimCombination::default())
+}
+
+/// Same as prove, with the claims on every layer reduced using the given claim combination.
+/// The verifier must use the same claim combination
+pub fn prove_with_combination<F: PrimeField>(
+    circuit: &mut Circuit<F>,
+    inputs: &[F],
+    claim_combination: ClaimCombination,
+) -> Proof<F> {
+    assert!(
+        inputs.len() <= 1 << circuit.input_num_vars(),
+        "more inputs than the circuit reads"
+    );
+
     let circuit_evaluation = circuit.evaluate(inputs.to_vec());
 
     let mut transcript = Transcript::new();
-    let mut layer_proofs = Vec::new();
-    let mut wb_evaluations = Vec::new();
-    let mut wc_evaluations = Vec::new();
-    let mut alpha = F::zero();
-    let mut beta = F::zero();
-    let mut rb_values = Vec::new();
-    let mut rc_values = Vec::new();
+    let output_claim = output_claim(&circuit_evaluation.output, &mut transcript);
+
+    let (reduce_proof, _input_claims, _) = prove_layers(
+        circuit,
+        &circuit_evaluation,
+        &CombinedClaim::from(&output_claim),
+        claim_combination,
+        &mut transcript,
+    );
+
+    Proof {
+        circuit_output: circuit_evaluation.output,
+        claimed_sum: output_claim.value,
+        sumcheck_proofs: reduce_proof.sumcheck_proofs,
+        wb_evaluations: reduce_proof.wb_evaluations,
+        wc_evaluations: reduce_proof.wc_evaluations,
+        source_evaluations: reduce_proof.source_evaluations,
+        multi_input_evaluations: reduce_proof.multi_input_evaluations,
+        line_restrictions: reduce_proof.line_restrictions,
+    }
+}
+
+pub fn verify_with_combination<F: PrimeField>(
+    circuit: &mut Circuit<F>,
+    proof: Proof<F>,
+    inputs: &[F],
+    claim_combination: ClaimCombination,
+) -> bool {
+    if inputs.len() > 1 << circuit.input_num_vars() {
+        return false;
+    }
+
+    let mut transcript = Transcript::new();
+    let output_claim = output_claim(&proof.circuit_output, &mut transcript);
+
+    let input_claims = match verify_layers(
+        circuit,
+        &verifier_layers(circuit),
+        &CombinedClaim::from(&output_claim),
+        LayerProofs::from(&proof),
+        claim_combination,
+        &mut transcript,
+    ) {
+        Some((input_claims, _)) => input_claims,
+        None => return false,
+    };
+
+    check_input_claims(circuit, &input_claims, inputs)
+}
+
+/// Verifies many proofs for the same circuit, proofs[k] being the proof for inputs[k].
+/// The wiring predicates are built once for the whole batch instead of once per proof,
+/// and the claims left on the input layer by every proof are checked together in one random linear combination.
+/// When the batch is rejected, the error holds the positions of the proofs that failed
+pub fn verify_batch<F: PrimeField>(
+    circuit: &mut Circuit<F>,
+    proofs: &[Proof<F>],
+    inputs: &[Vec<F>],
+) -> Result<(), BatchVerificationError> {
+    if proofs.len() != inputs.len() {
+        return Err(BatchVerificationError::LengthMismatch {
+            proofs: proofs.len(),
+            inputs: inputs.len(),
+        });
+    }
+
+    let verifier_layers = verifier_layers(circuit);
+    let mut failed_proofs = Vec::new();
+    let mut input_checks = Vec::with_capacity(proofs.len());
+
+    for (index, (proof, inputs)) in proofs.iter().zip(inputs.iter()).enumerate() {
+        if inputs.len() > 1 << circuit.input_num_vars() {
+            failed_proofs.push(index);
+            continue;
+        }
+
+        let mut transcript = Transcript::new();
+        let output_claim = output_claim(&proof.circuit_output, &mut transcript);
+
+        match verify_layers(
+            circuit,
+            &verifier_layers,
+            &CombinedClaim::from(&output_claim),
+            LayerProofs::from(proof),
+            ClaimCombination::default(),
+            &mut transcript,
+        ) {
+            Some((claims, combined_claim)) => input_checks.push(InputCheck {
+                index,
+                claims,
+                combined_claim,
+                inputs,
+            }),
+            None => failed_proofs.push(index),
+        }
+    }
+
+    // The input claims are only checked one by one to find the proofs that failed the combined check
+    if !check_batch_input_claims(&input_checks) {
+        failed_proofs.extend(
+            input_checks
+                .iter()
+                .filter(|check| !check_input_claims(circuit, &check.claims, check.inputs))
+                .map(|check| check.index),
+        );
+        failed_proofs.sort_unstable();
+    }
+
+    if failed_proofs.is_empty() {
+        Ok(())
+    } else {
+        Err(BatchVerificationError::InvalidProofs { failed_proofs })
+    }
+}
+
+/// Proves a pipeline of circuits in one transcript: the outputs of circuits[k] are fed as the inputs of circuits[k + 1].
+/// The claims are carried from the inputs of one circuit to the outputs of the circuit before it,
+/// so the intermediate outputs are never sent to the verifier.
+pub fn prove_chain<F: PrimeField>(circuits: &mut [Circuit<F>], inputs: &[F]) -> ChainProof<F> {
+    assert!(
+        chain_widths_match(circuits),
+        "the outputs of every circuit must have the same number of variables as the inputs of the next circuit"
+    );
+    assert!(
+        inputs.len() <= 1 << circuits[0].input_num_vars(),
+        "more inputs than the circuit reads"
+    );
+
+    let mut circuit_evaluations = Vec::with_capacity(circuits.len());
+    let mut current_inputs = inputs.to_vec();
+
+    for circuit in circuits.iter_mut() {
+        let circuit_evaluation = circuit.evaluate(current_inputs);
+        current_inputs = circuit_evaluation.output.clone();
+        circuit_evaluations.push(circuit_evaluation);
+    }
+
+    let mut transcript = Transcript::new();
+    let mut combined_claim = CombinedClaim::from(&output_claim(&current_inputs, &mut transcript));
+    let mut circuit_proofs = Vec::with_capacity(circuits.len());
 
-    // handling layer 0 computation
-    let mut w0_polynomial = Circuit::w_i_polynomial(&circuit_evaluation, 0);
+    // Starting from the last circuit, the claims left on the inputs of a circuit are claims on the outputs of the previous one
+    for (circuit, circuit_evaluation) in circuits.iter_mut().zip(circuit_evaluations.iter()).rev() {
+        let (reduce_proof, _input_claims, next_combined_claim) = prove_layers(
+            circuit,
+            circuit_evaluation,
+            &combined_claim,
+            ClaimCombination::default(),
+            &mut transcript,
+        );
 
-    // Checking to make sure the length of the output array from the circuit evaluation is not equal to 1
-    // if the length is 1, we pad it with a 0, so that it can represent a proper polynomial in evaluation form
-    if w0_polynomial.evaluated_values.len() == 1 {
-        let mut w0_padded_with_zero = w0_polynomial.evaluated_values;
-        w0_padded_with_zero.push(F::zero());
-        w0_polynomial = MultilinearPolynomialEV::new(&w0_padded_with_zero);
+        circuit_proofs.push(reduce_proof);
+        combined_claim = next_combined_claim;
     }
 
+    circuit_proofs.reverse();
+
+    ChainProof {
+        circuit_output: current_inputs,
+        circuit_proofs,
+    }
+}
+
+pub fn verify_chain<F: PrimeField>(
+    circuits: &mut [Circuit<F>],
+    proof: &ChainProof<F>,
+    inputs: &[F],
+) -> bool {
+    if circuits.is_empty()
+        || proof.circuit_proofs.len() != circuits.len()
+        || !chain_widths_match(circuits)
+        || inputs.len() > 1 << circuits[0].input_num_vars()
+    {
+        return false;
+    }
+
+    let mut transcript = Transcript::new();
+    let mut combined_claim =
+        CombinedClaim::from(&output_claim(&proof.circuit_output, &mut transcript));
+    let mut input_claims = Vec::new();
+
+    for (circuit, reduce_proof) in circuits.iter_mut().zip(proof.circuit_proofs.iter()).rev() {
+        match verify_layers(
+            circuit,
+            &verifier_layers(circuit),
+            &combined_claim,
+            LayerProofs::from(reduce_proof),
+            ClaimCombination::default(),
+            &mut transcript,
+        ) {
+            Some((current_input_claims, next_combined_claim)) => {
+                input_claims = current_input_claims;
+                combined_claim = next_combined_claim;
+            }
+            None => return false,
+        }
+    }
+
+    check_input_claims(&circuits[0], &input_claims, inputs)
+}
+
+// The output of the circuit is padded with zeros to a power of two (at least 2 values),
+// so that it can represent a proper polynomial in evaluation form.
+// The output polynomial is absorbed and evaluated at ra -> one random challenge for every variable
+pub fn output_claim<F: PrimeField>(output: &[F], transcript: &mut Transcript) -> Claim<F> {
+    let w0_polynomial = pad_to_boolean_hypercube(output);
+
     transcript.absorb(&w0_polynomial.convert_to_bytes());
-    let random_challenge_a: F = transcript.random_challenge_as_field_element(); // ra -> first random challenge
-    let mut claimed_sum = w0_polynomial.evaluate(&[random_challenge_a]); // m0 -> evaluation of the output polynomial at ra
+    let random_challenges_a: Vec<F> = (0..w0_polynomial.number_of_variables())
+        .map(|_| transcript.random_challenge_as_field_element())
+        .collect();
 
-    // This is where the proving begins: //
-    // We are checking the layer index, to determine how we handle proving
-    // For layer_index 0, we perform a normal partial evaluation on the add_i_abc and mul_i_abc to remove the variable "a"
-    // But for subsequent layers > 0, we use the alpha beta folding to compute compute add_i_bc and mul_i_bc, removing "a" from add_i_abc and mul_i_abc
-    for layer_index in 0..circuit.layers.len() {
-        let (add_i_abc_polynomial, mul_i_abc_polynomial) = circuit.add_i_and_mul_i_mle(layer_index);
+    Claim {
+        value: w0_polynomial.evaluate(&random_challenges_a), // m0 -> evaluation of the output polynomial at ra
+        point: random_challenges_a,
+    }
+}
 
-        let (add_i_bc, mul_i_bc) = if layer_index == 0 {
-            (
-                MultilinearPolynomialEV::partial_evaluate(
-                    &add_i_abc_polynomial.evaluated_values,
-                    0,
-                    random_challenge_a,
-                ),
-                MultilinearPolynomialEV::partial_evaluate(
-                    &mul_i_abc_polynomial.evaluated_values,
-                    0,
-                    random_challenge_a,
+/// Runs the layer by layer GKR reduction on an external transcript:
+/// starting from a claim on the output layer, it returns the claims on the input layer (at rb and rc of every layer reading it)
+/// that the caller is left to check, eg: against a commitment to the inputs, or as the input of another sumcheck
+pub fn prove_reduce<F: PrimeField>(
+    circuit: &mut Circuit<F>,
+    circuit_evaluation: &CircuitEvaluationResult<F>,
+    output_claim: &Claim<F>,
+    transcript: &mut Transcript,
+) -> (ReduceProof<F>, Vec<Claim<F>>) {
+    assert_eq!(
+        output_claim.point.len(),
+        circuit.layer_output_num_vars(0),
+        "output claim point has the wrong number of variables"
+    );
+
+    let (reduce_proof, input_claims, _) = prove_layers(
+        circuit,
+        circuit_evaluation,
+        &CombinedClaim::from(output_claim),
+        ClaimCombination::default(),
+        transcript,
+    );
+
+    (reduce_proof, input_claims)
+}
+
+/// Verifies the layer by layer GKR reduction on an external transcript
+/// It returns the claims on the input layer, which the caller must check, or None if any layer fails
+pub fn verify_reduce<F: PrimeField>(
+    circuit: &mut Circuit<F>,
+    output_claim: &Claim<F>,
+    proof: &ReduceProof<F>,
+    transcript: &mut Transcript,
+) -> Option<Vec<Claim<F>>> {
+    if output_claim.point.len() != circuit.layer_output_num_vars(0) {
+        return None;
+    }
+
+    verify_layers(
+        circuit,
+        &verifier_layers(circuit),
+        &CombinedClaim::from(output_claim),
+        LayerProofs::from(proof),
+        ClaimCombination::default(),
+        transcript,
+    )
+    .map(|(input_claims, _)| input_claims)
+}
+
+// Proves every layer of the circuit, starting from a combined claim on its output layer
+// Returns the proof, the claims on the input layer and their random linear combination
+fn prove_layers<F: PrimeField>(
+    circuit: &mut Circuit<F>,
+    circuit_evaluation: &CircuitEvaluationResult<F>,
+    output_claim: &CombinedClaim<F>,
+    claim_combination: ClaimCombination,
+    transcript: &mut Transcript,
+) -> (ReduceProof<F>, Vec<Claim<F>>, CombinedClaim<F>) {
+    let number_of_layers = circuit.layers.len();
+    let mut layer_proofs = Vec::new();
+    let mut source_evaluations = Vec::new();
+    let mut multi_input_evaluations = Vec::new();
+    let mut line_restrictions = Vec::new();
+    let mut combined_claim = output_claim.clone();
+
+    // layer_claims[j] collects the claims made on the layer j by the layers that read from it.
+    // Without skip connections, these are the two claims at rb and rc made by the layer directly above
+    let mut layer_claims: Vec<Vec<Claim<F>>> = vec![Vec::new(); number_of_layers + 1];
+
+    // This is where the proving begins: //
+    // For every layer, the wiring predicates add_i_abc and mul_i_abc are combined according to the claims on the layer:
+    // For the output layer (a single claim), this is a normal partial evaluation on the add_i_abc and mul_i_abc to remove the variable "a"
+    // But for subsequent layers, we use a random linear combination of all the claims on the layer to compute add_i_bc and mul_i_bc
+    for layer_index in 0..number_of_layers {
+        if layer_index > 0 {
+            assert!(
+                !layer_claims[layer_index].is_empty(),
+                "every layer must be read by a layer above it"
+            );
+            combined_claim = prove_claim_combination(
+                &layer_claims[layer_index],
+                &pad_to_num_vars(
+                    &circuit_evaluation.layer_evaluations[layer_index],
+                    circuit.layer_num_vars(layer_index),
                 ),
-            )
-        } else {
-            compute_new_add_i_mul_i(
-                alpha,
-                beta,
-                add_i_abc_polynomial,
-                mul_i_abc_polynomial,
-                &rb_values,
-                &rc_values,
-            )
-        };
+                claim_combination,
+                &mut line_restrictions,
+                transcript,
+            );
+        }
 
         // The wb_poly and wc_poly are the w-polynomials that makes up the inputs to the gates of the current layer, ...
-        // ... which means, it comes from the layer below the current layer.
+        // ... which means, they come from the layers below the current layer.
         // Layer in this case is the layers that makes up the circuit evaluations
-        // To get the layer below the current layer, we add 1 to the current layer index
-        let wb_poly = Circuit::w_i_polynomial(&circuit_evaluation, layer_index + 1);
-        let wc_poly = wb_poly.clone();
+        // Every layer the gates read from is padded to the same number of variables, so they can share the b and c variables
+        let arity = circuit.layer_arity(layer_index);
+        let predicates = circuit.layer_predicates(layer_index);
+        let source_polynomials = source_polynomials(circuit, circuit_evaluation, layer_index);
 
         // The f(b,c) polynomial is what we need to perform sumcheck: because we now have a sumcheck problem
         // A sumcheck problem is when we have a claimed_sum, and a polynomial that when evaluated we get the claim
-        // We are trying to prove that the f(b,c) polynomial, when computed using the w-polynomials of the layer below and evaluated,
+        // We are trying to prove that the f(b,c) polynomial, when computed using the w-polynomials of the layers below and evaluated,
         // will be equal to the claimed_sum
-        let fbc_polynomial = compute_fbc_polynomial(add_i_bc, mul_i_bc, &wb_poly, &wc_poly);
+        let fbc_polynomial = compute_layers_fbc_polynomial(
+            &predicates,
+            &combined_claim.weighted_points,
+            &source_polynomials,
+            layer_index + 1,
+            arity,
+        );
 
         // The sumcheck protocol here is specially implemented for GKR. => It takes in the f(b,c) polynomial, the claimed sum and the transcript
-        // NOTE: This sumcheck runs on the f(b,c) polynomial => Which is a SumPolynomial of two ProductPolynomial
-        let sumcheck_proof = sumcheck_prove(fbc_polynomial, claimed_sum, &mut transcript);
+        // NOTE: This sumcheck runs on the f(b,c) polynomial => Which is a SumPolynomial of ProductPolynomials
+        let sumcheck_proof = sumcheck_prove(fbc_polynomial, combined_claim.claimed_sum, transcript);
         layer_proofs.push(sumcheck_proof.clone());
 
-        // In the following code blocks, we are sending the evaluation of the w-polynomials (wb and wc)
-        // Since the verifier doesn't need to know the w-polynomials of the subsequent evaluation layers of the circuit ...
-        // ... because the verifier knows the output and the input, but the verifier still needs to verify these layers,
-        // the prover will send the wb and wc evaluation of the layers between the output and input of the circuit
+        // In the following code blocks, we are sending the evaluations of the w-polynomials (wb and wc)
+        // The verifier doesn't know the w-polynomials of the layers below the output,
+        // so the prover sends the evaluation of every layer read as a left input at rb, and as a right input at rc
+        // (and of the layer below at the other blocks of challenges, for the gates with more than two inputs).
+        // Each one becomes a claim on that layer, which is proven when the sumcheck reaches it
+        let sumcheck_challenges = sumcheck_proof.random_challenges;
+        let block_layers = block_source_layers(&predicates, layer_index, arity);
 
-        // We are also ensuring that the prover doesn't send the evaluation of the input layer, because the verifier knows the input
-        // that is why we are only evaluating w-polynomials for circuit_layer_length - 1
-        if layer_index < circuit.layers.len() - 1 {
-            let sumcheck_challenges = sumcheck_proof.random_challenges;
+        let block_evaluations = source_block_evaluations(
+            circuit,
+            circuit_evaluation,
+            &block_layers,
+            &sumcheck_challenges,
+        );
 
-            // Evaluate wb and wc to be used by verifier
-            let (wb_evaluation, wc_evaluation) =
-                evaluate_wb_wc(&wb_poly, &wc_poly, &sumcheck_challenges);
+        record_layer_claims(
+            circuit,
+            &sumcheck_challenges,
+            &block_layers,
+            &block_evaluations,
+            &mut layer_claims,
+            transcript,
+        );
 
-            wb_evaluations.push(wb_evaluation);
-            wc_evaluations.push(wc_evaluation);
+        let mut block_evaluations = block_evaluations.into_iter();
+        source_evaluations.push((
+            block_evaluations.next().unwrap_or_default(),
+            block_evaluations.next().unwrap_or_default(),
+        ));
+        multi_input_evaluations.push(block_evaluations.flatten().collect());
+    }
 
-            // use the randomness from the sumcheck proof, split into two vec! for rb and rc
-            let middle = sumcheck_challenges.len() / 2;
-            let (current_rb_values, current_rc_values) = sumcheck_challenges.split_at(middle);
-            rb_values = current_rb_values.to_vec();
-            rc_values = current_rc_values.to_vec();
+    // The claims left on the input layer are returned to the caller
+    let input_claims = layer_claims.swap_remove(number_of_layers);
+    let input_combined_claim = prove_claim_combination(
+        &input_claims,
+        &pad_to_num_vars(
+            &circuit_evaluation.layer_evaluations[number_of_layers],
+            circuit.input_num_vars(),
+        ),
+        claim_combination,
+        &mut line_restrictions,
+        transcript,
+    );
 
-            transcript.absorb(&field_element_to_bytes(wb_evaluation));
-            alpha = transcript.random_challenge_as_field_element();
+    // Every layer reading a single layer per input gets the flat layout, with one evaluation per layer
+    let single_sources = source_evaluations
+        .iter()
+        .all(|(wb, wc)| wb.len() == 1 && wc.len() == 1);
+    let (wb_evaluations, wc_evaluations) = if single_sources {
+        source_evaluations
+            .drain(..)
+            .map(|(wb, wc)| (wb[0], wc[0]))
+            .unzip()
+    } else {
+        (Vec::new(), Vec::new())
+    };
 
-            transcript.absorb(&field_element_to_bytes(wc_evaluation));
-            beta = transcript.random_challenge_as_field_element();
+    (
+        ReduceProof {
+            sumcheck_proofs: layer_proofs,
+            wb_evaluations,
+            wc_evaluations,
+            source_evaluations,
+            multi_input_evaluations,
+            line_restrictions,
+        },
+        input_claims,
+        input_combined_claim,
+    )
+}
 
-            // Compute claimed sum using linear combination form
-            claimed_sum = (alpha * wb_evaluation) + (beta * wc_evaluation);
-        }
-    }
+// Verifies every layer of the circuit, starting from a combined claim on its output layer
+// Returns the claims on the input layer and their random linear combination
+fn verify_layers<F: PrimeField>(
+    circuit: &Circuit<F>,
+    verifier_layers: &[VerifierLayer<F>],
+    output_claim: &CombinedClaim<F>,
+    proof: LayerProofs<'_, F>,
+    claim_combination: ClaimCombination,
+    transcript: &mut Transcript,
+) -> Option<(Vec<Claim<F>>, CombinedClaim<F>)> {
+    let number_of_layers = circuit.layers.len();
+    let mut line_restrictions = proof.line_restrictions.iter();
 
-    Proof {
-        circuit_output: circuit_evaluation.output,
-        claimed_sum,
-        sumcheck_proofs: layer_proofs,
-        wb_evaluations,
-        wc_evaluations,
-    }
-}
-pub fn verify<F: PrimeField>(circuit: &mut Circuit<F>, proof: Proof<F>, inputs: &[F]) -> bool {
-    let mut transcript = Transcript::new();
-    let mut alpha = F::zero();
-    let mut beta = F::zero();
-    let mut prev_sumcheck_challenges = Vec::new();
-
-    let w0_polynomial = if proof.circuit_output.len() == 1 {
-        let mut w0_padded_with_zero = proof.circuit_output;
-        w0_padded_with_zero.push(F::zero());
-        MultilinearPolynomialEV::new(&w0_padded_with_zero)
+    // Either the flat layout or the per source one, never both
+    let single_sources = proof.source_evaluations.is_empty();
+    let (flat_length, sources_length) = if single_sources {
+        (number_of_layers, 0)
     } else {
-        MultilinearPolynomialEV::new(&proof.circuit_output)
+        (0, number_of_layers)
     };
 
-    transcript.absorb(&w0_polynomial.convert_to_bytes());
-    let random_challenge_a: F = transcript.random_challenge_as_field_element();
+    if proof.sumcheck_proofs.len() != number_of_layers
+        || proof.wb_evaluations.len() != flat_length
+        || proof.wc_evaluations.len() != flat_length
+        || proof.source_evaluations.len() != sources_length
+        || proof.multi_input_evaluations.len() != number_of_layers
+    {
+        return None;
+    }
 
-    let mut claimed_sum = w0_polynomial.evaluate(&[random_challenge_a]);
+    let mut combined_claim = output_claim.clone();
+    let mut layer_claims: Vec<Vec<Claim<F>>> = vec![Vec::new(); number_of_layers + 1];
 
-    for layer_index in 0..circuit.layers.len() {
-        if claimed_sum != proof.sumcheck_proofs[layer_index].claimed_sum {
-            return false;
+    for layer_index in 0..number_of_layers {
+        if layer_index > 0 {
+            if layer_claims[layer_index].is_empty() {
+                return None;
+            }
+            combined_claim = verify_claim_combination(
+                &layer_claims[layer_index],
+                claim_combination,
+                &mut line_restrictions,
+                transcript,
+            )?;
         }
 
-        let verify_result = sumcheck_verify(&proof.sumcheck_proofs[layer_index], &mut transcript);
-        if !verify_result.is_proof_valid {
-            return false;
+        if combined_claim.claimed_sum != proof.sumcheck_proofs[layer_index].claimed_sum {
+            return None;
+        }
+
+        let VerifierLayer {
+            block_layers,
+            input_num_vars,
+            arity,
+            ..
+        } = &verifier_layers[layer_index];
+        let (input_num_vars, arity) = (*input_num_vars, *arity);
+
+        let verify_result = sumcheck_verify(&proof.sumcheck_proofs[layer_index], transcript);
+        if !verify_result.is_proof_valid
+            || verify_result.random_challenges.len() != arity * input_num_vars
+        {
+            return None;
         }
 
         let sumcheck_challenges = verify_result.random_challenges;
 
-        let (wb_evaluation, wc_evaluation) = if layer_index < circuit.layers.len() - 1 {
+        // wb, wc, then one evaluation of the layer below for every other block
+        let (wb_evaluations, wc_evaluations) = if single_sources {
             (
-                proof.wb_evaluations[layer_index],
-                proof.wc_evaluations[layer_index],
+                vec![proof.wb_evaluations[layer_index]],
+                vec![proof.wc_evaluations[layer_index]],
             )
         } else {
-            let wb_poly = MultilinearPolynomialEV::new(inputs);
-            let wc_poly = wb_poly.clone();
-            evaluate_wb_wc(&wb_poly, &wc_poly, &sumcheck_challenges)
+            proof.source_evaluations[layer_index].clone()
         };
-
-        let expected_claim = if layer_index == 0 {
-            compute_verifier_initial_claim(
-                circuit,
-                layer_index,
-                random_challenge_a,
-                &sumcheck_challenges,
-                wb_evaluation,
-                wc_evaluation,
+        let block_evaluations: Vec<Vec<F>> = [wb_evaluations, wc_evaluations]
+            .into_iter()
+            .chain(
+                proof.multi_input_evaluations[layer_index]
+                    .iter()
+                    .map(|evaluation| vec![*evaluation]),
             )
-        } else {
-            let challenges = SumcheckChallenges {
-                current: sumcheck_challenges.clone(),
-                previous: prev_sumcheck_challenges.clone(),
-            };
-
-            let evals = Evaluations {
-                wb: wb_evaluation,
-                wc: wc_evaluation,
-                alpha,
-                beta,
-            };
-
-            compute_verifier_folded_claim(circuit, layer_index, &challenges, &evals)
-        };
+            .collect();
+
+        if block_evaluations.len() != arity
+            || block_evaluations
+                .iter()
+                .zip(block_layers.iter())
+                .any(|(evaluations, source_layers)| evaluations.len() != source_layers.len())
+        {
+            return None;
+        }
+
+        let expected_claim = expected_layer_claim(
+            circuit,
+            &verifier_layers[layer_index],
+            &combined_claim.weighted_points,
+            &sumcheck_challenges,
+            &block_evaluations,
+        );
 
         if expected_claim != verify_result.last_claimed_sum {
-            return false;
+            return None;
+        }
+
+        record_layer_claims(
+            circuit,
+            &sumcheck_challenges,
+            block_layers,
+            &block_evaluations,
+            &mut layer_claims,
+            transcript,
+        );
+    }
+
+    let input_claims = layer_claims.swap_remove(number_of_layers);
+    let input_combined_claim = verify_claim_combination(
+        &input_claims,
+        claim_combination,
+        &mut line_restrictions,
+        transcript,
+    )?;
+
+    // Every line restriction sent by the prover must have been used
+    if line_restrictions.next().is_some() {
+        return None;
+    }
+
+    Some((input_claims, input_combined_claim))
+}
+
+// For every block of input variables, the layers read at that block by the layer at layer_index, sorted and without duplicates:
+// the first block holds the left inputs and the summation inputs, the second one the right inputs.
+// The multiplication gates with more inputs and the custom gates read the layer below at their first fan_in blocks
+fn block_source_layers<F: PrimeField>(
+    predicates: &LayerPredicates<F>,
+    layer_index: usize,
+    arity: usize,
+) -> Vec<Vec<usize>> {
+    let mut block_layers = vec![BTreeSet::new(); arity];
+
+    for predicate in predicates.wiring.iter() {
+        block_layers[0].insert(predicate.left_layer);
+        block_layers[1].insert(predicate.right_layer);
+    }
+
+    let below_fan_ins = predicates
+        .multi_input
+        .iter()
+        .map(|predicate| predicate.fan_in)
+        .chain(
+            predicates
+                .custom
+                .iter()
+                .map(|predicate| predicate.terms[0].exponents.len()),
+        );
+
+    for fan_in in below_fan_ins {
+        for source_layers in block_layers.iter_mut().take(fan_in) {
+            source_layers.insert(layer_index + 1);
+        }
+    }
+
+    block_layers
+        .into_iter()
+        .map(|source_layers| source_layers.into_iter().collect())
+        .collect()
+}
+
+// After the sumcheck of a layer, every layer read as a left input has a claim W(rb) = wb,
+// and every layer read as a right input has a claim W(rc) = wc (rb and rc cut down to the number of variables of that layer),
+// the same goes for the layer below at the other blocks of challenges when there are multiplications with more than two inputs.
+// The evaluations are absorbed into the transcript, and the claims are added to the claims of their layer
+fn record_layer_claims<F: PrimeField>(
+    circuit: &Circuit<F>,
+    sumcheck_challenges: &[F],
+    block_layers: &[Vec<usize>],
+    block_evaluations: &[Vec<F>],
+    layer_claims: &mut [Vec<Claim<F>>],
+    transcript: &mut Transcript,
+) {
+    for evaluation in block_evaluations.iter().flatten() {
+        transcript.absorb(&field_element_to_bytes(*evaluation));
+    }
+
+    push_layer_claims(
+        circuit,
+        sumcheck_challenges,
+        block_layers,
+        block_evaluations,
+        layer_claims,
+    );
+}
+
+// Adds the claims left by the sumcheck of a layer to the claims of the layers it reads from
+pub(crate) fn push_layer_claims<F: PrimeField>(
+    circuit: &Circuit<F>,
+    sumcheck_challenges: &[F],
+    block_layers: &[Vec<usize>],
+    block_evaluations: &[Vec<F>],
+    layer_claims: &mut [Vec<Claim<F>>],
+) {
+    // use the randomness from the sumcheck proof, split into one point per block: rb, rc, ...
+    let block_num_vars = sumcheck_challenges.len() / block_layers.len();
+    let block_points = sumcheck_challenges.chunks(block_num_vars);
+
+    for ((source_layers, evaluations), point) in block_layers
+        .iter()
+        .zip(block_evaluations.iter())
+        .zip(block_points)
+    {
+        for (source_layer, evaluation) in source_layers.iter().zip(evaluations.iter()) {
+            let (_, layer_point) = split_padded_point(point, circuit.layer_num_vars(*source_layer));
+            layer_claims[*source_layer].push(Claim {
+                point: layer_point.to_vec(),
+                value: *evaluation,
+            });
         }
+    }
+}
+
+// The layers the gates of layer_index read from, padded to the number of variables of the blocks of the sumcheck
+pub(crate) fn source_polynomials<F: PrimeField>(
+    circuit: &Circuit<F>,
+    circuit_evaluation: &CircuitEvaluationResult<F>,
+    layer_index: usize,
+) -> BTreeMap<usize, MultilinearPolynomialEV<F>> {
+    let input_num_vars = circuit.layer_input_num_vars(layer_index);
+
+    circuit
+        .source_layers(layer_index)
+        .into_iter()
+        .map(|source_layer| {
+            (
+                source_layer,
+                pad_to_num_vars(
+                    &circuit_evaluation.layer_evaluations[source_layer],
+                    input_num_vars,
+                ),
+            )
+        })
+        .collect()
+}
+
+// The evaluations the prover sends after the sumcheck of a layer: every layer read at a block, at the block's point
+pub(crate) fn source_block_evaluations<F: PrimeField>(
+    circuit: &Circuit<F>,
+    circuit_evaluation: &CircuitEvaluationResult<F>,
+    block_layers: &[Vec<usize>],
+    sumcheck_challenges: &[F],
+) -> Vec<Vec<F>> {
+    let block_num_vars = sumcheck_challenges.len() / block_layers.len();
+
+    block_layers
+        .iter()
+        .zip(sumcheck_challenges.chunks(block_num_vars))
+        .map(|(source_layers, point)| {
+            source_layers
+                .iter()
+                .map(|source_layer| {
+                    let (_, layer_point) =
+                        split_padded_point(point, circuit.layer_num_vars(*source_layer));
+                    pad_to_num_vars(
+                        &circuit_evaluation.layer_evaluations[*source_layer],
+                        layer_point.len(),
+                    )
+                    .evaluate(layer_point)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// What the last round of the sumcheck of a layer must be, from the evaluations sent by the prover
+pub(crate) fn expected_layer_claim<F: PrimeField>(
+    circuit: &Circuit<F>,
+    verifier_layer: &VerifierLayer<F>,
+    weighted_points: &[(F, Vec<F>)],
+    sumcheck_challenges: &[F],
+    block_evaluations: &[Vec<F>],
+) -> F {
+    // The prover sent the evaluations of the layers themselves,
+    // the verifier scales them to get the evaluations of the padded layers used in the sumcheck
+    let padded_evaluations: Vec<BTreeMap<usize, F>> = verifier_layer
+        .block_layers
+        .iter()
+        .zip(block_evaluations.iter())
+        .zip(sumcheck_challenges.chunks(verifier_layer.input_num_vars))
+        .map(|((source_layers, evaluations), point)| {
+            source_layers
+                .iter()
+                .zip(evaluations.iter())
+                .map(|(source_layer, evaluation)| {
+                    let (padding_factor, _) =
+                        split_padded_point(point, circuit.layer_num_vars(*source_layer));
+                    (*source_layer, padding_factor * evaluation)
+                })
+                .collect()
+        })
+        .collect();
+
+    compute_verifier_layers_claim(
+        &verifier_layer.predicates,
+        weighted_points,
+        sumcheck_challenges,
+        &padded_evaluations,
+        verifier_layer.layer_below,
+    )
+}
+
+// All the claims on a layer are combined into one with a random linear combination:
+// without skip connections, these are W(rb) = wb and W(rc) = wc, so the combination is
+// alpha * W(rb) + beta * W(rc) = alpha * wb + beta * wc
+pub(crate) fn combine_layer_claims<F: PrimeField>(
+    claims: &[Claim<F>],
+    transcript: &mut Transcript,
+) -> CombinedClaim<F> {
+    let mut weighted_points = Vec::with_capacity(claims.len());
+    let mut claimed_sum = F::zero();
+
+    for claim in claims.iter() {
+        let weight: F = transcript.random_challenge_as_field_element();
+
+        claimed_sum += weight * claim.value;
+        weighted_points.push((weight, claim.point.clone()));
+    }
+
+    // Compute claimed sum using linear combination form
+    CombinedClaim {
+        weighted_points,
+        claimed_sum,
+    }
+}
 
-        prev_sumcheck_challenges = sumcheck_challenges.to_vec();
+// Reduces the claims on a layer to one, as the prover: layer_polynomial is the (padded) layer the claims are about
+// With the line restriction, the restriction of the layer to the curve through the claim points is sent in line_restrictions
+fn prove_claim_combination<F: PrimeField>(
+    claims: &[Claim<F>],
+    layer_polynomial: &MultilinearPolynomialEV<F>,
+    claim_combination: ClaimCombination,
+    line_restrictions: &mut Vec<Vec<F>>,
+    transcript: &mut Transcript,
+) -> CombinedClaim<F> {
+    if claim_combination == ClaimCombination::RandomLinearCombination || claims.len() == 1 {
+        return combine_layer_claims(claims, transcript);
+    }
 
-        transcript.absorb(&field_element_to_bytes(wb_evaluation));
-        alpha = transcript.random_challenge_as_field_element();
+    let points: Vec<Vec<F>> = claims.iter().map(|claim| claim.point.clone()).collect();
+    let restriction = restrict_to_curve(layer_polynomial, &points);
 
-        transcript.absorb(&field_element_to_bytes(wc_evaluation));
-        beta = transcript.random_challenge_as_field_element();
+    let combined_claim = restrict_claims_to_curve(&points, &restriction, transcript);
+    line_restrictions.push(restriction);
 
-        claimed_sum = (alpha * wb_evaluation) + (beta * wc_evaluation);
+    combined_claim
+}
+
+// Reduces the claims on a layer to one, as the verifier
+// With the line restriction, the next restriction sent by the prover must agree with every claim at its point on the curve
+fn verify_claim_combination<'a, F: PrimeField>(
+    claims: &[Claim<F>],
+    claim_combination: ClaimCombination,
+    line_restrictions: &mut impl Iterator<Item = &'a Vec<F>>,
+    transcript: &mut Transcript,
+) -> Option<CombinedClaim<F>> {
+    if claim_combination == ClaimCombination::RandomLinearCombination || claims.len() == 1 {
+        return Some(combine_layer_claims(claims, transcript));
     }
 
-    true
+    let restriction = line_restrictions.next()?;
+    let degree = (claims.len() - 1) * claims[0].point.len();
+
+    // q(i) = W(curve(i)) = W(point_i), which must be the claimed value
+    if restriction.len() != degree + 1
+        || claims
+            .iter()
+            .enumerate()
+            .any(|(i, claim)| restriction[i] != claim.value)
+    {
+        return None;
+    }
+
+    let points: Vec<Vec<F>> = claims.iter().map(|claim| claim.point.clone()).collect();
+
+    Some(restrict_claims_to_curve(&points, restriction, transcript))
+}
+
+// The restriction is absorbed, and the random t picks the point curve(t) of the single claim W(curve(t)) = q(t)
+fn restrict_claims_to_curve<F: PrimeField>(
+    points: &[Vec<F>],
+    restriction: &[F],
+    transcript: &mut Transcript,
+) -> CombinedClaim<F> {
+    transcript.absorb(&univariate_to_bytes(restriction));
+    let t: F = transcript.random_challenge_as_field_element();
+
+    CombinedClaim {
+        weighted_points: vec![(F::one(), curve_through_points(points, t))],
+        claimed_sum: evaluate_restriction(restriction, t),
+    }
+}
+
+// The verifier knows the inputs, so it checks the input layer claims itself
+fn check_input_claims<F: PrimeField>(
+    circuit: &Circuit<F>,
+    input_claims: &[Claim<F>],
+    inputs: &[F],
+) -> bool {
+    let input_polynomial = pad_input_layer(circuit, inputs);
+
+    input_claims
+        .iter()
+        .all(|claim| input_polynomial.evaluate(&claim.point) == claim.value)
+}
+
+// What is left to check on the input layer for the proof at index of a batch, once its layers are verified
+struct InputCheck<'a, F: PrimeField> {
+    index: usize,
+    claims: Vec<Claim<F>>,
+    combined_claim: CombinedClaim<F>,
+    inputs: &'a [F],
+}
+
+// The input claims of a batch of proofs in one equation. With a weight rho_k for the proof k, drawn by the verifier
+// from the randomness of the operating system once every proof is fixed:
+// sum_k rho_k * sum_j w_kj * input_k(p_kj) = sum_k rho_k * claimed_sum_k
+// where sum_j w_kj * W(p_kj) = claimed_sum_k is the combined claim left on the input layer by the proof k.
+// The weighted eq tables of a proof are summed into one table, so every list of inputs is read once,
+// without padding it: the padding zeros add nothing to the inner product
+fn check_batch_input_claims<F: PrimeField>(input_checks: &[InputCheck<F>]) -> bool {
+    let mut combined_evaluation = F::zero();
+    let mut combined_sum = F::zero();
+
+    for InputCheck {
+        combined_claim,
+        inputs,
+        ..
+    } in input_checks.iter()
+    {
+        let rho = F::rand(&mut OsRng);
+        let mut weighted_eq = vec![F::zero(); inputs.len()];
+
+        for (weight, point) in combined_claim.weighted_points.iter() {
+            let scale = rho * weight;
+            let eq_values = MultilinearPolynomialEV::eq_polynomial(point).evaluated_values;
+
+            for (sum, eq_value) in weighted_eq.iter_mut().zip(eq_values.iter()) {
+                *sum += scale * eq_value;
+            }
+        }
+
+        combined_evaluation += weighted_eq
+            .iter()
+            .zip(inputs.iter())
+            .map(|(eq_value, input)| *eq_value * input)
+            .sum::<F>();
+        combined_sum += rho * combined_claim.claimed_sum;
+    }
+
+    combined_evaluation == combined_sum
+}
+
+pub(crate) fn verifier_layers<F: PrimeField>(circuit: &Circuit<F>) -> Vec<VerifierLayer<F>> {
+    (0..circuit.layers.len())
+        .map(|layer_index| {
+            let predicates = circuit.layer_predicates(layer_index);
+            let arity = circuit.layer_arity(layer_index);
+
+            VerifierLayer {
+                block_layers: block_source_layers(&predicates, layer_index, arity),
+                predicates,
+                input_num_vars: circuit.layer_input_num_vars(layer_index),
+                arity,
+                layer_below: layer_index + 1,
+            }
+        })
+        .collect()
+}
+
+fn chain_widths_match<F: PrimeField>(circuits: &[Circuit<F>]) -> bool {
+    circuits
+        .windows(2)
+        .all(|pair| pair[0].layer_output_num_vars(0) == pair[1].input_num_vars())
+}
+
+// The verifier's view of the input layer: the inputs padded with zeros to the width the circuit reads
+pub fn pad_input_layer<F: PrimeField>(
+    circuit: &Circuit<F>,
+    inputs: &[F],
+) -> MultilinearPolynomialEV<F> {
+    pad_to_num_vars(inputs, circuit.input_num_vars())
 }
@@ -56,8 +56,8 @@ impl<F: PrimeField> SumPolynomial<F> {
     // to one Polynomial by basically performing element-wise addition
     pub fn add_polynomials_element_wise(&self) -> MultilinearPolynomialEV<F> {
         assert!(
-            self.product_polynomials.len() > 1,
-            "more than one product polynomial required for add operation"
+            !self.product_polynomials.is_empty(),
+            "at least one product polynomial required for add operation"
         );
 
         let first_product = self.product_polynomials[0].multiply_polynomials_element_wise();
@@ -85,8 +85,13 @@ impl<F: PrimeField> SumPolynomial<F> {
         bytes
     }
 
+    // The degree of the sum is the degree of its highest degree product polynomial
     pub fn degree(&self) -> usize {
-        self.product_polynomials[0].degree()
+        self.product_polynomials
+            .iter()
+            .map(|product_polynomial| product_polynomial.degree())
+            .max()
+            .unwrap_or(0)
     }
 
     pub fn number_of_variables(&self) -> u32 {
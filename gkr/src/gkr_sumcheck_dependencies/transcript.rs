@@ -1,5 +1,6 @@
+use crate::binary_field::BinaryField128b;
 use crate::traits::HashTrait;
-use ark_ff::PrimeField;
+use ark_ff::{Field, PrimeField};
 use sha3::{Digest, Keccak256};
 
 #[derive(Debug, Clone)]
@@ -41,19 +42,41 @@ impl Transcript {
         output_hash
     }
 
-    pub(crate) fn random_challenge_as_field_element<F: PrimeField>(&mut self) -> F {
+    pub fn random_challenge_as_field_element<F: PrimeField>(&mut self) -> F {
         let random_challenge = self.sample_random_challenge();
 
         // convert bytes into field element using: from_bytes_mod_order()
         F::from_le_bytes_mod_order(&random_challenge)
     }
-}
 
-impl HashTrait for Keccak256 {
-    fn append(&mut self, data: &[u8]) {
-        self.update(data);
+    // Samples a challenge in an extension field, one base field challenge per coefficient:
+    // for small base fields (eg: 64 or 31 bits), challenges from the base field are not sound
+    pub fn random_challenge_as_extension_element<E: Field>(&mut self) -> E {
+        let coefficients: Vec<E::BasePrimeField> = (0..E::extension_degree())
+            .map(|_| self.random_challenge_as_field_element())
+            .collect();
+
+        E::from_base_prime_field_elems(coefficients)
+            .expect("one coefficient per degree of the extension")
     }
-    fn generate_hash(&self) -> Vec<u8> {
-        self.clone().finalize().to_vec()
+
+    // Samples a challenge in the 128 bits binary tower field, from the first 16 bytes of the hash
+    pub fn random_challenge_as_binary_element(&mut self) -> BinaryField128b {
+        let random_challenge = self.sample_random_challenge();
+
+        let mut challenge_bytes = [0; 16];
+        challenge_bytes.copy_from_slice(&random_challenge[..16]);
+
+        BinaryField128b::new(u128::from_le_bytes(challenge_bytes))
+    }
+
+    // Samples an index in the range 0..bound, used when the verifier needs to pick positions (eg: columns to open)
+    pub(crate) fn random_challenge_as_index(&mut self, bound: usize) -> usize {
+        let random_challenge = self.sample_random_challenge();
+
+        let mut index_bytes = [0; 8];
+        index_bytes.copy_from_slice(&random_challenge[..8]);
+
+        (u64::from_le_bytes(index_bytes) % bound as u64) as usize
     }
 }
@@ -0,0 +1,270 @@
+use crate::evaluation::MultilinearPolynomialEV;
+use crate::gkr_p::Claim;
+use crate::gkr_sumcheck_dependencies::poly_sum::SumPolynomial;
+use crate::gkr_sumcheck_dependencies::transcript::Transcript;
+use crate::product_poly::ProductPolynomial;
+use crate::sum_check::{
+    absorb_elements, prove as sumcheck_prove, verify as sumcheck_verify, SumcheckProverProof,
+};
+use crate::utility::utils::eq_evaluation;
+use ark_ff::PrimeField;
+
+// Grand product argument: the product of all the evaluations of a multilinear polynomial over the boolean hypercube,
+// computed by a binary tree of multiplications (a layered GKR circuit with a single gate type).
+// Layer i of the tree holds 2^i values: V_i(x) = V_{i+1}(x, 0) * V_{i+1}(x, 1), the root is the product.
+// The claim V_i(r) is reduced to the layer below with a degree 3 sum-check of
+// sum over x of eq(r, x) * V_{i+1}(x, 0) * V_{i+1}(x, 1), which leaves the claims V_{i+1}(r', 0) and V_{i+1}(r', 1),
+// merged into the claim V_{i+1}(r', c) = (1 - c) * V_{i+1}(r', 0) + c * V_{i+1}(r', 1) for a random c.
+// After the last layer, the claim is on the leaves, returned to be checked by the caller.
+// Several trees with the same number of leaves are proven together: the claims of every layer are combined
+// with the powers of a random alpha into a single sum-check, and the claims on the leaves share their point.
+
+// The reduction of the claims on a layer of the trees to the layer below
+#[derive(Clone, Debug)]
+pub struct ProductLayerProof<F: PrimeField> {
+    pub sumcheck_proof: SumcheckProverProof<F>,
+    // The evaluations of the even and odd halves of the layer below, for every tree
+    pub left_evaluations: Vec<F>,
+    pub right_evaluations: Vec<F>,
+}
+
+#[derive(Clone, Debug)]
+pub struct GrandProductProof<F: PrimeField> {
+    pub product: F,
+    pub layer_proofs: Vec<ProductLayerProof<F>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct BatchGrandProductProof<F: PrimeField> {
+    pub products: Vec<F>,
+    pub layer_proofs: Vec<ProductLayerProof<F>>,
+}
+
+// Proves the product of the evaluations of leaves, returns the proof and the claim on leaves
+pub fn prove<F: PrimeField>(
+    leaves: &MultilinearPolynomialEV<F>,
+    transcript: &mut Transcript,
+) -> (GrandProductProof<F>, Claim<F>) {
+    let (proof, mut claims) = prove_batch(std::slice::from_ref(leaves), transcript);
+
+    (
+        GrandProductProof {
+            product: proof.products[0],
+            layer_proofs: proof.layer_proofs,
+        },
+        claims.remove(0),
+    )
+}
+
+// Returns the claim on the leaves when the proof reduces proof.product to it, None otherwise.
+// The caller compares proof.product to the product it expects
+pub fn verify<F: PrimeField>(
+    proof: &GrandProductProof<F>,
+    num_vars: usize,
+    transcript: &mut Transcript,
+) -> Option<Claim<F>> {
+    let batch_proof = BatchGrandProductProof {
+        products: vec![proof.product],
+        layer_proofs: proof.layer_proofs.clone(),
+    };
+
+    verify_batch(&batch_proof, num_vars, transcript).map(|mut claims| claims.remove(0))
+}
+
+// Proves the products of several polynomials with the same number of variables,
+// the claims on the leaves are at the same point
+pub fn prove_batch<F: PrimeField>(
+    leaves: &[MultilinearPolynomialEV<F>],
+    transcript: &mut Transcript,
+) -> (BatchGrandProductProof<F>, Vec<Claim<F>>) {
+    assert!(!leaves.is_empty(), "at least one tree is needed");
+    let num_leaves = leaves[0].evaluated_values.len();
+    assert!(
+        num_leaves.is_power_of_two()
+            && leaves
+                .iter()
+                .all(|leaves| leaves.evaluated_values.len() == num_leaves),
+        "the trees must have the same power of two number of leaves"
+    );
+
+    let num_vars = num_leaves.ilog2() as usize;
+
+    // trees[t][i] is layer i of tree t
+    let trees: Vec<Vec<Vec<F>>> = leaves
+        .iter()
+        .map(|leaves| {
+            let mut layers = vec![leaves.evaluated_values.clone()];
+            while layers[0].len() > 1 {
+                let parents = layers[0].chunks(2).map(|pair| pair[0] * pair[1]).collect();
+                layers.insert(0, parents);
+            }
+            layers
+        })
+        .collect();
+
+    let products: Vec<F> = trees.iter().map(|layers| layers[0][0]).collect();
+    absorb_elements(&products, transcript);
+
+    let mut claims = products.clone();
+    let mut point: Vec<F> = Vec::new();
+    let mut layer_proofs = Vec::with_capacity(num_vars);
+
+    for layer in 1..=num_vars {
+        let alpha: F = transcript.random_challenge_as_field_element();
+        let eq_point = MultilinearPolynomialEV::eq_polynomial(&point);
+
+        let mut weight = F::one();
+        let mut claimed_sum = F::zero();
+        let mut halves = Vec::with_capacity(trees.len());
+        let mut product_polynomials = Vec::with_capacity(trees.len());
+
+        for (layers, claim) in trees.iter().zip(claims.iter()) {
+            let left: Vec<F> = layers[layer].iter().step_by(2).copied().collect();
+            let right: Vec<F> = layers[layer].iter().skip(1).step_by(2).copied().collect();
+            let (left, right) = (
+                MultilinearPolynomialEV::new(&left),
+                MultilinearPolynomialEV::new(&right),
+            );
+
+            product_polynomials.push(ProductPolynomial::new(vec![
+                eq_point.clone(),
+                left.scalar_mul(weight),
+                right.clone(),
+            ]));
+            halves.push((left, right));
+
+            claimed_sum += weight * claim;
+            weight *= alpha;
+        }
+
+        let sumcheck_proof = sumcheck_prove(
+            SumPolynomial::new(product_polynomials),
+            claimed_sum,
+            transcript,
+        );
+        let sumcheck_point = sumcheck_proof.random_challenges.clone();
+
+        let left_evaluations: Vec<F> = halves
+            .iter()
+            .map(|(left, _)| left.evaluate(&sumcheck_point))
+            .collect();
+        let right_evaluations: Vec<F> = halves
+            .iter()
+            .map(|(_, right)| right.evaluate(&sumcheck_point))
+            .collect();
+        absorb_elements(&left_evaluations, transcript);
+        absorb_elements(&right_evaluations, transcript);
+
+        let challenge: F = transcript.random_challenge_as_field_element();
+        claims = merge_halves(&left_evaluations, &right_evaluations, challenge);
+        point = sumcheck_point;
+        point.push(challenge);
+
+        layer_proofs.push(ProductLayerProof {
+            sumcheck_proof,
+            left_evaluations,
+            right_evaluations,
+        });
+    }
+
+    (
+        BatchGrandProductProof {
+            products,
+            layer_proofs,
+        },
+        leaf_claims(&point, claims),
+    )
+}
+
+// Returns the claims on the leaves of every tree when every layer reduction is valid, None otherwise
+pub fn verify_batch<F: PrimeField>(
+    proof: &BatchGrandProductProof<F>,
+    num_vars: usize,
+    transcript: &mut Transcript,
+) -> Option<Vec<Claim<F>>> {
+    let num_trees = proof.products.len();
+
+    if num_trees == 0 || proof.layer_proofs.len() != num_vars {
+        return None;
+    }
+
+    absorb_elements(&proof.products, transcript);
+
+    let mut claims = proof.products.clone();
+    let mut point: Vec<F> = Vec::new();
+
+    for layer_proof in proof.layer_proofs.iter() {
+        if layer_proof
+            .sumcheck_proof
+            .round_univariate_polynomials
+            .len()
+            != point.len()
+            || layer_proof.left_evaluations.len() != num_trees
+            || layer_proof.right_evaluations.len() != num_trees
+        {
+            return None;
+        }
+
+        let alpha: F = transcript.random_challenge_as_field_element();
+        let weights: Vec<F> = std::iter::successors(Some(F::one()), |weight| Some(*weight * alpha))
+            .take(num_trees)
+            .collect();
+
+        let claimed_sum: F = claims
+            .iter()
+            .zip(weights.iter())
+            .map(|(claim, weight)| *claim * weight)
+            .sum();
+        if layer_proof.sumcheck_proof.claimed_sum != claimed_sum {
+            return None;
+        }
+
+        let verification = sumcheck_verify(&layer_proof.sumcheck_proof, transcript);
+        let expected: F = layer_proof
+            .left_evaluations
+            .iter()
+            .zip(layer_proof.right_evaluations.iter())
+            .zip(weights.iter())
+            .map(|((left, right), weight)| *weight * left * right)
+            .sum();
+
+        if !verification.is_proof_valid
+            || verification.last_claimed_sum
+                != eq_evaluation(&point, &verification.random_challenges) * expected
+        {
+            return None;
+        }
+
+        absorb_elements(&layer_proof.left_evaluations, transcript);
+        absorb_elements(&layer_proof.right_evaluations, transcript);
+
+        let challenge: F = transcript.random_challenge_as_field_element();
+        claims = merge_halves(
+            &layer_proof.left_evaluations,
+            &layer_proof.right_evaluations,
+            challenge,
+        );
+        point = verification.random_challenges;
+        point.push(challenge);
+    }
+
+    Some(leaf_claims(&point, claims))
+}
+
+// V(r', c) = (1 - c) * V(r', 0) + c * V(r', 1)
+fn merge_halves<F: PrimeField>(left: &[F], right: &[F], challenge: F) -> Vec<F> {
+    left.iter()
+        .zip(right.iter())
+        .map(|(left, right)| *left + challenge * (*right - left))
+        .collect()
+}
+
+fn leaf_claims<F: PrimeField>(point: &[F], values: Vec<F>) -> Vec<Claim<F>> {
+    values
+        .into_iter()
+        .map(|value| Claim {
+            point: point.to_vec(),
+            value,
+        })
+        .collect()
+}
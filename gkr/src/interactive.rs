@@ -0,0 +1,586 @@
+use crate::circuits::circuit::{pad_to_num_vars, Circuit, LayerPredicates};
+use crate::gkr_p::{
+    expected_layer_claim, pad_input_layer, push_layer_claims, source_block_evaluations,
+    source_polynomials, verifier_layers, Claim, CombinedClaim,
+};
+use crate::gkr_sumcheck_dependencies::densed_uni_poly::DensedUnivariatePolynomial;
+use crate::gkr_sumcheck_dependencies::poly_sum::SumPolynomial;
+use crate::sum_check::{generate_round_univariate, SumcheckVerifierProof};
+use crate::wb_bc_utility::compute_layers_fbc_polynomial;
+use ark_ff::{BigInteger, PrimeField};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+// Interactive sum-check and GKR: the prover and the verifier run on the two ends of a Channel,
+// and every challenge is drawn by the verifier from the randomness of the operating system after the prover's message,
+// instead of being derived from a Transcript (no Fiat-Shamir).
+// A message is a list of field elements, in their little endian canonical encoding.
+// The protocol is the one of gkr_p with the claims on a layer combined by a random linear combination:
+// the verifier sends the output point, the weights of the claims on every layer and the sumcheck challenges,
+// the prover sends the output, the round polynomials (evaluations at 0, 1, ..., degree) and the evaluations of the layers.
+// A verifier that rejects sends an empty message in place of its next message, and the prover fails with ConnectionAborted
+// instead of waiting for an answer. At the end of GKR, the verifier sends its verdict: 1 when it accepts, the empty message otherwise.
+
+// Largest message accepted from a TcpChannel, so that a length prefix can't make the receiver allocate without bound
+const MAX_MESSAGE_SIZE: usize = 1 << 30;
+
+pub trait Channel {
+    fn send(&mut self, message: &[u8]) -> io::Result<()>;
+    fn receive(&mut self) -> io::Result<Vec<u8>>;
+}
+
+// The two ends of a channel in the same process, eg: the prover and the verifier on two threads
+pub struct InMemoryChannel {
+    sender: Sender<Vec<u8>>,
+    receiver: Receiver<Vec<u8>>,
+}
+
+impl InMemoryChannel {
+    pub fn pair() -> (Self, Self) {
+        let (first_sender, second_receiver) = channel();
+        let (second_sender, first_receiver) = channel();
+
+        (
+            Self {
+                sender: first_sender,
+                receiver: first_receiver,
+            },
+            Self {
+                sender: second_sender,
+                receiver: second_receiver,
+            },
+        )
+    }
+}
+
+impl Channel for InMemoryChannel {
+    fn send(&mut self, message: &[u8]) -> io::Result<()> {
+        self.sender
+            .send(message.to_vec())
+            .map_err(|_| closed_channel_error())
+    }
+
+    fn receive(&mut self) -> io::Result<Vec<u8>> {
+        self.receiver.recv().map_err(|_| closed_channel_error())
+    }
+}
+
+// A channel over a TCP connection, every message is sent with its length as a 4 bytes little endian prefix
+pub struct TcpChannel {
+    stream: TcpStream,
+}
+
+impl TcpChannel {
+    pub fn new(stream: TcpStream) -> io::Result<Self> {
+        // The messages are small and every one of them waits for an answer
+        stream.set_nodelay(true)?;
+
+        Ok(Self { stream })
+    }
+
+    pub fn connect<A: ToSocketAddrs>(address: A) -> io::Result<Self> {
+        Self::new(TcpStream::connect(address)?)
+    }
+
+    pub fn accept(listener: &TcpListener) -> io::Result<Self> {
+        let (stream, _) = listener.accept()?;
+
+        Self::new(stream)
+    }
+}
+
+impl Channel for TcpChannel {
+    fn send(&mut self, message: &[u8]) -> io::Result<()> {
+        let length = u32::try_from(message.len())
+            .ok()
+            .filter(|length| *length as usize <= MAX_MESSAGE_SIZE)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "message too large"))?;
+
+        self.stream.write_all(&length.to_le_bytes())?;
+        self.stream.write_all(message)?;
+        self.stream.flush()
+    }
+
+    fn receive(&mut self) -> io::Result<Vec<u8>> {
+        let mut length = [0; 4];
+        self.stream.read_exact(&mut length)?;
+
+        let length = u32::from_le_bytes(length) as usize;
+        if length > MAX_MESSAGE_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "message too large",
+            ));
+        }
+
+        let mut message = vec![0; length];
+        self.stream.read_exact(&mut message)?;
+
+        Ok(message)
+    }
+}
+
+// The verifier's randomness, read from the operating system
+pub struct OsRandomness {
+    source: OsRng,
+}
+
+impl OsRandomness {
+    pub fn new() -> Self {
+        Self { source: OsRng }
+    }
+
+    // 64 random bytes reduced modulo the field order, so that the bias is negligible
+    pub fn random_field_element<F: PrimeField>(&mut self) -> io::Result<F> {
+        let mut bytes = [0; 64];
+        self.source.try_fill_bytes(&mut bytes)?;
+
+        Ok(F::from_le_bytes_mod_order(&bytes))
+    }
+
+    fn random_field_elements<F: PrimeField>(&mut self, count: usize) -> io::Result<Vec<F>> {
+        (0..count).map(|_| self.random_field_element()).collect()
+    }
+}
+
+impl Default for OsRandomness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn send_field_elements<F: PrimeField, C: Channel>(
+    channel: &mut C,
+    elements: &[F],
+) -> io::Result<()> {
+    let message: Vec<u8> = elements
+        .iter()
+        .flat_map(|element| element.into_bigint().to_bytes_le())
+        .collect();
+
+    channel.send(&message)
+}
+
+// Fails with InvalidData when the message is not a list of canonical field elements
+pub fn receive_field_elements<F: PrimeField, C: Channel>(channel: &mut C) -> io::Result<Vec<F>> {
+    let message = channel.receive()?;
+    let element_size = F::zero().into_bigint().to_bytes_le().len();
+
+    if message.len() % element_size != 0 {
+        return Err(invalid_message_error());
+    }
+
+    message
+        .chunks(element_size)
+        .map(|bytes| {
+            let element = F::from_le_bytes_mod_order(bytes);
+            if element.into_bigint().to_bytes_le() == bytes {
+                Ok(element)
+            } else {
+                Err(invalid_message_error())
+            }
+        })
+        .collect()
+}
+
+// The prover of the sum-check, one round at a time: the round message is the evaluations of the round polynomial
+// at 0, 1, ..., degree, and the challenge of the verifier binds the first variable
+pub struct SumcheckProver<F: PrimeField> {
+    polynomial: SumPolynomial<F>,
+}
+
+impl<F: PrimeField> SumcheckProver<F> {
+    pub fn new(polynomial: SumPolynomial<F>) -> Self {
+        Self { polynomial }
+    }
+
+    pub fn number_of_rounds(&self) -> usize {
+        self.polynomial.number_of_variables() as usize
+    }
+
+    pub fn round_message(&self) -> Vec<F> {
+        generate_round_univariate(&self.polynomial)
+    }
+
+    pub fn receive_challenge(&mut self, challenge: F) {
+        self.polynomial = self.polynomial.partial_evaluate(0, challenge);
+    }
+}
+
+// The verifier of the sum-check, one round at a time: the round message must have degree + 1 evaluations
+// that sum to the current claim on 0 and 1, the next claim is the round polynomial at the challenge
+pub struct SumcheckVerifier<F: PrimeField> {
+    number_of_variables: usize,
+    degree: usize,
+    is_proof_valid: bool,
+    random_challenges: Vec<F>,
+    current_sum: F,
+}
+
+impl<F: PrimeField> SumcheckVerifier<F> {
+    pub fn new(claimed_sum: F, number_of_variables: usize, degree: usize) -> Self {
+        Self {
+            number_of_variables,
+            degree,
+            is_proof_valid: true,
+            random_challenges: Vec::with_capacity(number_of_variables),
+            current_sum: claimed_sum,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        !self.is_proof_valid || self.random_challenges.len() == self.number_of_variables
+    }
+
+    // Returns whether the round is accepted, the challenge must be drawn after the round message is received
+    pub fn receive_round_message(&mut self, evaluations: &[F], challenge: F) -> bool {
+        if self.is_done()
+            || evaluations.len() != self.degree + 1
+            || evaluations[0] + evaluations[1] != self.current_sum
+        {
+            self.is_proof_valid = false;
+            return false;
+        }
+
+        let x_values: Vec<F> = (0..=self.degree).map(|i| F::from(i as u64)).collect();
+        self.current_sum = DensedUnivariatePolynomial::lagrange_interpolate(&x_values, evaluations)
+            .evaluate(challenge);
+        self.random_challenges.push(challenge);
+
+        true
+    }
+
+    pub fn finish(self) -> SumcheckVerifierProof<F> {
+        let is_proof_valid =
+            self.is_proof_valid && self.random_challenges.len() == self.number_of_variables;
+
+        SumcheckVerifierProof {
+            is_proof_valid,
+            random_challenges: if is_proof_valid {
+                self.random_challenges
+            } else {
+                vec![]
+            },
+            last_claimed_sum: self.current_sum,
+        }
+    }
+}
+
+// Runs the prover side of the sum-check over the channel, returns the challenges of the verifier
+pub fn prove_sumcheck<F: PrimeField, C: Channel>(
+    channel: &mut C,
+    polynomial: SumPolynomial<F>,
+) -> io::Result<Vec<F>> {
+    let mut prover = SumcheckProver::new(polynomial);
+    let mut random_challenges = Vec::with_capacity(prover.number_of_rounds());
+
+    for _round in 0..prover.number_of_rounds() {
+        send_field_elements(channel, &prover.round_message())?;
+
+        let challenge = receive_single_element(channel)?;
+        prover.receive_challenge(challenge);
+        random_challenges.push(challenge);
+    }
+
+    Ok(random_challenges)
+}
+
+// Runs the verifier side of the sum-check over the channel, and tells the prover when a round is rejected.
+// As with sum_check::verify, last_claimed_sum is left to be checked by the caller at the random challenges
+pub fn verify_sumcheck<F: PrimeField, C: Channel>(
+    channel: &mut C,
+    randomness: &mut OsRandomness,
+    claimed_sum: F,
+    number_of_variables: usize,
+    degree: usize,
+) -> io::Result<SumcheckVerifierProof<F>> {
+    let verification = run_sumcheck_verifier(
+        channel,
+        randomness,
+        claimed_sum,
+        number_of_variables,
+        degree,
+    )?;
+
+    if !verification.is_proof_valid {
+        send_reject(channel);
+    }
+
+    Ok(verification)
+}
+
+// The rounds of verify_sumcheck, without the reject message
+fn run_sumcheck_verifier<F: PrimeField, C: Channel>(
+    channel: &mut C,
+    randomness: &mut OsRandomness,
+    claimed_sum: F,
+    number_of_variables: usize,
+    degree: usize,
+) -> io::Result<SumcheckVerifierProof<F>> {
+    let mut verifier = SumcheckVerifier::new(claimed_sum, number_of_variables, degree);
+
+    while !verifier.is_done() {
+        let round_message = receive_field_elements(channel)?;
+        let challenge = randomness.random_field_element()?;
+
+        if verifier.receive_round_message(&round_message, challenge) {
+            send_field_elements(channel, &[challenge])?;
+        }
+    }
+
+    Ok(verifier.finish())
+}
+
+// Runs the prover side of GKR over the channel, for the circuit evaluated on the inputs
+pub fn prove_gkr<F: PrimeField, C: Channel>(
+    channel: &mut C,
+    circuit: &mut Circuit<F>,
+    inputs: &[F],
+) -> io::Result<()> {
+    let circuit_evaluation = circuit.evaluate(inputs.to_vec());
+    let layers = verifier_layers(circuit);
+    let number_of_layers = circuit.layers.len();
+
+    send_field_elements(channel, &circuit_evaluation.output)?;
+
+    let output_num_vars = circuit.layer_output_num_vars(0);
+    let output_point = receive_exact_elements(channel, output_num_vars)?;
+    let mut combined_claim = CombinedClaim::from(&Claim {
+        value: pad_to_num_vars(&circuit_evaluation.output, output_num_vars).evaluate(&output_point),
+        point: output_point,
+    });
+    let mut layer_claims: Vec<Vec<Claim<F>>> = vec![Vec::new(); number_of_layers + 1];
+
+    for (layer_index, layer) in layers.iter().enumerate() {
+        if layer_index > 0 {
+            let weights = receive_exact_elements(channel, layer_claims[layer_index].len())?;
+            combined_claim = combine_claims(&layer_claims[layer_index], &weights);
+        }
+
+        let fbc_polynomial = compute_layers_fbc_polynomial(
+            &layer.predicates,
+            &combined_claim.weighted_points,
+            &source_polynomials(circuit, &circuit_evaluation, layer_index),
+            layer.layer_below,
+            layer.arity,
+        );
+        let sumcheck_challenges = prove_sumcheck(channel, fbc_polynomial)?;
+
+        let block_evaluations = source_block_evaluations(
+            circuit,
+            &circuit_evaluation,
+            &layer.block_layers,
+            &sumcheck_challenges,
+        );
+        send_field_elements(channel, &block_evaluations.concat())?;
+
+        push_layer_claims(
+            circuit,
+            &sumcheck_challenges,
+            &layer.block_layers,
+            &block_evaluations,
+            &mut layer_claims,
+        );
+    }
+
+    // 1 when the verifier accepts, a reject fails in receive_single_element
+    if receive_single_element::<F, C>(channel)? != F::one() {
+        return Err(invalid_message_error());
+    }
+
+    Ok(())
+}
+
+// Runs the verifier side of GKR over the channel, the verifier knows the circuit and its inputs.
+// Returns the output of the circuit when the prover convinced the verifier, None otherwise.
+// Either way, the prover is told the verdict
+pub fn verify_gkr<F: PrimeField, C: Channel>(
+    channel: &mut C,
+    randomness: &mut OsRandomness,
+    circuit: &mut Circuit<F>,
+    inputs: &[F],
+) -> io::Result<Option<Vec<F>>> {
+    let output = run_gkr_verifier(channel, randomness, circuit, inputs)?;
+
+    match output {
+        Some(_) => send_field_elements(channel, &[F::one()])?,
+        None => send_reject(channel),
+    }
+
+    Ok(output)
+}
+
+// The layers of verify_gkr, without the verdict
+fn run_gkr_verifier<F: PrimeField, C: Channel>(
+    channel: &mut C,
+    randomness: &mut OsRandomness,
+    circuit: &mut Circuit<F>,
+    inputs: &[F],
+) -> io::Result<Option<Vec<F>>> {
+    if inputs.len() > 1 << circuit.input_num_vars() {
+        return Ok(None);
+    }
+
+    let layers = verifier_layers(circuit);
+    let number_of_layers = circuit.layers.len();
+
+    let output: Vec<F> = receive_field_elements(channel)?;
+    let output_num_vars = circuit.layer_output_num_vars(0);
+    if output.len() > 1 << output_num_vars {
+        return Ok(None);
+    }
+
+    let output_point = randomness.random_field_elements(output_num_vars)?;
+    send_field_elements(channel, &output_point)?;
+
+    let mut combined_claim = CombinedClaim::from(&Claim {
+        value: pad_to_num_vars(&output, output_num_vars).evaluate(&output_point),
+        point: output_point,
+    });
+    let mut layer_claims: Vec<Vec<Claim<F>>> = vec![Vec::new(); number_of_layers + 1];
+
+    for (layer_index, layer) in layers.iter().enumerate() {
+        if layer_index > 0 {
+            if layer_claims[layer_index].is_empty() {
+                return Ok(None);
+            }
+
+            let weights = randomness.random_field_elements(layer_claims[layer_index].len())?;
+            send_field_elements(channel, &weights)?;
+            combined_claim = combine_claims(&layer_claims[layer_index], &weights);
+        }
+
+        let verification = run_sumcheck_verifier(
+            channel,
+            randomness,
+            combined_claim.claimed_sum,
+            layer.arity * layer.input_num_vars,
+            layer_degree(&layer.predicates),
+        )?;
+        if !verification.is_proof_valid {
+            return Ok(None);
+        }
+
+        let evaluations: Vec<F> = receive_field_elements(channel)?;
+        if evaluations.len() != layer.block_layers.iter().map(Vec::len).sum::<usize>() {
+            return Ok(None);
+        }
+
+        let mut evaluations = evaluations.into_iter();
+        let block_evaluations: Vec<Vec<F>> = layer
+            .block_layers
+            .iter()
+            .map(|source_layers| evaluations.by_ref().take(source_layers.len()).collect())
+            .collect();
+
+        let sumcheck_challenges = verification.random_challenges;
+        let expected_claim = expected_layer_claim(
+            circuit,
+            layer,
+            &combined_claim.weighted_points,
+            &sumcheck_challenges,
+            &block_evaluations,
+        );
+        if expected_claim != verification.last_claimed_sum {
+            return Ok(None);
+        }
+
+        push_layer_claims(
+            circuit,
+            &sumcheck_challenges,
+            &layer.block_layers,
+            &block_evaluations,
+            &mut layer_claims,
+        );
+    }
+
+    // The verifier knows the inputs, so it checks the input layer claims itself
+    let input_polynomial = pad_input_layer(circuit, inputs);
+    let inputs_hold = layer_claims[number_of_layers]
+        .iter()
+        .all(|claim| input_polynomial.evaluate(&claim.point) == claim.value);
+
+    Ok(inputs_hold.then_some(output))
+}
+
+// The degree of the sumcheck of a layer, as built by compute_layers_fbc_polynomial:
+// 2 for the wiring and multi-input predicates (predicate * combined inputs), 1 + the largest exponent for the custom gates
+fn layer_degree<F: PrimeField>(predicates: &LayerPredicates<F>) -> usize {
+    let custom_degree = predicates
+        .custom
+        .iter()
+        .flat_map(|predicate| predicate.terms.iter())
+        .map(|term| 1 + term.exponents.iter().copied().max().unwrap_or(0))
+        .max()
+        .unwrap_or(0);
+
+    if predicates.wiring.is_empty() && predicates.multi_input.is_empty() {
+        custom_degree
+    } else {
+        custom_degree.max(2)
+    }
+}
+
+// sum of weight_k * W(point_k) = sum of weight_k * value_k
+fn combine_claims<F: PrimeField>(claims: &[Claim<F>], weights: &[F]) -> CombinedClaim<F> {
+    CombinedClaim {
+        weighted_points: weights
+            .iter()
+            .zip(claims.iter())
+            .map(|(weight, claim)| (*weight, claim.point.clone()))
+            .collect(),
+        claimed_sum: weights
+            .iter()
+            .zip(claims.iter())
+            .map(|(weight, claim)| *weight * claim.value)
+            .sum(),
+    }
+}
+
+// The prover can't go on with a message of the wrong shape: the verifier doesn't follow the protocol.
+// Every message the prover waits for has at least one element, so an empty one is the verifier rejecting
+fn receive_exact_elements<F: PrimeField, C: Channel>(
+    channel: &mut C,
+    count: usize,
+) -> io::Result<Vec<F>> {
+    let elements = receive_field_elements(channel)?;
+
+    if elements.is_empty() {
+        return Err(rejected_proof_error());
+    }
+    if elements.len() != count {
+        return Err(invalid_message_error());
+    }
+
+    Ok(elements)
+}
+
+fn receive_single_element<F: PrimeField, C: Channel>(channel: &mut C) -> io::Result<F> {
+    Ok(receive_exact_elements(channel, 1)?[0])
+}
+
+// The prover may already be gone when the verifier rejects, there is nobody left to tell then
+fn send_reject<C: Channel>(channel: &mut C) {
+    let _ = channel.send(&[]);
+}
+
+fn rejected_proof_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::ConnectionAborted,
+        "the verifier rejected the proof",
+    )
+}
+
+fn closed_channel_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::BrokenPipe,
+        "the other end of the channel is closed",
+    )
+}
+
+pub(crate) fn invalid_message_error() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "invalid protocol message")
+}
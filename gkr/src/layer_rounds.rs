@@ -0,0 +1,67 @@
+use crate::gkr_sumcheck_dependencies::densed_uni_poly::DensedUnivariatePolynomial;
+use crate::gkr_sumcheck_dependencies::transcript::Transcript;
+use crate::sum_check::univariate_to_bytes;
+use crate::sum_check_rounds::fold;
+use ark_ff::PrimeField;
+
+// The rounds of the gkr_p layer sumcheck over a sum of products of tables, for the provers that hold
+// the tables in pieces (distributed: a part of every table per worker, collaborative: shares of every table).
+// products[i][j] is the j-th table of the i-th product, and the variable bound in a round is the first one
+// (the most significant bit of the index), as in sum_check::prove.
+
+// The evaluations at 0, 1, ..., degree of the sum over the remaining variables with the first one set to each point,
+// the degree being the largest number of tables in a product
+pub(crate) fn round_evaluations<F: PrimeField>(products: &[Vec<Vec<F>>]) -> Vec<F> {
+    let degree = products
+        .iter()
+        .map(|factors| factors.len())
+        .max()
+        .unwrap_or(0);
+
+    (0..=degree)
+        .map(|point| {
+            let point = F::from(point as u64);
+
+            products
+                .iter()
+                .map(|factors| {
+                    let half = factors[0].len() / 2;
+
+                    (0..half)
+                        .map(|index| {
+                            factors
+                                .iter()
+                                .map(|table| {
+                                    table[index] + point * (table[index + half] - table[index])
+                                })
+                                .product::<F>()
+                        })
+                        .sum::<F>()
+                })
+                .sum()
+        })
+        .collect()
+}
+
+// Binds the first variable of every table to the challenge
+pub(crate) fn fold_products<F: PrimeField>(products: &mut [Vec<Vec<F>>], challenge: F) {
+    for table in products.iter_mut().flatten() {
+        *table = fold(table, challenge);
+    }
+}
+
+// Absorbs the round polynomial given by its evaluations at 0, 1, ..., degree, as sum_check::prove does,
+// and returns the challenge
+pub(crate) fn absorb_round<F: PrimeField>(
+    evaluations: &[F],
+    transcript: &mut Transcript,
+    round_univariate_polynomials: &mut Vec<DensedUnivariatePolynomial<F>>,
+) -> F {
+    let x_values: Vec<F> = (0..evaluations.len()).map(|i| F::from(i as u64)).collect();
+    let univariate_poly = DensedUnivariatePolynomial::lagrange_interpolate(&x_values, evaluations);
+
+    transcript.absorb(&univariate_to_bytes(&univariate_poly.coefficients));
+    round_univariate_polynomials.push(univariate_poly);
+
+    transcript.random_challenge_as_field_element()
+}
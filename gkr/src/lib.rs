@@ -1,3 +1,4 @@
+pub mod circom;
 pub mod circuits;
 pub mod multilinear_poly;
 pub mod prover;
@@ -5,9 +6,29 @@ pub mod traits;
 pub mod utility;
 pub mod verifier;
 
+pub mod binary_field;
+pub mod binary_sum_check;
+pub mod collaborative;
+pub mod distributed;
 pub mod evaluation;
+pub mod extension_gkr;
+pub mod extension_sum_check;
+pub mod fields;
+pub mod gkr_data_parallel;
 pub mod gkr_p;
 pub mod gkr_sumcheck_dependencies;
+pub mod grand_product;
+pub mod interactive;
+pub(crate) mod layer_rounds;
+pub mod ligero;
+pub mod logup;
+pub mod matmul;
+pub mod memory_checking;
+pub mod merkle_tree;
+pub mod plonkish;
 pub mod product_poly;
+pub mod r1cs;
+pub mod spartan;
 pub mod sum_check;
+pub mod sum_check_rounds;
 pub mod wb_bc_utility;
@@ -0,0 +1,319 @@
+use crate::evaluation::MultilinearPolynomialEV;
+use crate::gkr_sumcheck_dependencies::transcript::Transcript;
+use crate::merkle_tree::{MerkleMultiProof, MerkleTree};
+use crate::sum_check::field_element_to_bytes;
+use ark_ff::PrimeField;
+use sha3::Keccak256;
+use std::collections::BTreeMap;
+
+// Ligero/Brakedown style polynomial commitment for multilinear polynomials
+// The evaluations of the polynomial are arranged in a (rows x columns) matrix, every row is Reed-Solomon encoded,
+// then the columns of the encoded matrix are committed to using a Keccak256 Merkle tree.
+// Since f(r) = q1^T * M * q2 where q1 and q2 are the eq polynomials of the row and column parts of r,
+// the prover sends the row q1^T * M and the verifier checks its encoding against a few opened columns.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LigeroParams {
+    // The codeword of every row is rate_inverse times longer than the row: code rate = 1 / rate_inverse
+    pub rate_inverse: usize,
+    // The number of encoded columns the verifier samples and checks against the commitment
+    pub num_column_queries: usize,
+}
+
+impl Default for LigeroParams {
+    fn default() -> Self {
+        Self {
+            rate_inverse: 4,
+            num_column_queries: 32,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LigeroCommitment {
+    pub root: Vec<u8>,
+    pub num_rows: usize,
+    pub num_columns: usize,
+}
+
+// Everything the prover keeps after committing, so that it can answer evaluation queries later
+#[derive(Clone, Debug)]
+pub struct LigeroProverData<F: PrimeField> {
+    pub rows: Vec<Vec<F>>,
+    pub encoded_rows: Vec<Vec<F>>,
+    pub merkle_tree: MerkleTree<Keccak256>,
+    pub params: LigeroParams,
+}
+
+#[derive(Clone, Debug)]
+pub struct ColumnOpening<F: PrimeField> {
+    pub index: usize,
+    pub values: Vec<F>,
+}
+
+#[derive(Clone, Debug)]
+pub struct LigeroEvaluationProof<F: PrimeField> {
+    // random combination of the rows, used to test that the committed rows are close to codewords
+    pub proximity_row: Vec<F>,
+    // q1^T * M, whose inner product with q2 is the claimed evaluation
+    pub evaluation_row: Vec<F>,
+    pub column_openings: Vec<ColumnOpening<F>>,
+    // a single batch proof for all the opened columns, shared paths are only sent once
+    pub merkle_proof: MerkleMultiProof,
+}
+
+pub fn commit<F: PrimeField>(
+    polynomial: &MultilinearPolynomialEV<F>,
+    params: &LigeroParams,
+) -> (LigeroCommitment, LigeroProverData<F>) {
+    assert!(params.rate_inverse > 1, "rate_inverse must be at least 2");
+    assert!(
+        polynomial.evaluated_values.len().is_power_of_two(),
+        "polynomial must have a power of two number of evaluations"
+    );
+
+    let (num_rows, num_columns) = matrix_dimensions(polynomial.number_of_variables() as usize);
+    let codeword_length = num_columns * params.rate_inverse;
+
+    let rows: Vec<Vec<F>> = polynomial
+        .evaluated_values
+        .chunks(num_columns)
+        .map(|row| row.to_vec())
+        .collect();
+
+    let encoded_rows: Vec<Vec<F>> = rows
+        .iter()
+        .map(|row| encode_row(row, codeword_length))
+        .collect();
+
+    let leaves: Vec<Vec<u8>> = (0..codeword_length)
+        .map(|column_index| row_to_bytes(&column_values(&encoded_rows, column_index)))
+        .collect();
+    let merkle_tree = MerkleTree::<Keccak256>::new(&leaves);
+    let root = merkle_tree.root();
+
+    let commitment = LigeroCommitment {
+        root,
+        num_rows,
+        num_columns,
+    };
+
+    let prover_data = LigeroProverData {
+        rows,
+        encoded_rows,
+        merkle_tree,
+        params: params.clone(),
+    };
+
+    (commitment, prover_data)
+}
+
+/// Opens the committed polynomial at `point`: returns the evaluation and the proof that it is consistent with the commitment
+pub fn open<F: PrimeField>(
+    commitment: &LigeroCommitment,
+    prover_data: &LigeroProverData<F>,
+    point: &[F],
+    transcript: &mut Transcript,
+) -> (F, LigeroEvaluationProof<F>) {
+    let row_variables = commitment.num_rows.ilog2() as usize;
+    assert_eq!(
+        point.len(),
+        row_variables + commitment.num_columns.ilog2() as usize,
+        "point has the wrong number of variables"
+    );
+
+    let (row_point, column_point) = point.split_at(row_variables);
+    let q1 = MultilinearPolynomialEV::eq_polynomial(row_point).evaluated_values;
+    let q2 = MultilinearPolynomialEV::eq_polynomial(column_point).evaluated_values;
+
+    let evaluation_row = combine_rows(&prover_data.rows, &q1);
+    let evaluation = inner_product(&evaluation_row, &q2);
+
+    absorb_statement(transcript, commitment, point, evaluation);
+
+    let gammas: Vec<F> = (0..commitment.num_rows)
+        .map(|_| transcript.random_challenge_as_field_element())
+        .collect();
+    let proximity_row = combine_rows(&prover_data.rows, &gammas);
+
+    transcript.absorb(&row_to_bytes(&proximity_row));
+    transcript.absorb(&row_to_bytes(&evaluation_row));
+
+    let column_indices = sample_column_indices(transcript, commitment, &prover_data.params);
+    let merkle_proof = prover_data.merkle_tree.prove_batch(&column_indices);
+
+    let column_openings = column_indices
+        .into_iter()
+        .map(|index| ColumnOpening {
+            index,
+            values: column_values(&prover_data.encoded_rows, index),
+        })
+        .collect();
+
+    (
+        evaluation,
+        LigeroEvaluationProof {
+            proximity_row,
+            evaluation_row,
+            column_openings,
+            merkle_proof,
+        },
+    )
+}
+
+// The soundness parameters are the verifier's own: they are not read from anything the prover sends
+pub fn verify<F: PrimeField>(
+    commitment: &LigeroCommitment,
+    params: &LigeroParams,
+    point: &[F],
+    evaluation: F,
+    proof: &LigeroEvaluationProof<F>,
+    transcript: &mut Transcript,
+) -> bool {
+    // The sizes come from the prover: anything but powers of two would make ilog2 panic or drop rows
+    if !commitment.num_rows.is_power_of_two() || !commitment.num_columns.is_power_of_two() {
+        return false;
+    }
+
+    let row_variables = commitment.num_rows.ilog2() as usize;
+    if params.rate_inverse < 2
+        || point.len() != row_variables + commitment.num_columns.ilog2() as usize
+        || proof.proximity_row.len() != commitment.num_columns
+        || proof.evaluation_row.len() != commitment.num_columns
+        || proof.column_openings.len() != params.num_column_queries
+    {
+        return false;
+    }
+
+    let (row_point, column_point) = point.split_at(row_variables);
+    let q1 = MultilinearPolynomialEV::eq_polynomial(row_point).evaluated_values;
+    let q2 = MultilinearPolynomialEV::eq_polynomial(column_point).evaluated_values;
+
+    absorb_statement(transcript, commitment, point, evaluation);
+
+    let gammas: Vec<F> = (0..commitment.num_rows)
+        .map(|_| transcript.random_challenge_as_field_element())
+        .collect();
+
+    transcript.absorb(&row_to_bytes(&proof.proximity_row));
+    transcript.absorb(&row_to_bytes(&proof.evaluation_row));
+
+    let codeword_length = match commitment.num_columns.checked_mul(params.rate_inverse) {
+        Some(codeword_length) => codeword_length,
+        None => return false,
+    };
+    // the committed tree has one leaf per codeword column, padded to a power of two
+    if proof.merkle_proof.depth != codeword_length.next_power_of_two().ilog2() as usize {
+        return false;
+    }
+
+    let encoded_proximity_row = encode_row(&proof.proximity_row, codeword_length);
+    let encoded_evaluation_row = encode_row(&proof.evaluation_row, codeword_length);
+
+    let column_indices = sample_column_indices(transcript, commitment, params);
+
+    // The same column can be sampled more than once, but it is only a single leaf of the batch proof
+    let mut opened_columns = BTreeMap::new();
+
+    for (opening, expected_index) in proof.column_openings.iter().zip(column_indices) {
+        if opening.index != expected_index || opening.values.len() != commitment.num_rows {
+            return false;
+        }
+
+        let column_bytes = row_to_bytes(&opening.values);
+        if *opened_columns
+            .entry(opening.index)
+            .or_insert_with(|| column_bytes.clone())
+            != column_bytes
+        {
+            return false;
+        }
+
+        // The opened column must agree with the encodings of both combined rows at that position
+        if inner_product(&opening.values, &gammas) != encoded_proximity_row[opening.index]
+            || inner_product(&opening.values, &q1) != encoded_evaluation_row[opening.index]
+        {
+            return false;
+        }
+    }
+
+    let opened_indices: Vec<usize> = opened_columns.keys().copied().collect();
+    let leaves: Vec<Vec<u8>> = opened_columns.into_values().collect();
+    if proof.merkle_proof.leaf_indices != opened_indices
+        || !MerkleTree::<Keccak256>::verify_batch(&commitment.root, &leaves, &proof.merkle_proof)
+    {
+        return false;
+    }
+
+    inner_product(&proof.evaluation_row, &q2) == evaluation
+}
+
+// The row variables are the first half of the polynomial variables, so the evaluations can be read row by row
+pub fn matrix_dimensions(number_of_variables: usize) -> (usize, usize) {
+    let row_variables = number_of_variables / 2;
+    let column_variables = number_of_variables - row_variables;
+
+    (1 << row_variables, 1 << column_variables)
+}
+
+// Reed-Solomon encoding: the row is treated as the coefficients of a univariate polynomial,
+// which is evaluated at the points 0, 1, ..., codeword_length - 1 using Horner's method
+pub fn encode_row<F: PrimeField>(row: &[F], codeword_length: usize) -> Vec<F> {
+    (0..codeword_length)
+        .map(|x| {
+            let x = F::from(x as u64);
+            row.iter()
+                .rev()
+                .fold(F::zero(), |result, coefficient| result * x + coefficient)
+        })
+        .collect()
+}
+
+fn combine_rows<F: PrimeField>(rows: &[Vec<F>], coefficients: &[F]) -> Vec<F> {
+    let mut combined_row = vec![F::zero(); rows[0].len()];
+
+    for (row, coefficient) in rows.iter().zip(coefficients.iter()) {
+        for (combined_value, value) in combined_row.iter_mut().zip(row.iter()) {
+            *combined_value += *coefficient * value;
+        }
+    }
+
+    combined_row
+}
+
+fn inner_product<F: PrimeField>(left: &[F], right: &[F]) -> F {
+    left.iter().zip(right.iter()).map(|(l, r)| *l * r).sum()
+}
+
+fn column_values<F: PrimeField>(encoded_rows: &[Vec<F>], column_index: usize) -> Vec<F> {
+    encoded_rows.iter().map(|row| row[column_index]).collect()
+}
+
+fn row_to_bytes<F: PrimeField>(row: &[F]) -> Vec<u8> {
+    row.iter()
+        .flat_map(|value| field_element_to_bytes(*value))
+        .collect()
+}
+
+fn absorb_statement<F: PrimeField>(
+    transcript: &mut Transcript,
+    commitment: &LigeroCommitment,
+    point: &[F],
+    evaluation: F,
+) {
+    transcript.absorb(&commitment.root);
+    transcript.absorb(&row_to_bytes(point));
+    transcript.absorb(&field_element_to_bytes(evaluation));
+}
+
+fn sample_column_indices(
+    transcript: &mut Transcript,
+    commitment: &LigeroCommitment,
+    params: &LigeroParams,
+) -> Vec<usize> {
+    let codeword_length = commitment.num_columns * params.rate_inverse;
+
+    (0..params.num_column_queries)
+        .map(|_| transcript.random_challenge_as_index(codeword_length))
+        .collect()
+}
@@ -0,0 +1,364 @@
+use crate::evaluation::MultilinearPolynomialEV;
+use crate::gkr_p::Claim;
+use crate::gkr_sumcheck_dependencies::poly_sum::SumPolynomial;
+use crate::gkr_sumcheck_dependencies::transcript::Transcript;
+use crate::product_poly::ProductPolynomial;
+use crate::sum_check::{
+    field_element_to_bytes, prove as sumcheck_prove, verify as sumcheck_verify, SumcheckProverProof,
+};
+use crate::utility::utils::eq_evaluation;
+use ark_ff::PrimeField;
+
+// LogUp lookup argument: every entry of the witness w is in the table t if and only if there are multiplicities m with
+// sum over i of 1 / (alpha - w_i) = sum over j of m_j / (alpha - t_j), as rational functions of alpha,
+// which is checked at a random alpha.
+// Each side is a sum of fractions p / q, computed by a binary tree of fraction additions (a layered GKR circuit):
+// layer i holds 2^i fractions with p_i(x) = p_{i+1}(x, 0) * q_{i+1}(x, 1) + p_{i+1}(x, 1) * q_{i+1}(x, 0)
+// and q_i(x) = q_{i+1}(x, 0) * q_{i+1}(x, 1).
+// The claims p_i(r), q_i(r) are combined with a random lambda and reduced to the layer below with a degree 3 sum-check.
+// The leaves are p = 1, q = alpha - w for the witness, and p = -m, q = alpha - t for the table,
+// so the claims on the leaves are claims on w, t and m, returned to be checked by the caller.
+// The witness, the table and the multiplicities must be fixed before alpha is drawn: prove and verify take
+// the commitments to them (or the values of a public table) and absorb them in the transcript before drawing alpha.
+
+#[derive(Clone, Debug)]
+pub struct FractionalLayerProof<F: PrimeField> {
+    pub sumcheck_proof: SumcheckProverProof<F>,
+    // p(r', 0), p(r', 1), q(r', 0), q(r', 1) of the layer below
+    pub numerator_evaluations: [F; 2],
+    pub denominator_evaluations: [F; 2],
+}
+
+#[derive(Clone, Debug)]
+pub struct FractionalSumProof<F: PrimeField> {
+    pub numerator: F,
+    pub denominator: F,
+    pub layer_proofs: Vec<FractionalLayerProof<F>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct LogUpProof<F: PrimeField> {
+    pub witness_sum: FractionalSumProof<F>,
+    pub table_sum: FractionalSumProof<F>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogUpClaims<F: PrimeField> {
+    pub witness: Claim<F>,
+    pub table: Claim<F>,
+    pub multiplicities: Claim<F>,
+}
+
+// The number of times every entry of the table appears in the witness, counted on the first occurrence in the table
+pub fn multiplicities<F: PrimeField>(witness: &[F], table: &[F]) -> Vec<F> {
+    let mut multiplicities = vec![F::zero(); table.len()];
+
+    for value in witness.iter() {
+        let index = table
+            .iter()
+            .position(|entry| entry == value)
+            .expect("the witness value is not in the table");
+        multiplicities[index] += F::one();
+    }
+
+    multiplicities
+}
+
+pub fn prove<F: PrimeField>(
+    witness: &MultilinearPolynomialEV<F>,
+    table: &MultilinearPolynomialEV<F>,
+    multiplicities: &MultilinearPolynomialEV<F>,
+    commitments: &[Vec<u8>],
+    transcript: &mut Transcript,
+) -> (LogUpProof<F>, LogUpClaims<F>) {
+    assert_eq!(
+        table.evaluated_values.len(),
+        multiplicities.evaluated_values.len(),
+        "one multiplicity is needed per table entry"
+    );
+
+    absorb_statement(
+        witness.number_of_variables(),
+        table.number_of_variables(),
+        commitments,
+        transcript,
+    );
+    let alpha: F = transcript.random_challenge_as_field_element();
+
+    let shift = |values: &[F]| values.iter().map(|value| alpha - value).collect::<Vec<F>>();
+
+    let (witness_sum, witness_point, _) = prove_fractional_sum(
+        vec![F::one(); witness.evaluated_values.len()],
+        shift(&witness.evaluated_values),
+        transcript,
+    );
+    let (table_sum, table_point, _) = prove_fractional_sum(
+        multiplicities
+            .evaluated_values
+            .iter()
+            .map(|multiplicity| -*multiplicity)
+            .collect(),
+        shift(&table.evaluated_values),
+        transcript,
+    );
+
+    let claims = LogUpClaims {
+        witness: Claim {
+            value: witness.evaluate(&witness_point),
+            point: witness_point,
+        },
+        table: Claim {
+            value: table.evaluate(&table_point),
+            point: table_point.clone(),
+        },
+        multiplicities: Claim {
+            value: multiplicities.evaluate(&table_point),
+            point: table_point,
+        },
+    };
+
+    (
+        LogUpProof {
+            witness_sum,
+            table_sum,
+        },
+        claims,
+    )
+}
+
+// Returns the claims on the witness, the table and the multiplicities when the proof is valid, None otherwise
+pub fn verify<F: PrimeField>(
+    proof: &LogUpProof<F>,
+    witness_num_vars: u32,
+    table_num_vars: u32,
+    commitments: &[Vec<u8>],
+    transcript: &mut Transcript,
+) -> Option<LogUpClaims<F>> {
+    let (witness_sum, table_sum) = (&proof.witness_sum, &proof.table_sum);
+
+    // p_w / q_w + p_t / q_t = 0
+    if witness_sum.denominator == F::zero()
+        || table_sum.denominator == F::zero()
+        || witness_sum.numerator * table_sum.denominator
+            + table_sum.numerator * witness_sum.denominator
+            != F::zero()
+    {
+        return None;
+    }
+
+    absorb_statement(witness_num_vars, table_num_vars, commitments, transcript);
+    let alpha: F = transcript.random_challenge_as_field_element();
+
+    let (witness_point, ones, shifted_witness) =
+        verify_fractional_sum(witness_sum, witness_num_vars as usize, transcript)?;
+    let (table_point, negated_multiplicities, shifted_table) =
+        verify_fractional_sum(table_sum, table_num_vars as usize, transcript)?;
+
+    if ones != F::one() {
+        return None;
+    }
+
+    Some(LogUpClaims {
+        witness: Claim {
+            point: witness_point,
+            value: alpha - shifted_witness,
+        },
+        table: Claim {
+            point: table_point.clone(),
+            value: alpha - shifted_table,
+        },
+        multiplicities: Claim {
+            point: table_point,
+            value: -negated_multiplicities,
+        },
+    })
+}
+
+// Computes the tree of fraction additions of the leaves p / q and reduces the claim on the root
+// to claims on the leaves: returns the proof, the point of the leaves and the claims (p(point), q(point))
+pub fn prove_fractional_sum<F: PrimeField>(
+    numerators: Vec<F>,
+    denominators: Vec<F>,
+    transcript: &mut Transcript,
+) -> (FractionalSumProof<F>, Vec<F>, (F, F)) {
+    assert_eq!(
+        numerators.len(),
+        denominators.len(),
+        "one denominator is needed per numerator"
+    );
+    assert!(
+        numerators.len().is_power_of_two(),
+        "the number of leaves must be a power of two"
+    );
+
+    let num_vars = numerators.len().ilog2() as usize;
+
+    // layers[i] = (p_i, q_i)
+    let mut layers = vec![(numerators, denominators)];
+    while layers[0].0.len() > 1 {
+        let (numerators, denominators) = &layers[0];
+        let parents: (Vec<F>, Vec<F>) = numerators
+            .chunks(2)
+            .zip(denominators.chunks(2))
+            .map(|(p, q)| (p[0] * q[1] + p[1] * q[0], q[0] * q[1]))
+            .unzip();
+        layers.insert(0, parents);
+    }
+
+    let (numerator, denominator) = (layers[0].0[0], layers[0].1[0]);
+    transcript.absorb(&field_element_to_bytes(numerator));
+    transcript.absorb(&field_element_to_bytes(denominator));
+
+    let mut claims = (numerator, denominator);
+    let mut point: Vec<F> = Vec::new();
+    let mut layer_proofs = Vec::with_capacity(num_vars);
+
+    for (numerators, denominators) in layers.iter().skip(1) {
+        let lambda: F = transcript.random_challenge_as_field_element();
+        let eq_point = MultilinearPolynomialEV::eq_polynomial(&point);
+
+        let [p_0, p_1, q_0, q_1] = [
+            (numerators, 0),
+            (numerators, 1),
+            (denominators, 0),
+            (denominators, 1),
+        ]
+        .map(|(values, offset)| {
+            let half: Vec<F> = values.iter().skip(offset).step_by(2).copied().collect();
+            MultilinearPolynomialEV::new(&half)
+        });
+
+        // eq * (p_0 * q_1 + p_1 * q_0 + lambda * q_0 * q_1)
+        let sum_polynomial = SumPolynomial::new(vec![
+            ProductPolynomial::new(vec![eq_point.clone(), p_0.clone(), q_1.clone()]),
+            ProductPolynomial::new(vec![eq_point.clone(), p_1.clone(), q_0.clone()]),
+            ProductPolynomial::new(vec![eq_point, q_0.scalar_mul(lambda), q_1.clone()]),
+        ]);
+        let sumcheck_proof =
+            sumcheck_prove(sum_polynomial, claims.0 + lambda * claims.1, transcript);
+        let sumcheck_point = sumcheck_proof.random_challenges.clone();
+
+        let numerator_evaluations = [p_0.evaluate(&sumcheck_point), p_1.evaluate(&sumcheck_point)];
+        let denominator_evaluations =
+            [q_0.evaluate(&sumcheck_point), q_1.evaluate(&sumcheck_point)];
+
+        let challenge =
+            absorb_layer_evaluations(&numerator_evaluations, &denominator_evaluations, transcript);
+        claims = (
+            merge_halves(numerator_evaluations, challenge),
+            merge_halves(denominator_evaluations, challenge),
+        );
+        point = sumcheck_point;
+        point.push(challenge);
+
+        layer_proofs.push(FractionalLayerProof {
+            sumcheck_proof,
+            numerator_evaluations,
+            denominator_evaluations,
+        });
+    }
+
+    (
+        FractionalSumProof {
+            numerator,
+            denominator,
+            layer_proofs,
+        },
+        point,
+        claims,
+    )
+}
+
+// Returns the point of the leaves and the claims (p(point), q(point)) when every layer reduction is valid, None otherwise
+pub fn verify_fractional_sum<F: PrimeField>(
+    proof: &FractionalSumProof<F>,
+    num_vars: usize,
+    transcript: &mut Transcript,
+) -> Option<(Vec<F>, F, F)> {
+    if proof.layer_proofs.len() != num_vars {
+        return None;
+    }
+
+    transcript.absorb(&field_element_to_bytes(proof.numerator));
+    transcript.absorb(&field_element_to_bytes(proof.denominator));
+
+    let mut claims = (proof.numerator, proof.denominator);
+    let mut point: Vec<F> = Vec::new();
+
+    for layer_proof in proof.layer_proofs.iter() {
+        let lambda: F = transcript.random_challenge_as_field_element();
+
+        if layer_proof
+            .sumcheck_proof
+            .round_univariate_polynomials
+            .len()
+            != point.len()
+            || layer_proof.sumcheck_proof.claimed_sum != claims.0 + lambda * claims.1
+        {
+            return None;
+        }
+
+        let verification = sumcheck_verify(&layer_proof.sumcheck_proof, transcript);
+        let [p_0, p_1] = layer_proof.numerator_evaluations;
+        let [q_0, q_1] = layer_proof.denominator_evaluations;
+
+        if !verification.is_proof_valid
+            || verification.last_claimed_sum
+                != eq_evaluation(&point, &verification.random_challenges)
+                    * (p_0 * q_1 + p_1 * q_0 + lambda * q_0 * q_1)
+        {
+            return None;
+        }
+
+        let challenge = absorb_layer_evaluations(
+            &layer_proof.numerator_evaluations,
+            &layer_proof.denominator_evaluations,
+            transcript,
+        );
+        claims = (
+            merge_halves(layer_proof.numerator_evaluations, challenge),
+            merge_halves(layer_proof.denominator_evaluations, challenge),
+        );
+        point = verification.random_challenges;
+        point.push(challenge);
+    }
+
+    Some((point, claims.0, claims.1))
+}
+
+// The sizes, then every commitment after its length
+fn absorb_statement(
+    witness_num_vars: u32,
+    table_num_vars: u32,
+    commitments: &[Vec<u8>],
+    transcript: &mut Transcript,
+) {
+    transcript.absorb(&witness_num_vars.to_be_bytes());
+    transcript.absorb(&table_num_vars.to_be_bytes());
+
+    for commitment in commitments.iter() {
+        transcript.absorb(&(commitment.len() as u64).to_be_bytes());
+        transcript.absorb(commitment);
+    }
+}
+
+// Absorbs the evaluations of a layer and draws the challenge merging its two halves
+fn absorb_layer_evaluations<F: PrimeField>(
+    numerator_evaluations: &[F; 2],
+    denominator_evaluations: &[F; 2],
+    transcript: &mut Transcript,
+) -> F {
+    for evaluation in numerator_evaluations
+        .iter()
+        .chain(denominator_evaluations.iter())
+    {
+        transcript.absorb(&field_element_to_bytes(*evaluation));
+    }
+
+    transcript.random_challenge_as_field_element()
+}
+
+// V(r', c) = (1 - c) * V(r', 0) + c * V(r', 1)
+fn merge_halves<F: PrimeField>([left, right]: [F; 2], challenge: F) -> F {
+    left + challenge * (right - left)
+}
@@ -0,0 +1,282 @@
+use crate::circuits::circuit::num_vars_for_size;
+use crate::evaluation::MultilinearPolynomialEV;
+use crate::gkr_p::Claim;
+use crate::gkr_sumcheck_dependencies::poly_sum::SumPolynomial;
+use crate::gkr_sumcheck_dependencies::transcript::Transcript;
+use crate::product_poly::ProductPolynomial;
+use crate::sum_check::{
+    field_element_to_bytes, prove as sumcheck_prove, verify as sumcheck_verify, SumcheckProverProof,
+};
+use ark_ff::PrimeField;
+
+// Thaler's sum-check for matrix multiplication.
+// The multilinear extension of a matrix is indexed by (row bits, column bits), the row bits are the first variables.
+// For C = A * B: C(x, y) = sum over k of A(x, k) * B(k, y), so after the verifier picks a random point (rx, ry),
+// a single sum-check over the log(inner) variables k reduces the claim C(rx, ry) to A(rx, rk) and B(rk, ry).
+// The prover only needs A(rx, k) and B(k, ry) for every k, which takes time linear in the sizes of A and B.
+// Every dimension is padded with zeros to a power of two (at least 2).
+
+// A is rows x inner, B is inner x columns and C is rows x columns, all stored row by row
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MatmulDimensions {
+    pub rows: usize,
+    pub inner: usize,
+    pub columns: usize,
+}
+
+impl MatmulDimensions {
+    pub fn new(rows: usize, inner: usize, columns: usize) -> Self {
+        assert!(
+            rows > 0 && inner > 0 && columns > 0,
+            "the matrices can't be empty"
+        );
+
+        Self {
+            rows,
+            inner,
+            columns,
+        }
+    }
+
+    fn row_vars(&self) -> usize {
+        num_vars_for_size(self.rows)
+    }
+
+    fn inner_vars(&self) -> usize {
+        num_vars_for_size(self.inner)
+    }
+
+    fn column_vars(&self) -> usize {
+        num_vars_for_size(self.columns)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MatmulProof<F: PrimeField> {
+    pub sumcheck_proof: SumcheckProverProof<F>,
+    pub a_evaluation: F,
+    pub b_evaluation: F,
+}
+
+// The claims on the multilinear extensions of the three matrices the proof reduces to
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatmulClaims<F: PrimeField> {
+    pub a: Claim<F>,
+    pub b: Claim<F>,
+    pub c: Claim<F>,
+}
+
+// The multilinear extension of a matrix stored row by row, every dimension padded with zeros
+pub fn matrix_mle<F: PrimeField>(
+    values: &[F],
+    rows: usize,
+    columns: usize,
+) -> MultilinearPolynomialEV<F> {
+    assert_eq!(
+        values.len(),
+        rows * columns,
+        "the matrix has the wrong size"
+    );
+
+    let padded_columns = 1 << num_vars_for_size(columns);
+    let mut padded_values = vec![F::zero(); (1 << num_vars_for_size(rows)) * padded_columns];
+
+    for (row_index, row) in values.chunks(columns).enumerate() {
+        padded_values[row_index * padded_columns..row_index * padded_columns + columns]
+            .copy_from_slice(row);
+    }
+
+    MultilinearPolynomialEV::new(&padded_values)
+}
+
+// Proves that c = a * b: c is absorbed, the point of the claim on C is drawn from the transcript
+pub fn prove<F: PrimeField>(
+    a: &[F],
+    b: &[F],
+    c: &[F],
+    dimensions: &MatmulDimensions,
+    transcript: &mut Transcript,
+) -> (MatmulProof<F>, MatmulClaims<F>) {
+    assert_eq!(
+        c.len(),
+        dimensions.rows * dimensions.columns,
+        "the matrix has the wrong size"
+    );
+
+    let point = c_point(c, dimensions, transcript);
+
+    prove_at_point(a, b, dimensions, &point, transcript)
+}
+
+// The claims on A and B are left to the caller, the claim on C is checked against c
+pub fn verify<F: PrimeField>(
+    proof: &MatmulProof<F>,
+    c: &[F],
+    dimensions: &MatmulDimensions,
+    transcript: &mut Transcript,
+) -> Option<MatmulClaims<F>> {
+    if c.len() != dimensions.rows * dimensions.columns {
+        return None;
+    }
+
+    let point = c_point(c, dimensions, transcript);
+    let c_claim = Claim {
+        value: matrix_mle(c, dimensions.rows, dimensions.columns).evaluate(&point),
+        point,
+    };
+
+    verify_at_point(proof, dimensions, &c_claim, transcript)
+}
+
+// Reduces the evaluation of the multilinear extension of a * b at point, eg: a claim left by another protocol,
+// to claims on A and B
+pub fn prove_at_point<F: PrimeField>(
+    a: &[F],
+    b: &[F],
+    dimensions: &MatmulDimensions,
+    point: &[F],
+    transcript: &mut Transcript,
+) -> (MatmulProof<F>, MatmulClaims<F>) {
+    assert_eq!(
+        a.len(),
+        dimensions.rows * dimensions.inner,
+        "the matrix has the wrong size"
+    );
+    assert_eq!(
+        b.len(),
+        dimensions.inner * dimensions.columns,
+        "the matrix has the wrong size"
+    );
+    assert_eq!(
+        point.len(),
+        dimensions.row_vars() + dimensions.column_vars(),
+        "the point has the wrong number of variables"
+    );
+
+    let (row_point, column_point) = point.split_at(dimensions.row_vars());
+    let row_weights = MultilinearPolynomialEV::eq_polynomial(row_point).evaluated_values;
+    let column_weights = MultilinearPolynomialEV::eq_polynomial(column_point).evaluated_values;
+    let padded_inner = 1 << dimensions.inner_vars();
+
+    // A(rx, k) = sum over i of eq(rx, i) * A[i][k], B(k, ry) = sum over j of eq(ry, j) * B[k][j]
+    let mut a_values = vec![F::zero(); padded_inner];
+    for (row, weight) in a.chunks(dimensions.inner).zip(row_weights.iter()) {
+        for (a_value, value) in a_values.iter_mut().zip(row.iter()) {
+            *a_value += *weight * value;
+        }
+    }
+
+    let mut b_values = vec![F::zero(); padded_inner];
+    for (b_value, row) in b_values.iter_mut().zip(b.chunks(dimensions.columns)) {
+        *b_value = row
+            .iter()
+            .zip(column_weights.iter())
+            .map(|(value, weight)| *value * weight)
+            .sum();
+    }
+
+    let a_polynomial = MultilinearPolynomialEV::new(&a_values);
+    let b_polynomial = MultilinearPolynomialEV::new(&b_values);
+    let claimed_sum: F = a_values
+        .iter()
+        .zip(b_values.iter())
+        .map(|(a_value, b_value)| *a_value * b_value)
+        .sum();
+
+    let sum_polynomial = SumPolynomial::new(vec![ProductPolynomial::new(vec![
+        a_polynomial.clone(),
+        b_polynomial.clone(),
+    ])]);
+    let sumcheck_proof = sumcheck_prove(sum_polynomial, claimed_sum, transcript);
+
+    let inner_point = sumcheck_proof.random_challenges.clone();
+    let a_evaluation = a_polynomial.evaluate(&inner_point);
+    let b_evaluation = b_polynomial.evaluate(&inner_point);
+
+    transcript.absorb(&field_element_to_bytes(a_evaluation));
+    transcript.absorb(&field_element_to_bytes(b_evaluation));
+
+    let claims = matmul_claims(
+        row_point,
+        column_point,
+        &inner_point,
+        (a_evaluation, b_evaluation, claimed_sum),
+    );
+
+    (
+        MatmulProof {
+            sumcheck_proof,
+            a_evaluation,
+            b_evaluation,
+        },
+        claims,
+    )
+}
+
+// Returns the claims on A and B (and c_claim) when the proof reduces c_claim to them, None otherwise
+pub fn verify_at_point<F: PrimeField>(
+    proof: &MatmulProof<F>,
+    dimensions: &MatmulDimensions,
+    c_claim: &Claim<F>,
+    transcript: &mut Transcript,
+) -> Option<MatmulClaims<F>> {
+    if c_claim.point.len() != dimensions.row_vars() + dimensions.column_vars()
+        || proof.sumcheck_proof.claimed_sum != c_claim.value
+        || proof.sumcheck_proof.round_univariate_polynomials.len() != dimensions.inner_vars()
+    {
+        return None;
+    }
+
+    let verification = sumcheck_verify(&proof.sumcheck_proof, transcript);
+    if !verification.is_proof_valid
+        || verification.last_claimed_sum != proof.a_evaluation * proof.b_evaluation
+    {
+        return None;
+    }
+
+    transcript.absorb(&field_element_to_bytes(proof.a_evaluation));
+    transcript.absorb(&field_element_to_bytes(proof.b_evaluation));
+
+    let (row_point, column_point) = c_claim.point.split_at(dimensions.row_vars());
+
+    Some(matmul_claims(
+        row_point,
+        column_point,
+        &verification.random_challenges,
+        (proof.a_evaluation, proof.b_evaluation, c_claim.value),
+    ))
+}
+
+fn c_point<F: PrimeField>(
+    c: &[F],
+    dimensions: &MatmulDimensions,
+    transcript: &mut Transcript,
+) -> Vec<F> {
+    transcript.absorb(&matrix_mle(c, dimensions.rows, dimensions.columns).convert_to_bytes());
+
+    (0..dimensions.row_vars() + dimensions.column_vars())
+        .map(|_| transcript.random_challenge_as_field_element())
+        .collect()
+}
+
+fn matmul_claims<F: PrimeField>(
+    row_point: &[F],
+    column_point: &[F],
+    inner_point: &[F],
+    (a_evaluation, b_evaluation, c_evaluation): (F, F, F),
+) -> MatmulClaims<F> {
+    MatmulClaims {
+        a: Claim {
+            point: [row_point, inner_point].concat(),
+            value: a_evaluation,
+        },
+        b: Claim {
+            point: [inner_point, column_point].concat(),
+            value: b_evaluation,
+        },
+        c: Claim {
+            point: [row_point, column_point].concat(),
+            value: c_evaluation,
+        },
+    }
+}
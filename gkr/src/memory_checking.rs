@@ -0,0 +1,438 @@
+use crate::evaluation::MultilinearPolynomialEV;
+use crate::gkr_p::Claim;
+use crate::gkr_sumcheck_dependencies::transcript::Transcript;
+use crate::grand_product::{prove_batch, verify_batch, BatchGrandProductProof};
+use crate::logup::{self, LogUpProof};
+use crate::sum_check::absorb_elements;
+use ark_ff::PrimeField;
+use std::collections::HashMap;
+
+// Offline memory checking (Spice / Lasso): a log of reads and writes to a memory is consistent
+// if every read returns the last value written to its address.
+// Every operation k reads the current (address, value, timestamp) of a cell, then writes (address, value', k + 1) back,
+// a read writing back the value it read. With the initial cells (address, initial value, 0) and the final cells,
+// the log is consistent if and only if init + writes = reads + final as multisets
+// and every read timestamp is at most k.
+// The multisets are compared with the fingerprints h(a, v, t) = gamma^2 * a + gamma * v + t - tau for random gamma, tau:
+// the grand products of the fingerprints of init and writes and of reads and final must be equal.
+// The timestamps are checked with a LogUp lookup of k - read timestamp in the table 0..number of operations.
+// The leaves of the grand products are fingerprints of the columns of the trace, so the proof ends in claims
+// on the columns held by the prover, returned to be checked by the caller (eg: against commitments).
+// The columns and the timestamp multiplicities must be fixed before gamma and tau are drawn:
+// prove and verify take the commitments to them and absorb them in the transcript with the instance.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessRecord<F: PrimeField> {
+    pub address: usize,
+    pub read_value: F,
+    pub read_timestamp: usize,
+    pub write_value: F,
+}
+
+// The read / write log of a memory of a power of two size
+#[derive(Clone, Debug, PartialEq)]
+pub struct MemoryLog<F: PrimeField> {
+    pub initial_memory: Vec<F>,
+    pub records: Vec<AccessRecord<F>>,
+    memory: Vec<F>,
+    timestamps: Vec<usize>,
+}
+
+impl<F: PrimeField> MemoryLog<F> {
+    pub fn new(initial_memory: Vec<F>) -> Self {
+        assert!(
+            initial_memory.len().is_power_of_two(),
+            "the memory size must be a power of two"
+        );
+
+        Self {
+            memory: initial_memory.clone(),
+            timestamps: vec![0; initial_memory.len()],
+            initial_memory,
+            records: Vec::new(),
+        }
+    }
+
+    pub fn read(&mut self, address: usize) -> F {
+        let value = self.memory[address];
+        self.access(address, value);
+
+        value
+    }
+
+    pub fn write(&mut self, address: usize, value: F) {
+        self.access(address, value);
+    }
+
+    fn access(&mut self, address: usize, write_value: F) {
+        assert!(address < self.memory.len(), "address out of the memory");
+
+        self.records.push(AccessRecord {
+            address,
+            read_value: self.memory[address],
+            read_timestamp: self.timestamps[address],
+            write_value,
+        });
+
+        self.memory[address] = write_value;
+        self.timestamps[address] = self.records.len();
+    }
+
+    // The columns proven by the memory checking argument: the log is padded with reads of address 0
+    // to a power of two number of operations (at least 2)
+    pub fn trace(&self) -> MemoryTrace<F> {
+        let mut log = self.clone();
+        while log.records.len() < 2 || !log.records.len().is_power_of_two() {
+            log.read(0);
+        }
+
+        let column = |value: fn(&AccessRecord<F>) -> F| log.records.iter().map(value).collect();
+        let timestamps = |timestamps: &[usize]| {
+            timestamps
+                .iter()
+                .map(|timestamp| F::from(*timestamp as u64))
+                .collect()
+        };
+
+        MemoryTrace {
+            addresses: column(|record| F::from(record.address as u64)),
+            read_values: column(|record| record.read_value),
+            read_timestamps: column(|record| F::from(record.read_timestamp as u64)),
+            write_values: column(|record| record.write_value),
+            final_values: log.memory,
+            final_timestamps: timestamps(&log.timestamps),
+            initial_memory: log.initial_memory,
+        }
+    }
+}
+
+// The columns of the operations (one entry per operation) and of the memory (one entry per cell)
+#[derive(Clone, Debug, PartialEq)]
+pub struct MemoryTrace<F: PrimeField> {
+    pub initial_memory: Vec<F>,
+    pub addresses: Vec<F>,
+    pub read_values: Vec<F>,
+    pub read_timestamps: Vec<F>,
+    pub write_values: Vec<F>,
+    pub final_values: Vec<F>,
+    pub final_timestamps: Vec<F>,
+}
+
+impl<F: PrimeField> MemoryTrace<F> {
+    pub fn num_operations(&self) -> usize {
+        self.addresses.len()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MemoryCheckingProof<F: PrimeField> {
+    // init then final
+    pub memory_products: BatchGrandProductProof<F>,
+    // reads then writes
+    pub operation_products: BatchGrandProductProof<F>,
+    // final value, final timestamp
+    pub memory_evaluations: [F; 2],
+    // address, read value, read timestamp, write value
+    pub operation_evaluations: [F; 4],
+    pub timestamp_proof: LogUpProof<F>,
+}
+
+// The claims on the columns of the trace
+#[derive(Clone, Debug, PartialEq)]
+pub struct MemoryCheckingClaims<F: PrimeField> {
+    pub addresses: Claim<F>,
+    pub read_values: Claim<F>,
+    // at the point of the grand products, then at the point of the timestamp lookup
+    pub read_timestamps: [Claim<F>; 2],
+    pub write_values: Claim<F>,
+    pub final_values: Claim<F>,
+    pub final_timestamps: Claim<F>,
+    // The multiplicities of the timestamp lookup
+    pub timestamp_multiplicities: Claim<F>,
+}
+
+pub fn prove<F: PrimeField>(
+    trace: &MemoryTrace<F>,
+    commitments: &[Vec<u8>],
+    transcript: &mut Transcript,
+) -> (MemoryCheckingProof<F>, MemoryCheckingClaims<F>) {
+    let num_operations = trace.num_operations();
+    let memory_size = trace.initial_memory.len();
+    assert!(
+        num_operations.is_power_of_two() && memory_size.is_power_of_two(),
+        "the trace must have a power of two number of operations and of cells"
+    );
+
+    absorb_instance(
+        &trace.initial_memory,
+        num_operations,
+        commitments,
+        transcript,
+    );
+    let gamma: F = transcript.random_challenge_as_field_element();
+    let tau: F = transcript.random_challenge_as_field_element();
+
+    let cell_addresses: Vec<F> = counter(memory_size);
+    let write_timestamps: Vec<F> = counter::<F>(num_operations)
+        .into_iter()
+        .map(|timestamp| timestamp + F::one())
+        .collect();
+    let fingerprints = |addresses: &[F], values: &[F], timestamps: &[F]| {
+        let values: Vec<F> = addresses
+            .iter()
+            .zip(values.iter().zip(timestamps.iter()))
+            .map(|(address, (value, timestamp))| {
+                fingerprint(gamma, tau, *address, *value, *timestamp)
+            })
+            .collect();
+        MultilinearPolynomialEV::new(&values)
+    };
+
+    let (memory_products, memory_claims) = prove_batch(
+        &[
+            fingerprints(
+                &cell_addresses,
+                &trace.initial_memory,
+                &vec![F::zero(); memory_size],
+            ),
+            fingerprints(
+                &cell_addresses,
+                &trace.final_values,
+                &trace.final_timestamps,
+            ),
+        ],
+        transcript,
+    );
+    let (operation_products, operation_claims) = prove_batch(
+        &[
+            fingerprints(&trace.addresses, &trace.read_values, &trace.read_timestamps),
+            fingerprints(&trace.addresses, &trace.write_values, &write_timestamps),
+        ],
+        transcript,
+    );
+
+    let memory_point = &memory_claims[0].point;
+    let operation_point = &operation_claims[0].point;
+    let evaluate = |values: &[F], point: &[F]| MultilinearPolynomialEV::new(values).evaluate(point);
+
+    let memory_evaluations = [
+        evaluate(&trace.final_values, memory_point),
+        evaluate(&trace.final_timestamps, memory_point),
+    ];
+    let operation_evaluations = [
+        evaluate(&trace.addresses, operation_point),
+        evaluate(&trace.read_values, operation_point),
+        evaluate(&trace.read_timestamps, operation_point),
+        evaluate(&trace.write_values, operation_point),
+    ];
+    absorb_elements(&memory_evaluations, transcript);
+    absorb_elements(&operation_evaluations, transcript);
+
+    // The lookup columns are already bound by the commitments absorbed with the instance
+    let (timestamp_proof, lookup_claims) = logup::prove(
+        &MultilinearPolynomialEV::new(&timestamp_differences(trace)),
+        &MultilinearPolynomialEV::new(&counter(num_operations)),
+        &MultilinearPolynomialEV::new(&timestamp_multiplicities(trace)),
+        &[],
+        transcript,
+    );
+
+    let claims = memory_checking_claims(
+        memory_point,
+        &memory_evaluations,
+        operation_point,
+        &operation_evaluations,
+        &lookup_claims,
+    );
+
+    (
+        MemoryCheckingProof {
+            memory_products,
+            operation_products,
+            memory_evaluations,
+            operation_evaluations,
+            timestamp_proof,
+        },
+        claims,
+    )
+}
+
+// Returns the claims on the columns of the trace when the proof is valid, None otherwise
+pub fn verify<F: PrimeField>(
+    initial_memory: &[F],
+    num_operations: usize,
+    proof: &MemoryCheckingProof<F>,
+    commitments: &[Vec<u8>],
+    transcript: &mut Transcript,
+) -> Option<MemoryCheckingClaims<F>> {
+    let memory_size = initial_memory.len();
+    let (memory_products, operation_products) = (
+        &proof.memory_products.products,
+        &proof.operation_products.products,
+    );
+
+    if !num_operations.is_power_of_two()
+        || !memory_size.is_power_of_two()
+        || memory_products.len() != 2
+        || operation_products.len() != 2
+        // init * writes = reads * final
+        || memory_products[0] * operation_products[1] != operation_products[0] * memory_products[1]
+    {
+        return None;
+    }
+
+    absorb_instance(initial_memory, num_operations, commitments, transcript);
+    let gamma: F = transcript.random_challenge_as_field_element();
+    let tau: F = transcript.random_challenge_as_field_element();
+
+    let memory_num_vars = memory_size.ilog2();
+    let operations_num_vars = num_operations.ilog2();
+
+    let memory_claims = verify_batch(&proof.memory_products, memory_num_vars as usize, transcript)?;
+    let operation_claims = verify_batch(
+        &proof.operation_products,
+        operations_num_vars as usize,
+        transcript,
+    )?;
+
+    let memory_point = &memory_claims[0].point;
+    let operation_point = &operation_claims[0].point;
+    let cell_address = MultilinearPolynomialEV::new(&counter(memory_size)).evaluate(memory_point);
+    let operation_counter =
+        MultilinearPolynomialEV::new(&counter(num_operations)).evaluate(operation_point);
+
+    let [final_value, final_timestamp] = proof.memory_evaluations;
+    let [address, read_value, read_timestamp, write_value] = proof.operation_evaluations;
+    let initial_value = MultilinearPolynomialEV::new(initial_memory).evaluate(memory_point);
+
+    if memory_claims[0].value != fingerprint(gamma, tau, cell_address, initial_value, F::zero())
+        || memory_claims[1].value
+            != fingerprint(gamma, tau, cell_address, final_value, final_timestamp)
+        || operation_claims[0].value != fingerprint(gamma, tau, address, read_value, read_timestamp)
+        || operation_claims[1].value
+            != fingerprint(
+                gamma,
+                tau,
+                address,
+                write_value,
+                operation_counter + F::one(),
+            )
+    {
+        return None;
+    }
+
+    absorb_elements(&proof.memory_evaluations, transcript);
+    absorb_elements(&proof.operation_evaluations, transcript);
+
+    let lookup_claims = logup::verify(
+        &proof.timestamp_proof,
+        operations_num_vars,
+        operations_num_vars,
+        &[],
+        transcript,
+    )?;
+
+    // The table of the lookup is 0..number of operations
+    if lookup_claims.table.value
+        != MultilinearPolynomialEV::new(&counter(num_operations))
+            .evaluate(&lookup_claims.table.point)
+    {
+        return None;
+    }
+
+    Some(memory_checking_claims(
+        memory_point,
+        &proof.memory_evaluations,
+        operation_point,
+        &proof.operation_evaluations,
+        &lookup_claims,
+    ))
+}
+
+// The multiplicities of the timestamp lookup, committed to by the caller with the columns of the trace.
+// A difference out of the table is left out of the multiplicities, the lookup then fails
+pub fn timestamp_multiplicities<F: PrimeField>(trace: &MemoryTrace<F>) -> Vec<F> {
+    let num_operations = trace.num_operations();
+    let positions: HashMap<F, usize> = counter(num_operations)
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| (value, index))
+        .collect();
+
+    let mut multiplicities = vec![F::zero(); num_operations];
+    for difference in timestamp_differences(trace).iter() {
+        if let Some(index) = positions.get(difference) {
+            multiplicities[*index] += F::one();
+        }
+    }
+
+    multiplicities
+}
+
+// k - read timestamp, which is in 0..number of operations
+fn timestamp_differences<F: PrimeField>(trace: &MemoryTrace<F>) -> Vec<F> {
+    counter::<F>(trace.num_operations())
+        .iter()
+        .zip(trace.read_timestamps.iter())
+        .map(|(operation, timestamp)| *operation - timestamp)
+        .collect()
+}
+
+// h(a, v, t) = gamma^2 * a + gamma * v + t - tau
+fn fingerprint<F: PrimeField>(gamma: F, tau: F, address: F, value: F, timestamp: F) -> F {
+    (address * gamma + value) * gamma + timestamp - tau
+}
+
+// 0, 1, ..., size - 1
+fn counter<F: PrimeField>(size: usize) -> Vec<F> {
+    (0..size).map(|index| F::from(index as u64)).collect()
+}
+
+fn memory_checking_claims<F: PrimeField>(
+    memory_point: &[F],
+    [final_value, final_timestamp]: &[F; 2],
+    operation_point: &[F],
+    [address, read_value, read_timestamp, write_value]: &[F; 4],
+    lookup_claims: &logup::LogUpClaims<F>,
+) -> MemoryCheckingClaims<F> {
+    let claim = |point: &[F], value: &F| Claim {
+        point: point.to_vec(),
+        value: *value,
+    };
+
+    // difference(r) = k(r) - read timestamp(r)
+    let lookup_point = &lookup_claims.witness.point;
+    let operation_counter =
+        MultilinearPolynomialEV::new(&counter(1 << lookup_point.len())).evaluate(lookup_point);
+
+    MemoryCheckingClaims {
+        addresses: claim(operation_point, address),
+        read_values: claim(operation_point, read_value),
+        read_timestamps: [
+            claim(operation_point, read_timestamp),
+            claim(
+                lookup_point,
+                &(operation_counter - lookup_claims.witness.value),
+            ),
+        ],
+        write_values: claim(operation_point, write_value),
+        final_values: claim(memory_point, final_value),
+        final_timestamps: claim(memory_point, final_timestamp),
+        timestamp_multiplicities: lookup_claims.multiplicities.clone(),
+    }
+}
+
+fn absorb_instance<F: PrimeField>(
+    initial_memory: &[F],
+    num_operations: usize,
+    commitments: &[Vec<u8>],
+    transcript: &mut Transcript,
+) {
+    transcript.absorb(&(num_operations as u64).to_be_bytes());
+    absorb_elements(initial_memory, transcript);
+
+    for commitment in commitments.iter() {
+        transcript.absorb(&(commitment.len() as u64).to_be_bytes());
+        transcript.absorb(commitment);
+    }
+}
@@ -0,0 +1,2 @@
+// The Merkle tree lives in fiat_shamir, next to the transcript, and is shared by both crates
+pub use fiat_shamir::merkle_tree::*;
@@ -0,0 +1,490 @@
+use crate::circuits::circuit::{num_vars_for_size, pad_to_num_vars};
+use crate::evaluation::MultilinearPolynomialEV;
+use crate::gkr_p::Claim;
+use crate::gkr_sumcheck_dependencies::poly_sum::SumPolynomial;
+use crate::gkr_sumcheck_dependencies::transcript::Transcript;
+use crate::grand_product::{prove_batch, verify_batch, BatchGrandProductProof};
+use crate::product_poly::ProductPolynomial;
+use crate::sum_check::{
+    absorb_elements, prove as sumcheck_prove, verify as sumcheck_verify, SumcheckProverProof,
+};
+use crate::utility::utils::eq_evaluation;
+use ark_ff::PrimeField;
+
+// HyperPlonk-style Plonkish constraint systems, proven over the boolean hypercube.
+// The rows of the table are the points of the hypercube: every wire and every selector is a multilinear polynomial.
+// Gate identity: gate(selectors(x), wires(x)) + PI(x) = 0 for every row x, where PI(x) = -public_input_x on the first rows.
+// It is proven with a zerocheck: sum over x of eq(tau, x) * (gate(x) + PI(x)) = 0 for a random tau.
+// Copy constraints: the wires are equal along the cycles of the permutation sigma of the positions (wire, row),
+// proven with the products over every wire j and row x of
+// (w_j(x) + beta * id_j(x) + gamma) and (w_j(x) + beta * sigma_j(x) + gamma), which are equal for random beta, gamma.
+// The products are proven together with the grand product argument, which leaves claims on the leaves,
+// and the leaves only depend on the wires and on id and sigma, which the verifier evaluates itself.
+// Both checks end in claims on the wires, returned to be checked by the caller (eg: against commitments).
+
+// A term of the gate: selector * product of the wires (a wire can be repeated for powers)
+#[derive(Clone, Debug, PartialEq)]
+pub struct GateTerm {
+    pub selector: usize,
+    pub wires: Vec<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlonkishGate {
+    pub num_selectors: usize,
+    pub num_wires: usize,
+    pub terms: Vec<GateTerm>,
+}
+
+impl PlonkishGate {
+    pub fn new(num_selectors: usize, num_wires: usize, terms: Vec<GateTerm>) -> Self {
+        assert!(num_wires > 0, "at least one wire is needed");
+        assert!(!terms.is_empty(), "the gate needs at least one term");
+        assert!(
+            terms.iter().all(|term| term.selector < num_selectors
+                && term.wires.iter().all(|wire| *wire < num_wires)),
+            "unknown selector or wire in the gate"
+        );
+
+        Self {
+            num_selectors,
+            num_wires,
+            terms,
+        }
+    }
+
+    // q_L * w_0 + q_R * w_1 + q_M * w_0 * w_1 + q_O * w_2 + q_C, with the selectors in this order
+    pub fn vanilla() -> Self {
+        let term = |selector, wires: &[usize]| GateTerm {
+            selector,
+            wires: wires.to_vec(),
+        };
+
+        Self::new(
+            5,
+            3,
+            vec![
+                term(0, &[0]),
+                term(1, &[1]),
+                term(2, &[0, 1]),
+                term(3, &[2]),
+                term(4, &[]),
+            ],
+        )
+    }
+
+    pub fn evaluate<F: PrimeField>(&self, selectors: &[F], wires: &[F]) -> F {
+        self.terms
+            .iter()
+            .map(|term| {
+                term.wires
+                    .iter()
+                    .fold(selectors[term.selector], |product, wire| {
+                        product * wires[*wire]
+                    })
+            })
+            .sum()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WirePosition {
+    pub wire: usize,
+    pub row: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlonkishCircuit<F: PrimeField> {
+    pub gate: PlonkishGate,
+    pub num_vars: usize,
+    pub selectors: Vec<MultilinearPolynomialEV<F>>,
+    // sigma over the positions wire * 2^num_vars + row
+    pub permutation: Vec<usize>,
+    pub num_public_inputs: usize,
+}
+
+impl<F: PrimeField> PlonkishCircuit<F> {
+    // selectors[s] holds the values of the selector s on every row, the rows are padded with zero selectors,
+    // which satisfy the gate whatever the wires.
+    // Every copy constraint makes two positions equal, the public inputs are on the first rows
+    pub fn new(
+        gate: PlonkishGate,
+        selectors: &[Vec<F>],
+        copy_constraints: &[(WirePosition, WirePosition)],
+        num_public_inputs: usize,
+    ) -> Self {
+        assert_eq!(
+            selectors.len(),
+            gate.num_selectors,
+            "one column of values is needed per selector"
+        );
+        let num_rows = selectors[0].len();
+        assert!(
+            selectors.iter().all(|column| column.len() == num_rows),
+            "the selectors must have the same number of rows"
+        );
+        assert!(
+            num_public_inputs <= num_rows,
+            "the public inputs are on the first rows"
+        );
+
+        let num_vars = num_vars_for_size(num_rows);
+        let num_positions = gate.num_wires << num_vars;
+
+        // Union find over the positions, every class becomes a cycle of sigma
+        let mut parents: Vec<usize> = (0..num_positions).collect();
+        fn find(parents: &mut [usize], position: usize) -> usize {
+            let mut root = position;
+            while parents[root] != root {
+                root = parents[root];
+            }
+            parents[position] = root;
+            root
+        }
+
+        for (left, right) in copy_constraints.iter() {
+            assert!(
+                left.wire < gate.num_wires
+                    && right.wire < gate.num_wires
+                    && left.row < num_rows
+                    && right.row < num_rows,
+                "copy constraint out of the table"
+            );
+
+            let left_root = find(&mut parents, (left.wire << num_vars) + left.row);
+            let right_root = find(&mut parents, (right.wire << num_vars) + right.row);
+            parents[left_root] = right_root;
+        }
+
+        let mut classes: Vec<Vec<usize>> = vec![Vec::new(); num_positions];
+        for position in 0..num_positions {
+            let root = find(&mut parents, position);
+            classes[root].push(position);
+        }
+
+        let mut permutation: Vec<usize> = (0..num_positions).collect();
+        for class in classes.iter() {
+            for (index, position) in class.iter().enumerate() {
+                permutation[*position] = class[(index + 1) % class.len()];
+            }
+        }
+
+        Self {
+            num_vars,
+            selectors: selectors
+                .iter()
+                .map(|column| pad_to_num_vars(column, num_vars))
+                .collect(),
+            gate,
+            permutation,
+            num_public_inputs,
+        }
+    }
+
+    pub fn num_rows(&self) -> usize {
+        1 << self.num_vars
+    }
+
+    // The columns of wire values, padded with zeros
+    pub fn wire_mles(&self, wires: &[Vec<F>]) -> Vec<MultilinearPolynomialEV<F>> {
+        assert_eq!(
+            wires.len(),
+            self.gate.num_wires,
+            "one column of values is needed per wire"
+        );
+
+        wires
+            .iter()
+            .map(|column| pad_to_num_vars(column, self.num_vars))
+            .collect()
+    }
+
+    // PI(x) = -public_input_x on the first rows, 0 on the others
+    pub fn public_input_mle(&self, public_inputs: &[F]) -> MultilinearPolynomialEV<F> {
+        let values: Vec<F> = public_inputs.iter().map(|value| -*value).collect();
+
+        pad_to_num_vars(&values, self.num_vars)
+    }
+
+    // id_j(x) = j * 2^num_vars + x
+    pub fn identity_mle(&self, wire: usize) -> MultilinearPolynomialEV<F> {
+        let values: Vec<F> = (0..self.num_rows())
+            .map(|row| F::from(((wire << self.num_vars) + row) as u64))
+            .collect();
+
+        MultilinearPolynomialEV::new(&values)
+    }
+
+    pub fn permutation_mle(&self, wire: usize) -> MultilinearPolynomialEV<F> {
+        let values: Vec<F> = self.permutation[wire << self.num_vars..(wire + 1) << self.num_vars]
+            .iter()
+            .map(|position| F::from(*position as u64))
+            .collect();
+
+        MultilinearPolynomialEV::new(&values)
+    }
+
+    pub fn is_satisfied(&self, public_inputs: &[F], wires: &[Vec<F>]) -> bool {
+        if public_inputs.len() != self.num_public_inputs
+            || wires.len() != self.gate.num_wires
+            || wires.iter().any(|column| column.len() > self.num_rows())
+        {
+            return false;
+        }
+
+        let wires = self.wire_mles(wires);
+        let public_input = self.public_input_mle(public_inputs);
+
+        let gates_hold = (0..self.num_rows()).all(|row| {
+            let selector_values: Vec<F> = self
+                .selectors
+                .iter()
+                .map(|selector| selector.evaluated_values[row])
+                .collect();
+            let wire_values: Vec<F> = wires
+                .iter()
+                .map(|wire| wire.evaluated_values[row])
+                .collect();
+
+            self.gate.evaluate(&selector_values, &wire_values) + public_input.evaluated_values[row]
+                == F::zero()
+        });
+
+        let value = |position: usize| {
+            wires[position >> self.num_vars].evaluated_values[position & (self.num_rows() - 1)]
+        };
+        let copies_hold = self
+            .permutation
+            .iter()
+            .enumerate()
+            .all(|(position, next)| value(position) == value(*next));
+
+        gates_hold && copies_hold
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PlonkishProof<F: PrimeField> {
+    pub zerocheck_proof: SumcheckProverProof<F>,
+    pub gate_wire_evaluations: Vec<F>,
+    // The products of the identity leaves of every wire, then of the permutation leaves
+    pub permutation_proof: BatchGrandProductProof<F>,
+    pub permutation_wire_evaluations: Vec<F>,
+}
+
+// The claims on every wire left by the gate identity and by the copy constraints
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlonkishClaims<F: PrimeField> {
+    pub gate: Vec<Claim<F>>,
+    pub permutation: Vec<Claim<F>>,
+}
+
+pub fn prove<F: PrimeField>(
+    circuit: &PlonkishCircuit<F>,
+    public_inputs: &[F],
+    wires: &[Vec<F>],
+    transcript: &mut Transcript,
+) -> (PlonkishProof<F>, PlonkishClaims<F>) {
+    assert_eq!(
+        public_inputs.len(),
+        circuit.num_public_inputs,
+        "wrong number of public inputs"
+    );
+
+    let wires = circuit.wire_mles(wires);
+
+    absorb_instance(circuit, public_inputs, transcript);
+
+    // Zerocheck of the gate identity
+    let tau: Vec<F> = (0..circuit.num_vars)
+        .map(|_| transcript.random_challenge_as_field_element())
+        .collect();
+    let eq_tau = MultilinearPolynomialEV::eq_polynomial(&tau);
+
+    let mut products: Vec<ProductPolynomial<F>> = circuit
+        .gate
+        .terms
+        .iter()
+        .map(|term| {
+            let mut factors = vec![eq_tau.clone(), circuit.selectors[term.selector].clone()];
+            factors.extend(term.wires.iter().map(|wire| wires[*wire].clone()));
+            ProductPolynomial::new(factors)
+        })
+        .collect();
+    products.push(ProductPolynomial::new(vec![
+        eq_tau,
+        circuit.public_input_mle(public_inputs),
+    ]));
+
+    let zerocheck_proof = sumcheck_prove(SumPolynomial::new(products), F::zero(), transcript);
+    let gate_point = zerocheck_proof.random_challenges.clone();
+    let gate_wire_evaluations: Vec<F> = wires
+        .iter()
+        .map(|wire| wire.evaluate(&gate_point))
+        .collect();
+    absorb_elements(&gate_wire_evaluations, transcript);
+
+    // Product check of the copy constraints
+    let beta: F = transcript.random_challenge_as_field_element();
+    let gamma: F = transcript.random_challenge_as_field_element();
+
+    let leaf = |wire: &MultilinearPolynomialEV<F>, positions: MultilinearPolynomialEV<F>| {
+        let values: Vec<F> = wire
+            .evaluated_values
+            .iter()
+            .zip(positions.evaluated_values.iter())
+            .map(|(value, position)| *value + beta * position + gamma)
+            .collect();
+        MultilinearPolynomialEV::new(&values)
+    };
+    let mut leaves: Vec<MultilinearPolynomialEV<F>> = (0..circuit.gate.num_wires)
+        .map(|index| leaf(&wires[index], circuit.identity_mle(index)))
+        .collect();
+    leaves.extend(
+        (0..circuit.gate.num_wires)
+            .map(|index| leaf(&wires[index], circuit.permutation_mle(index))),
+    );
+
+    let (permutation_proof, leaf_claims) = prove_batch(&leaves, transcript);
+    let permutation_point = leaf_claims[0].point.clone();
+    let permutation_wire_evaluations: Vec<F> = wires
+        .iter()
+        .map(|wire| wire.evaluate(&permutation_point))
+        .collect();
+    absorb_elements(&permutation_wire_evaluations, transcript);
+
+    let claims = plonkish_claims(
+        &gate_point,
+        &gate_wire_evaluations,
+        &permutation_point,
+        &permutation_wire_evaluations,
+    );
+
+    (
+        PlonkishProof {
+            zerocheck_proof,
+            gate_wire_evaluations,
+            permutation_proof,
+            permutation_wire_evaluations,
+        },
+        claims,
+    )
+}
+
+// Returns the claims on the wires when the proof is valid, None otherwise
+pub fn verify<F: PrimeField>(
+    circuit: &PlonkishCircuit<F>,
+    public_inputs: &[F],
+    proof: &PlonkishProof<F>,
+    transcript: &mut Transcript,
+) -> Option<PlonkishClaims<F>> {
+    let num_wires = circuit.gate.num_wires;
+
+    if public_inputs.len() != circuit.num_public_inputs
+        || proof.zerocheck_proof.claimed_sum != F::zero()
+        || proof.zerocheck_proof.round_univariate_polynomials.len() != circuit.num_vars
+        || proof.gate_wire_evaluations.len() != num_wires
+        || proof.permutation_wire_evaluations.len() != num_wires
+    {
+        return None;
+    }
+
+    absorb_instance(circuit, public_inputs, transcript);
+
+    let tau: Vec<F> = (0..circuit.num_vars)
+        .map(|_| transcript.random_challenge_as_field_element())
+        .collect();
+
+    let zerocheck = sumcheck_verify(&proof.zerocheck_proof, transcript);
+    if !zerocheck.is_proof_valid {
+        return None;
+    }
+
+    let gate_point = zerocheck.random_challenges;
+    let selector_evaluations: Vec<F> = circuit
+        .selectors
+        .iter()
+        .map(|selector| selector.evaluate(&gate_point))
+        .collect();
+    let gate_evaluation = circuit
+        .gate
+        .evaluate(&selector_evaluations, &proof.gate_wire_evaluations)
+        + circuit
+            .public_input_mle(public_inputs)
+            .evaluate(&gate_point);
+
+    if zerocheck.last_claimed_sum != eq_evaluation(&tau, &gate_point) * gate_evaluation {
+        return None;
+    }
+
+    absorb_elements(&proof.gate_wire_evaluations, transcript);
+
+    let beta: F = transcript.random_challenge_as_field_element();
+    let gamma: F = transcript.random_challenge_as_field_element();
+
+    let products = &proof.permutation_proof.products;
+    if products.len() != 2 * num_wires
+        || products[..num_wires].iter().product::<F>()
+            != products[num_wires..].iter().product::<F>()
+    {
+        return None;
+    }
+
+    let leaf_claims = verify_batch(&proof.permutation_proof, circuit.num_vars, transcript)?;
+    let permutation_point = leaf_claims[0].point.clone();
+    let leaf_evaluations: Vec<F> = leaf_claims.iter().map(|claim| claim.value).collect();
+
+    // The leaves are w_j + beta * id_j + gamma, then w_j + beta * sigma_j + gamma
+    for index in 0..num_wires {
+        let wire_evaluation = proof.permutation_wire_evaluations[index];
+        let identity = circuit.identity_mle(index).evaluate(&permutation_point);
+        let permutation = circuit.permutation_mle(index).evaluate(&permutation_point);
+
+        if leaf_evaluations[index] != wire_evaluation + beta * identity + gamma
+            || leaf_evaluations[num_wires + index] != wire_evaluation + beta * permutation + gamma
+        {
+            return None;
+        }
+    }
+
+    absorb_elements(&proof.permutation_wire_evaluations, transcript);
+
+    Some(plonkish_claims(
+        &gate_point,
+        &proof.gate_wire_evaluations,
+        &permutation_point,
+        &proof.permutation_wire_evaluations,
+    ))
+}
+
+fn plonkish_claims<F: PrimeField>(
+    gate_point: &[F],
+    gate_wire_evaluations: &[F],
+    permutation_point: &[F],
+    permutation_wire_evaluations: &[F],
+) -> PlonkishClaims<F> {
+    let claims = |point: &[F], evaluations: &[F]| {
+        evaluations
+            .iter()
+            .map(|value| Claim {
+                point: point.to_vec(),
+                value: *value,
+            })
+            .collect()
+    };
+
+    PlonkishClaims {
+        gate: claims(gate_point, gate_wire_evaluations),
+        permutation: claims(permutation_point, permutation_wire_evaluations),
+    }
+}
+
+fn absorb_instance<F: PrimeField>(
+    circuit: &PlonkishCircuit<F>,
+    public_inputs: &[F],
+    transcript: &mut Transcript,
+) {
+    transcript.absorb(&(circuit.num_vars as u64).to_be_bytes());
+    transcript.absorb(&(circuit.gate.num_wires as u64).to_be_bytes());
+    absorb_elements(public_inputs, transcript);
+}
@@ -57,8 +57,8 @@ impl<F: PrimeField> ProductPolynomial<F> {
     // basically performing element-wise multiplication on the multilinear polynomials that makes up the ProductPolynomial
     pub fn multiply_polynomials_element_wise(&self) -> MultilinearPolynomialEV<F> {
         assert!(
-            self.polynomials.len() > 1,
-            "more than one polynomial required for mul operation"
+            !self.polynomials.is_empty(),
+            "at least one polynomial required for mul operation"
         );
 
         let mut resultant_values = self.polynomials[0].evaluated_values.to_vec();
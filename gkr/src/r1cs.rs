@@ -0,0 +1,300 @@
+use crate::circuits::circuit::num_vars_for_size;
+use crate::evaluation::MultilinearPolynomialEV;
+use ark_ff::PrimeField;
+
+// Rank-1 constraint systems: (A * z) o (B * z) = C * z, where o is the entry-wise product
+// and z = (1, public inputs, witness) is the full assignment, like the wires of a Circom circuit.
+// For the sum-check based proofs, z is laid out as two halves of the same power of two size:
+// (1, public inputs) padded with zeros, then the witness padded with zeros.
+// The verifier evaluates the multilinear extension of the first half itself and is left with a claim on the witness.
+
+// The non-zero entries of a matrix, as (row, column, value)
+#[derive(Clone, Debug, PartialEq)]
+pub struct SparseMatrix<F: PrimeField> {
+    pub num_rows: usize,
+    pub num_columns: usize,
+    pub entries: Vec<(usize, usize, F)>,
+}
+
+impl<F: PrimeField> SparseMatrix<F> {
+    pub fn new(num_rows: usize, num_columns: usize, entries: Vec<(usize, usize, F)>) -> Self {
+        assert!(
+            entries
+                .iter()
+                .all(|(row, column, _)| *row < num_rows && *column < num_columns),
+            "matrix entry out of bounds"
+        );
+
+        Self {
+            num_rows,
+            num_columns,
+            entries,
+        }
+    }
+
+    pub fn multiply_vector(&self, vector: &[F]) -> Vec<F> {
+        assert_eq!(
+            vector.len(),
+            self.num_columns,
+            "the vector has the wrong size"
+        );
+
+        let mut result = vec![F::zero(); self.num_rows];
+        for (row, column, value) in self.entries.iter() {
+            result[*row] += *value * vector[*column];
+        }
+
+        result
+    }
+
+    // The multilinear extension of the matrix indexed by (row bits, column bits), every dimension padded to a power of two
+    pub fn mle(&self) -> SparseMultilinearPolynomial<F> {
+        let column_vars = num_vars_for_size(self.num_columns);
+
+        SparseMultilinearPolynomial::new(
+            num_vars_for_size(self.num_rows) + column_vars,
+            self.entries
+                .iter()
+                .map(|(row, column, value)| ((row << column_vars) | column, *value))
+                .collect(),
+        )
+    }
+}
+
+// A multilinear extension stored by its non-zero evaluations over the boolean hypercube, as (index, value),
+// where the index follows the ordering of MultilinearPolynomialEV: variable 0 is the most significant bit
+#[derive(Clone, Debug, PartialEq)]
+pub struct SparseMultilinearPolynomial<F: PrimeField> {
+    pub num_vars: usize,
+    pub entries: Vec<(usize, F)>,
+}
+
+impl<F: PrimeField> SparseMultilinearPolynomial<F> {
+    pub fn new(num_vars: usize, entries: Vec<(usize, F)>) -> Self {
+        assert!(
+            entries.iter().all(|(index, _)| *index < 1 << num_vars),
+            "entry out of the boolean hypercube"
+        );
+
+        Self { num_vars, entries }
+    }
+
+    // sum over the entries of value * eq(point, index): linear in the number of entries
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(
+            point.len(),
+            self.num_vars,
+            "the point has the wrong number of variables"
+        );
+
+        self.entries
+            .iter()
+            .map(|(index, value)| {
+                point
+                    .iter()
+                    .enumerate()
+                    .fold(*value, |result, (variable, coordinate)| {
+                        if (index >> (self.num_vars - 1 - variable)) & 1 == 1 {
+                            result * coordinate
+                        } else {
+                            result * (F::one() - coordinate)
+                        }
+                    })
+            })
+            .sum()
+    }
+
+    // Fixes the first variables to point, leaving the dense polynomial in the remaining variables,
+    // eg: M(rx, y) for the sum-check over y
+    pub fn fix_first_variables(&self, point: &[F]) -> MultilinearPolynomialEV<F> {
+        assert!(point.len() <= self.num_vars, "too many variables to fix");
+
+        let remaining_vars = self.num_vars - point.len();
+        let weights = MultilinearPolynomialEV::eq_polynomial(point).evaluated_values;
+        let mut values = vec![F::zero(); 1 << remaining_vars];
+
+        for (index, value) in self.entries.iter() {
+            values[index & ((1 << remaining_vars) - 1)] += weights[index >> remaining_vars] * value;
+        }
+
+        MultilinearPolynomialEV::new(&values)
+    }
+
+    pub fn to_dense(&self) -> MultilinearPolynomialEV<F> {
+        self.fix_first_variables(&[])
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct R1CS<F: PrimeField> {
+    pub a: SparseMatrix<F>,
+    pub b: SparseMatrix<F>,
+    pub c: SparseMatrix<F>,
+    pub num_public_inputs: usize,
+}
+
+impl<F: PrimeField> R1CS<F> {
+    pub fn new(
+        a: SparseMatrix<F>,
+        b: SparseMatrix<F>,
+        c: SparseMatrix<F>,
+        num_public_inputs: usize,
+    ) -> Self {
+        assert!(
+            a.num_rows == b.num_rows
+                && a.num_rows == c.num_rows
+                && a.num_columns == b.num_columns
+                && a.num_columns == c.num_columns,
+            "the matrices must have the same dimensions"
+        );
+        assert!(
+            num_public_inputs < a.num_columns,
+            "the assignment holds 1 and the public inputs"
+        );
+
+        Self {
+            a,
+            b,
+            c,
+            num_public_inputs,
+        }
+    }
+
+    // The multilinear extensions of A, B and C
+    pub fn matrix_mles(&self) -> [SparseMultilinearPolynomial<F>; 3] {
+        [&self.a, &self.b, &self.c].map(|matrix| matrix.mle())
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.a.num_rows
+    }
+
+    // The length of the full assignment z
+    pub fn num_variables(&self) -> usize {
+        self.a.num_columns
+    }
+
+    pub fn num_witness_variables(&self) -> usize {
+        self.num_variables() - self.num_public_inputs - 1
+    }
+
+    // z = (1, public inputs, witness)
+    pub fn assignment(&self, public_inputs: &[F], witness: &[F]) -> Vec<F> {
+        assert_eq!(
+            public_inputs.len(),
+            self.num_public_inputs,
+            "wrong number of public inputs"
+        );
+        assert_eq!(
+            witness.len(),
+            self.num_witness_variables(),
+            "wrong number of witness variables"
+        );
+
+        let mut assignment = vec![F::one()];
+        assignment.extend_from_slice(public_inputs);
+        assignment.extend_from_slice(witness);
+
+        assignment
+    }
+
+    pub fn is_satisfied(&self, public_inputs: &[F], witness: &[F]) -> bool {
+        if public_inputs.len() != self.num_public_inputs
+            || witness.len() != self.num_witness_variables()
+        {
+            return false;
+        }
+
+        let assignment = self.assignment(public_inputs, witness);
+        let az = self.a.multiply_vector(&assignment);
+        let bz = self.b.multiply_vector(&assignment);
+        let cz = self.c.multiply_vector(&assignment);
+
+        az.iter()
+            .zip(bz.iter())
+            .zip(cz.iter())
+            .all(|((a, b), c)| *a * b == *c)
+    }
+
+    // The number of variables indexing the constraints
+    pub fn constraint_num_vars(&self) -> usize {
+        num_vars_for_size(self.num_constraints())
+    }
+
+    // The number of variables of each half of the padded assignment
+    pub fn half_assignment_num_vars(&self) -> usize {
+        num_vars_for_size((self.num_public_inputs + 1).max(self.num_witness_variables()))
+    }
+
+    // The number of variables of the padded assignment: the first one picks the half
+    pub fn assignment_num_vars(&self) -> usize {
+        1 + self.half_assignment_num_vars()
+    }
+
+    // The position of the column of z in the padded assignment
+    pub fn padded_column(&self, column: usize) -> usize {
+        let public_length = self.num_public_inputs + 1;
+
+        if column < public_length {
+            column
+        } else {
+            (1 << self.half_assignment_num_vars()) + column - public_length
+        }
+    }
+
+    // The multilinear extension of the padded assignment
+    pub fn assignment_mle(&self, public_inputs: &[F], witness: &[F]) -> MultilinearPolynomialEV<F> {
+        let half_size = 1 << self.half_assignment_num_vars();
+
+        let mut values = vec![F::zero(); 2 * half_size];
+        values[0] = F::one();
+        values[1..=self.num_public_inputs].copy_from_slice(public_inputs);
+        values[half_size..half_size + witness.len()].copy_from_slice(witness);
+
+        MultilinearPolynomialEV::new(&values)
+    }
+
+    // The multilinear extension of (1, public inputs), the first half of the padded assignment
+    pub fn public_mle(&self, public_inputs: &[F]) -> MultilinearPolynomialEV<F> {
+        let mut values = vec![F::zero(); 1 << self.half_assignment_num_vars()];
+        values[0] = F::one();
+        values[1..=self.num_public_inputs].copy_from_slice(public_inputs);
+
+        MultilinearPolynomialEV::new(&values)
+    }
+
+    // The multilinear extension of the witness, the second half of the padded assignment
+    pub fn witness_mle(&self, witness: &[F]) -> MultilinearPolynomialEV<F> {
+        let mut values = vec![F::zero(); 1 << self.half_assignment_num_vars()];
+        values[..witness.len()].copy_from_slice(witness);
+
+        MultilinearPolynomialEV::new(&values)
+    }
+
+    // M(rx, y) for every y of the padded assignment, where M is the combination of the matrices with the given weights:
+    // sum over the entries (row, column, value) of weight * value * eq(rx, row), placed at the padded column
+    pub fn combined_row(&self, row_point: &[F], weights: [F; 3]) -> Vec<F> {
+        let row_weights = MultilinearPolynomialEV::eq_polynomial(row_point).evaluated_values;
+        let mut values = vec![F::zero(); 1 << self.assignment_num_vars()];
+
+        for (matrix, weight) in [&self.a, &self.b, &self.c].into_iter().zip(weights) {
+            for (row, column, value) in matrix.entries.iter() {
+                values[self.padded_column(*column)] += weight * value * row_weights[*row];
+            }
+        }
+
+        values
+    }
+
+    // The evaluation at (rx, ry) of the multilinear extension of the weighted combination of the matrices,
+    // straight from the sparse entries: linear in the number of entries
+    pub fn combined_evaluation(&self, row_point: &[F], column_point: &[F], weights: [F; 3]) -> F {
+        let column_weights = MultilinearPolynomialEV::eq_polynomial(column_point).evaluated_values;
+
+        self.combined_row(row_point, weights)
+            .iter()
+            .zip(column_weights.iter())
+            .map(|(value, weight)| *value * weight)
+            .sum()
+    }
+}
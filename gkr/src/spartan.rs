@@ -0,0 +1,188 @@
+use crate::evaluation::MultilinearPolynomialEV;
+use crate::gkr_p::Claim;
+use crate::gkr_sumcheck_dependencies::poly_sum::SumPolynomial;
+use crate::gkr_sumcheck_dependencies::transcript::Transcript;
+use crate::product_poly::ProductPolynomial;
+use crate::r1cs::R1CS;
+use crate::sum_check::{
+    absorb_elements, field_element_to_bytes, prove as sumcheck_prove, verify as sumcheck_verify,
+    SumcheckProverProof,
+};
+use crate::utility::utils::eq_evaluation;
+use ark_ff::PrimeField;
+
+// Spartan-style proof of R1CS satisfiability.
+// Outer sum-check: sum over x of eq(tau, x) * (Az(x) * Bz(x) - Cz(x)) = 0 for a random tau,
+// which holds (with high probability) only if every constraint is satisfied. It leaves the claims Az(rx), Bz(rx), Cz(rx).
+// Inner sum-check: the three claims are combined with random weights into
+// sum over y of (wa * A(rx, y) + wb * B(rx, y) + wc * C(rx, y)) * z(y), which leaves M(rx, ry) * z(ry).
+// The verifier computes M(rx, ry) from the sparse matrices, and z(ry) from the public inputs and
+// the witness evaluation sent by the prover: the claim on the witness is returned, to be checked by the caller
+// (eg: against a commitment to the witness).
+
+#[derive(Clone, Debug)]
+pub struct SpartanProof<F: PrimeField> {
+    pub outer_sumcheck_proof: SumcheckProverProof<F>,
+    // Az(rx), Bz(rx), Cz(rx)
+    pub matrix_evaluations: [F; 3],
+    pub inner_sumcheck_proof: SumcheckProverProof<F>,
+    pub witness_evaluation: F,
+}
+
+pub fn prove<F: PrimeField>(
+    r1cs: &R1CS<F>,
+    public_inputs: &[F],
+    witness: &[F],
+    transcript: &mut Transcript,
+) -> (SpartanProof<F>, Claim<F>) {
+    let assignment = r1cs.assignment(public_inputs, witness);
+    let constraint_size = 1 << r1cs.constraint_num_vars();
+
+    absorb_instance(r1cs, public_inputs, transcript);
+
+    let tau: Vec<F> = (0..r1cs.constraint_num_vars())
+        .map(|_| transcript.random_challenge_as_field_element())
+        .collect();
+
+    let [az, bz, cz] = [&r1cs.a, &r1cs.b, &r1cs.c].map(|matrix| {
+        let mut values = matrix.multiply_vector(&assignment);
+        values.resize(constraint_size, F::zero());
+        MultilinearPolynomialEV::new(&values)
+    });
+    let eq_tau = MultilinearPolynomialEV::eq_polynomial(&tau);
+
+    let outer_polynomial = SumPolynomial::new(vec![
+        ProductPolynomial::new(vec![eq_tau.clone(), az.clone(), bz.clone()]),
+        ProductPolynomial::new(vec![eq_tau, cz.scalar_mul(-F::one())]),
+    ]);
+    let outer_sumcheck_proof = sumcheck_prove(outer_polynomial, F::zero(), transcript);
+
+    let rx = outer_sumcheck_proof.random_challenges.clone();
+    let matrix_evaluations = [&az, &bz, &cz].map(|polynomial| polynomial.evaluate(&rx));
+    absorb_elements(&matrix_evaluations, transcript);
+
+    let weights = inner_weights(transcript);
+    let inner_claim = combine(&matrix_evaluations, &weights);
+
+    let combined_row = MultilinearPolynomialEV::new(&r1cs.combined_row(&rx, weights));
+    let assignment_mle = r1cs.assignment_mle(public_inputs, witness);
+
+    let inner_polynomial = SumPolynomial::new(vec![ProductPolynomial::new(vec![
+        combined_row,
+        assignment_mle,
+    ])]);
+    let inner_sumcheck_proof = sumcheck_prove(inner_polynomial, inner_claim, transcript);
+
+    // ry = (the half, the point in the half)
+    let witness_point = inner_sumcheck_proof.random_challenges[1..].to_vec();
+    let witness_evaluation = r1cs.witness_mle(witness).evaluate(&witness_point);
+    transcript.absorb(&field_element_to_bytes(witness_evaluation));
+
+    (
+        SpartanProof {
+            outer_sumcheck_proof,
+            matrix_evaluations,
+            inner_sumcheck_proof,
+            witness_evaluation,
+        },
+        Claim {
+            point: witness_point,
+            value: witness_evaluation,
+        },
+    )
+}
+
+// Returns the claim on the multilinear extension of the witness when the proof is valid, None otherwise
+pub fn verify<F: PrimeField>(
+    r1cs: &R1CS<F>,
+    public_inputs: &[F],
+    proof: &SpartanProof<F>,
+    transcript: &mut Transcript,
+) -> Option<Claim<F>> {
+    if public_inputs.len() != r1cs.num_public_inputs
+        || proof.outer_sumcheck_proof.claimed_sum != F::zero()
+        || proof
+            .outer_sumcheck_proof
+            .round_univariate_polynomials
+            .len()
+            != r1cs.constraint_num_vars()
+        || proof
+            .inner_sumcheck_proof
+            .round_univariate_polynomials
+            .len()
+            != r1cs.assignment_num_vars()
+    {
+        return None;
+    }
+
+    absorb_instance(r1cs, public_inputs, transcript);
+
+    let tau: Vec<F> = (0..r1cs.constraint_num_vars())
+        .map(|_| transcript.random_challenge_as_field_element())
+        .collect();
+
+    let outer_verification = sumcheck_verify(&proof.outer_sumcheck_proof, transcript);
+    let [az, bz, cz] = proof.matrix_evaluations;
+
+    if !outer_verification.is_proof_valid
+        || outer_verification.last_claimed_sum
+            != eq_evaluation(&tau, &outer_verification.random_challenges) * (az * bz - cz)
+    {
+        return None;
+    }
+
+    let rx = outer_verification.random_challenges;
+    absorb_elements(&proof.matrix_evaluations, transcript);
+
+    let weights = inner_weights(transcript);
+    if proof.inner_sumcheck_proof.claimed_sum != combine(&proof.matrix_evaluations, &weights) {
+        return None;
+    }
+
+    let inner_verification = sumcheck_verify(&proof.inner_sumcheck_proof, transcript);
+    if !inner_verification.is_proof_valid {
+        return None;
+    }
+
+    let ry = inner_verification.random_challenges;
+    let (half, witness_point) = (ry[0], &ry[1..]);
+
+    // z(ry) = (1 - ry_0) * (1, public inputs)(ry_1..) + ry_0 * witness(ry_1..)
+    let assignment_evaluation = (F::one() - half)
+        * r1cs.public_mle(public_inputs).evaluate(witness_point)
+        + half * proof.witness_evaluation;
+    let matrix_evaluation = r1cs.combined_evaluation(&rx, &ry, weights);
+
+    if inner_verification.last_claimed_sum != matrix_evaluation * assignment_evaluation {
+        return None;
+    }
+
+    transcript.absorb(&field_element_to_bytes(proof.witness_evaluation));
+
+    Some(Claim {
+        point: witness_point.to_vec(),
+        value: proof.witness_evaluation,
+    })
+}
+
+fn absorb_instance<F: PrimeField>(
+    r1cs: &R1CS<F>,
+    public_inputs: &[F],
+    transcript: &mut Transcript,
+) {
+    transcript.absorb(&(r1cs.num_constraints() as u64).to_be_bytes());
+    transcript.absorb(&(r1cs.num_variables() as u64).to_be_bytes());
+    absorb_elements(public_inputs, transcript);
+}
+
+fn inner_weights<F: PrimeField>(transcript: &mut Transcript) -> [F; 3] {
+    [(); 3].map(|_| transcript.random_challenge_as_field_element())
+}
+
+fn combine<F: PrimeField>(evaluations: &[F; 3], weights: &[F; 3]) -> F {
+    evaluations
+        .iter()
+        .zip(weights.iter())
+        .map(|(evaluation, weight)| *evaluation * weight)
+        .sum()
+}
@@ -150,3 +150,10 @@ pub fn univariate_to_bytes<F: PrimeField>(univariate_poly: &[F]) -> Vec<u8> {
 pub fn field_element_to_bytes<F: PrimeField>(field_element: F) -> Vec<u8> {
     field_element.into_bigint().to_bytes_be()
 }
+
+// Absorbs the elements one by one, each as its own message
+pub fn absorb_elements<F: PrimeField>(elements: &[F], transcript: &mut Transcript) {
+    for element in elements.iter() {
+        transcript.absorb(&field_element_to_bytes(*element));
+    }
+}
@@ -0,0 +1,94 @@
+use crate::binary_field::BinaryField128b;
+use crate::extension_sum_check::extension_element_to_bytes;
+use crate::gkr_sumcheck_dependencies::transcript::Transcript;
+use ark_ff::Field;
+use std::iter::{Product, Sum};
+use std::ops::{Add, Mul, Sub};
+
+// The rounds shared by the sum-checks over small fields: extension_sum_check (any extension of ark_ff)
+// and binary_sum_check (the 128 bits binary tower field, which is not an ark_ff Field).
+// The round polynomials are sent as their evaluations at round_point(0), round_point(1), ..., round_point(degree):
+// the integers 0, 1, ... in odd characteristic, the elements whose bits are 0, 1, ... in the binary tower,
+// where the integers are not distinct field elements.
+pub(crate) trait ChallengeField:
+    Copy + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Sum + Product
+{
+    fn round_point(index: usize) -> Self;
+
+    fn invert(&self) -> Option<Self>;
+
+    fn to_transcript_bytes(&self) -> Vec<u8>;
+
+    fn sample_challenge(transcript: &mut Transcript) -> Self;
+}
+
+impl<E: Field> ChallengeField for E {
+    fn round_point(index: usize) -> Self {
+        E::from(index as u64)
+    }
+
+    fn invert(&self) -> Option<Self> {
+        self.inverse()
+    }
+
+    fn to_transcript_bytes(&self) -> Vec<u8> {
+        extension_element_to_bytes(*self)
+    }
+
+    fn sample_challenge(transcript: &mut Transcript) -> Self {
+        transcript.random_challenge_as_extension_element()
+    }
+}
+
+impl ChallengeField for BinaryField128b {
+    fn round_point(index: usize) -> Self {
+        BinaryField128b::new(index as u128)
+    }
+
+    fn invert(&self) -> Option<Self> {
+        self.inverse()
+    }
+
+    fn to_transcript_bytes(&self) -> Vec<u8> {
+        self.to_bytes()
+    }
+
+    fn sample_challenge(transcript: &mut Transcript) -> Self {
+        transcript.random_challenge_as_binary_element()
+    }
+}
+
+// Absorbs the evaluations of a round polynomial and draws the challenge of the round
+pub(crate) fn absorb_round<E: ChallengeField>(evaluations: &[E], transcript: &mut Transcript) -> E {
+    for evaluation in evaluations.iter() {
+        transcript.absorb(&evaluation.to_transcript_bytes());
+    }
+
+    E::sample_challenge(transcript)
+}
+
+// Binds the first variable (the most significant bit) of a table to the challenge
+pub(crate) fn fold<E: ChallengeField>(values: &[E], challenge: E) -> Vec<E> {
+    let (low, high) = values.split_at(values.len() / 2);
+
+    low.iter()
+        .zip(high.iter())
+        .map(|(low, high)| *low + challenge * (*high - *low))
+        .collect()
+}
+
+// The evaluation at x of the polynomial of degree evaluations.len() - 1
+// with the given evaluations at round_point(0), round_point(1), ...
+pub(crate) fn interpolate_at<E: ChallengeField>(evaluations: &[E], x: E) -> E {
+    let points: Vec<E> = (0..evaluations.len()).map(E::round_point).collect();
+
+    (0..evaluations.len())
+        .map(|i| {
+            let others = || (0..evaluations.len()).filter(move |j| *j != i);
+            let numerator: E = others().map(|j| x - points[j]).product();
+            let denominator: E = others().map(|j| points[i] - points[j]).product();
+
+            evaluations[i] * numerator * denominator.invert().unwrap()
+        })
+        .sum()
+}
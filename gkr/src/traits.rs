@@ -1,4 +1,2 @@
-pub trait HashTrait {
-    fn append(&mut self, data: &[u8]);
-    fn generate_hash(&self) -> Vec<u8>;
-}
+// Shared with fiat_shamir, so that the same hashers work with both crates' transcripts and Merkle trees
+pub use fiat_shamir::traits::HashTrait;
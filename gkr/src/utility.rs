@@ -21,4 +21,13 @@ pub mod utils {
     pub fn field_element_to_bytes<F: PrimeField>(field_element: F) -> Vec<u8> {
         field_element.into_bigint().to_bytes_be()
     }
+
+    // eq(x, y) = product of (x_i * y_i + (1 - x_i) * (1 - y_i))
+    // It is 1 when x and y are the same point of the boolean hypercube, and 0 for any other point
+    pub fn eq_evaluation<F: PrimeField>(x: &[F], y: &[F]) -> F {
+        x.iter()
+            .zip(y.iter())
+            .map(|(x_i, y_i)| (*x_i * y_i) + ((F::one() - x_i) * (F::one() - y_i)))
+            .product()
+    }
 }
@@ -1,21 +1,11 @@
-use crate::circuits::circuit::Circuit;
+use crate::circuits::circuit::{Circuit, LayerPredicates};
 use ark_ff::PrimeField;
 
 use crate::evaluation::MultilinearPolynomialEV;
+use crate::gkr_sumcheck_dependencies::densed_uni_poly::DensedUnivariatePolynomial;
 use crate::gkr_sumcheck_dependencies::poly_sum::SumPolynomial;
 use crate::product_poly::ProductPolynomial;
-
-pub struct SumcheckChallenges<F: PrimeField> {
-    pub current: Vec<F>,
-    pub previous: Vec<F>,
-}
-
-pub struct Evaluations<F: PrimeField> {
-    pub wb: F,
-    pub wc: F,
-    pub alpha: F,
-    pub beta: F,
-}
+use std::collections::BTreeMap;
 
 pub fn compute_fbc_polynomial<F: PrimeField>(
     add_i_bc: MultilinearPolynomialEV<F>,
@@ -32,51 +22,21 @@ pub fn compute_fbc_polynomial<F: PrimeField>(
     SumPolynomial::new(vec![add_i_term, mul_i_term])
 }
 
-pub fn compute_new_add_i_mul_i<F: PrimeField>(
-    alpha: F,
-    beta: F,
+// Partially evaluates add_i_abc and mul_i_abc at every random challenge of "a", leaving add_i_bc and mul_i_bc
+pub fn remove_variable_a<F: PrimeField>(
     add_i_abc: MultilinearPolynomialEV<F>,
     mul_i_abc: MultilinearPolynomialEV<F>,
-    rb_values: &[F],
-    rc_values: &[F],
+    random_challenges_a: &[F],
 ) -> (MultilinearPolynomialEV<F>, MultilinearPolynomialEV<F>) {
-    // Partial evaluating add_i_abc and mul_i_abc at all the random values using loop
-    // The goal is to remove the "a" variable, so that we get add_i_bc and mul_i_bc
-    // The random challenges array (rb_values and rc_values) are based on the layer bits: =>
-    // => Eg: layer2 will have 2 bits value for variable a => rb_values and rc_values array length will be 2 each
-
-    // We first evaluated at with random values at 0 index, so that we don't have to use .clone()
-    // rb => random challenges for b, rc => random challenges for c
-    let mut add_rb_bc =
-        MultilinearPolynomialEV::partial_evaluate(&add_i_abc.evaluated_values, 0, rb_values[0]);
-    let mut add_rc_bc =
-        MultilinearPolynomialEV::partial_evaluate(&add_i_abc.evaluated_values, 0, rc_values[0]);
-
-    let mut mul_rb_bc =
-        MultilinearPolynomialEV::partial_evaluate(&mul_i_abc.evaluated_values, 0, rb_values[0]);
-    let mut mul_rc_bc =
-        MultilinearPolynomialEV::partial_evaluate(&mul_i_abc.evaluated_values, 0, rc_values[0]);
-
-    for rb in rb_values.iter().skip(1) {
-        add_rb_bc = MultilinearPolynomialEV::partial_evaluate(&add_rb_bc.evaluated_values, 0, *rb);
-        mul_rb_bc = MultilinearPolynomialEV::partial_evaluate(&mul_rb_bc.evaluated_values, 0, *rb);
-    }
+    let mut add_i_bc = add_i_abc;
+    let mut mul_i_bc = mul_i_abc;
 
-    for rc in rc_values.iter().skip(1) {
-        add_rc_bc = MultilinearPolynomialEV::partial_evaluate(&add_rc_bc.evaluated_values, 0, *rc);
-        mul_rc_bc = MultilinearPolynomialEV::partial_evaluate(&mul_rc_bc.evaluated_values, 0, *rc);
+    for ra in random_challenges_a.iter() {
+        add_i_bc = MultilinearPolynomialEV::partial_evaluate(&add_i_bc.evaluated_values, 0, *ra);
+        mul_i_bc = MultilinearPolynomialEV::partial_evaluate(&mul_i_bc.evaluated_values, 0, *ra);
     }
 
-    let new_add_i = MultilinearPolynomialEV::add_polynomials(
-        &add_rb_bc.scalar_mul(alpha),
-        &add_rc_bc.scalar_mul(beta),
-    );
-    let new_mul_i = MultilinearPolynomialEV::add_polynomials(
-        &mul_rb_bc.scalar_mul(alpha),
-        &mul_rc_bc.scalar_mul(beta),
-    );
-
-    (new_add_i, new_mul_i)
+    (add_i_bc, mul_i_bc)
 }
 
 pub fn evaluate_wb_wc<F: PrimeField>(
@@ -93,28 +53,61 @@ pub fn evaluate_wb_wc<F: PrimeField>(
     (wb_poly_evaluated, wc_poly_evaluated)
 }
 
-pub fn compute_verifier_initial_claim<F: PrimeField>(
+// Combines the wiring predicates for several claims on the output of a layer:
+// new_add_i(b, c) = sum of weight_k * add_i(point_k, b, c), the same for mul_i
+// With a single claim of weight 1 this just removes the variable "a",
+// with the claims at rb and rc weighted by alpha and beta this is the alpha-beta folding
+pub fn compute_combined_add_i_mul_i<F: PrimeField>(
+    add_i_abc: &MultilinearPolynomialEV<F>,
+    mul_i_abc: &MultilinearPolynomialEV<F>,
+    weighted_points: &[(F, Vec<F>)],
+) -> (MultilinearPolynomialEV<F>, MultilinearPolynomialEV<F>) {
+    (
+        compute_combined_predicate(add_i_abc, weighted_points),
+        compute_combined_predicate(mul_i_abc, weighted_points),
+    )
+}
+
+// The same combination for a single wiring predicate: new_predicate(b) = sum of weight_k * predicate(point_k, b)
+pub fn compute_combined_predicate<F: PrimeField>(
+    predicate_abc: &MultilinearPolynomialEV<F>,
+    weighted_points: &[(F, Vec<F>)],
+) -> MultilinearPolynomialEV<F> {
+    let mut combined_predicate: Option<MultilinearPolynomialEV<F>> = None;
+
+    for (weight, point) in weighted_points.iter() {
+        let mut predicate_bc = predicate_abc.clone();
+
+        for ra in point.iter() {
+            predicate_bc =
+                MultilinearPolynomialEV::partial_evaluate(&predicate_bc.evaluated_values, 0, *ra);
+        }
+
+        let predicate_bc = predicate_bc.scalar_mul(*weight);
+
+        combined_predicate = Some(match combined_predicate {
+            Some(sum) => MultilinearPolynomialEV::add_polynomials(&sum, &predicate_bc),
+            None => predicate_bc,
+        });
+    }
+
+    combined_predicate.expect("at least one claim is required")
+}
+
+// What the verifier expects the last round of the layer sumcheck to be:
+// new_add_i(rb, rc) * (wb + wc) + new_mul_i(rb, rc) * (wb * wc)
+pub fn compute_verifier_combined_claim<F: PrimeField>(
     circuit: &mut Circuit<F>,
     layer_index: usize,
-    initial_random_challenge: F,
+    weighted_points: &[(F, Vec<F>)],
     sumcheck_challenges: &[F],
     wb_evaluation: F,
     wc_evaluation: F,
 ) -> F {
     let (add_i_abc, mul_i_abc) = circuit.add_i_and_mul_i_mle(layer_index);
 
-    let (add_i_bc, mul_i_bc) = (
-        MultilinearPolynomialEV::partial_evaluate(
-            &add_i_abc.evaluated_values,
-            0,
-            initial_random_challenge,
-        ),
-        MultilinearPolynomialEV::partial_evaluate(
-            &mul_i_abc.evaluated_values,
-            0,
-            initial_random_challenge,
-        ),
-    );
+    let (add_i_bc, mul_i_bc) =
+        compute_combined_add_i_mul_i(&add_i_abc, &mul_i_abc, weighted_points);
 
     let add_i_r = add_i_bc.evaluate(sumcheck_challenges);
     let mul_i_r = mul_i_bc.evaluate(sumcheck_challenges);
@@ -122,27 +115,209 @@ pub fn compute_verifier_initial_claim<F: PrimeField>(
     (add_i_r * (wb_evaluation + wc_evaluation)) + (mul_i_r * (wb_evaluation * wc_evaluation))
 }
 
-pub fn compute_verifier_folded_claim<F: PrimeField>(
-    circuit: &mut Circuit<F>,
-    layer_index: usize,
-    challenges: &SumcheckChallenges<F>,
-    evals: &Evaluations<F>,
+// The f(b,c) polynomial of a layer whose gates read from several layers (skip connections) or have more than two inputs:
+// the sum over the wiring predicates of new_add_i(b, c) * (W_left(b) + W_right(c)) + new_mul_i(b, c) * W_left(b) * W_right(c),
+// plus new_sum(b_1) * W(b_1) for the summation gates and new_product_k(b_1, ..., b_k) * W(b_1) * ... * W(b_k) for the multiplication gates,
+// plus new_custom(b_1, ..., b_k) * coefficient * W(b_1)^e_1 * ... * W(b_k)^e_k for every term of every custom gate.
+// The inputs of a gate are on disjoint blocks of variables, so W(b_1) * ... * W(b_k) is still a single multilinear polynomial.
+// source_polynomials holds the layers the gates read from, all padded to the same number of variables
+pub fn compute_layers_fbc_polynomial<F: PrimeField>(
+    predicates: &LayerPredicates<F>,
+    weighted_points: &[(F, Vec<F>)],
+    source_polynomials: &BTreeMap<usize, MultilinearPolynomialEV<F>>,
+    layer_below: usize,
+    arity: usize,
+) -> SumPolynomial<F> {
+    let mut product_polynomials = Vec::with_capacity(2 * predicates.wiring.len());
+
+    for predicate in predicates.wiring.iter() {
+        let (add_i_bc, mul_i_bc) =
+            compute_combined_add_i_mul_i(&predicate.add_i, &predicate.mul_i, weighted_points);
+
+        let w_bc = [
+            (0, &source_polynomials[&predicate.left_layer]),
+            (1, &source_polynomials[&predicate.right_layer]),
+        ];
+
+        let add_wbc = combine_input_blocks(&w_bc, arity, |x, y| x + y);
+        let mul_wbc = combine_input_blocks(&w_bc, arity, |x, y| x * y);
+
+        product_polynomials.push(ProductPolynomial::new(vec![add_i_bc, add_wbc]));
+        product_polynomials.push(ProductPolynomial::new(vec![mul_i_bc, mul_wbc]));
+    }
+
+    let w_below = &source_polynomials[&layer_below];
+
+    for predicate in predicates.multi_input.iter() {
+        let predicate_bc = compute_combined_predicate(&predicate.predicate, weighted_points);
+
+        let inputs: Vec<(usize, &MultilinearPolynomialEV<F>)> = (0..predicate.fan_in)
+            .map(|block| (block, w_below))
+            .collect();
+        let w_inputs = combine_input_blocks(&inputs, arity, |x, y| x * y);
+
+        product_polynomials.push(ProductPolynomial::new(vec![predicate_bc, w_inputs]));
+    }
+
+    // W(b_1)^e_1 * ... * W(b_k)^e_k is split into max(e) multilinear factors:
+    // the p-th factor is the product of W(b_j) over the inputs with e_j >= p.
+    // So x^5 makes a product of 6 polynomials (degree 6 sumcheck), and a * b + c two products of 2 polynomials
+    for predicate in predicates.custom.iter() {
+        let predicate_bc = compute_combined_predicate(&predicate.predicate, weighted_points);
+
+        for term in predicate.terms.iter() {
+            let mut factors = vec![predicate_bc.scalar_mul(F::from(term.coefficient))];
+            let max_exponent = term.exponents.iter().copied().max().unwrap_or(0);
+
+            for power in 1..=max_exponent {
+                let inputs: Vec<(usize, &MultilinearPolynomialEV<F>)> = term
+                    .exponents
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, exponent)| **exponent >= power)
+                    .map(|(block, _)| (block, w_below))
+                    .collect();
+
+                factors.push(combine_input_blocks(&inputs, arity, |x, y| x * y));
+            }
+
+            product_polynomials.push(ProductPolynomial::new(factors));
+        }
+    }
+
+    SumPolynomial::new(product_polynomials)
+}
+
+// What the verifier expects the last round of the layer sumcheck to be, when the gates read from several layers or have more than two inputs
+// block_evaluations[k] holds the evaluations of the padded source layers at the k-th block of the sumcheck challenges, by layer
+pub fn compute_verifier_layers_claim<F: PrimeField>(
+    predicates: &LayerPredicates<F>,
+    weighted_points: &[(F, Vec<F>)],
+    sumcheck_challenges: &[F],
+    block_evaluations: &[BTreeMap<usize, F>],
+    layer_below: usize,
 ) -> F {
-    let (prev_rb, prev_rc) = challenges.previous.split_at(challenges.previous.len() / 2);
+    let mut claim = F::zero();
 
-    let (add_i_abc, mul_i_abc) = circuit.add_i_and_mul_i_mle(layer_index);
+    for predicate in predicates.wiring.iter() {
+        let (add_i_bc, mul_i_bc) =
+            compute_combined_add_i_mul_i(&predicate.add_i, &predicate.mul_i, weighted_points);
+
+        let add_i_r = add_i_bc.evaluate(sumcheck_challenges);
+        let mul_i_r = mul_i_bc.evaluate(sumcheck_challenges);
+
+        let wb_evaluation = block_evaluations[0][&predicate.left_layer];
+        let wc_evaluation = block_evaluations[1][&predicate.right_layer];
+
+        claim += (add_i_r * (wb_evaluation + wc_evaluation))
+            + (mul_i_r * (wb_evaluation * wc_evaluation));
+    }
+
+    for predicate in predicates.multi_input.iter() {
+        let predicate_r = compute_combined_predicate(&predicate.predicate, weighted_points)
+            .evaluate(sumcheck_challenges);
+
+        let w_inputs: F = block_evaluations[..predicate.fan_in]
+            .iter()
+            .map(|evaluations| evaluations[&layer_below])
+            .product();
+
+        claim += predicate_r * w_inputs;
+    }
+
+    for predicate in predicates.custom.iter() {
+        let predicate_r = compute_combined_predicate(&predicate.predicate, weighted_points)
+            .evaluate(sumcheck_challenges);
+
+        let w_inputs: Vec<F> = block_evaluations
+            .iter()
+            .take(predicate.terms[0].exponents.len())
+            .map(|evaluations| evaluations[&layer_below])
+            .collect();
+
+        let gate_value: F = predicate
+            .terms
+            .iter()
+            .map(|term| term.evaluate(&w_inputs))
+            .sum();
 
-    let (new_add_i, new_mul_i) = compute_new_add_i_mul_i(
-        evals.alpha,
-        evals.beta,
-        add_i_abc,
-        mul_i_abc,
-        prev_rb,
-        prev_rc,
-    );
+        claim += predicate_r * gate_value;
+    }
+
+    claim
+}
+
+// Puts every polynomial on its block of input variables (eg: (0, W) puts W on b_1, (1, W) on b_2)
+// and combines them: the result over (b_1, ..., b_arity) is combine(W(b_i), W'(b_j), ...).
+// The blocks without a polynomial are ignored, the wiring predicates are 0 outside of b = 0 on those blocks.
+// With two blocks, sum is polynomial_tensor_add and product is polynomial_tensor_mul
+pub fn combine_input_blocks<F: PrimeField>(
+    polynomials: &[(usize, &MultilinearPolynomialEV<F>)],
+    arity: usize,
+    combine: impl Fn(F, F) -> F,
+) -> MultilinearPolynomialEV<F> {
+    let block_num_vars = polynomials[0].1.number_of_variables() as usize;
+    let block_mask = (1 << block_num_vars) - 1;
+
+    let values: Vec<F> = (0..1usize << (arity * block_num_vars))
+        .map(|index| {
+            polynomials
+                .iter()
+                .map(|(block, polynomial)| {
+                    let shift = (arity - 1 - block) * block_num_vars;
+                    polynomial.evaluated_values[(index >> shift) & block_mask]
+                })
+                .reduce(&combine)
+                .expect("at least one polynomial is required")
+        })
+        .collect();
+
+    MultilinearPolynomialEV::new(&values)
+}
+
+// A layer with fewer variables than the sumcheck point is padded with zeros at the end of its evaluations,
+// ie: the extra variables are the first ones and must all be 0. So W_padded(r) = padding_factor * W(last variables of r)
+// Returns the padding factor and the part of the point that the layer itself is evaluated at
+pub fn split_padded_point<F: PrimeField>(point: &[F], num_vars: usize) -> (F, &[F]) {
+    let (padding_variables, layer_point) = point.split_at(point.len() - num_vars);
+
+    let padding_factor = padding_variables
+        .iter()
+        .fold(F::one(), |factor, r| factor * (F::one() - r));
+
+    (padding_factor, layer_point)
+}
+
+// The curve of degree points.len() - 1 through the points: curve(0) = points[0], curve(1) = points[1], ...
+// For two points (rb and rc), this is the line through them. Returns the point of the curve at t
+pub fn curve_through_points<F: PrimeField>(points: &[Vec<F>], t: F) -> Vec<F> {
+    let x_values: Vec<F> = (0..points.len()).map(|i| F::from(i as u64)).collect();
+
+    (0..points[0].len())
+        .map(|variable| {
+            let y_values: Vec<F> = points.iter().map(|point| point[variable]).collect();
+            DensedUnivariatePolynomial::lagrange_interpolate(&x_values, &y_values).evaluate(t)
+        })
+        .collect()
+}
+
+// The restriction of a layer polynomial to the curve through the points: q(t) = W(curve(t))
+// Every coordinate of the curve has degree points.len() - 1 and W is multilinear,
+// so q has degree (points.len() - 1) * number_of_variables. It is returned as its evaluations at 0, 1, ..., degree
+pub fn restrict_to_curve<F: PrimeField>(
+    polynomial: &MultilinearPolynomialEV<F>,
+    points: &[Vec<F>],
+) -> Vec<F> {
+    let degree = (points.len() - 1) * polynomial.number_of_variables() as usize;
+
+    (0..=degree)
+        .map(|t| polynomial.evaluate(&curve_through_points(points, F::from(t as u64))))
+        .collect()
+}
 
-    let add_r = new_add_i.evaluate(&challenges.current);
-    let mul_r = new_mul_i.evaluate(&challenges.current);
+// Evaluates the restriction q, given as its evaluations at 0, 1, ..., degree, at t
+pub fn evaluate_restriction<F: PrimeField>(restriction: &[F], t: F) -> F {
+    let x_values: Vec<F> = (0..restriction.len()).map(|i| F::from(i as u64)).collect();
 
-    (add_r * (evals.wb + evals.wc)) + (mul_r * (evals.wb * evals.wc))
+    DensedUnivariatePolynomial::lagrange_interpolate(&x_values, restriction).evaluate(t)
 }
@@ -0,0 +1,145 @@
+use gkr::binary_field::{
+    BinaryField128b, BinaryField2b, BinaryField8b, BinaryMultilinearPolynomial, BinaryTower,
+};
+use gkr::binary_sum_check::{
+    evaluate_gate_layer, prove, prove_gate_layer, verify, verify_gate_layer, BooleanGate,
+};
+use gkr::gkr_sumcheck_dependencies::transcript::Transcript;
+
+fn sample_elements() -> Vec<BinaryField128b> {
+    [
+        1u128,
+        2,
+        0x1234_5678_9abc_def0,
+        0xdead_beef_0000_0001_ffff_0000_1357_9bdf,
+        u128::MAX,
+    ]
+    .into_iter()
+    .map(BinaryField128b::new)
+    .collect()
+}
+
+fn bits(values: &[u8]) -> Vec<bool> {
+    values.iter().map(|value| *value == 1).collect()
+}
+
+#[test]
+fn test_small_tower_fields() {
+    // GF(4) = GF(2)[X] / (X^2 + X + 1)
+    let x = BinaryField2b::new(2);
+    assert_eq!(x * x, BinaryField2b::new(3));
+    assert_eq!(x * x * x, BinaryField2b::ONE);
+
+    // every non zero element of GF(2^8) has an inverse, and the subfields embed into the 128 bits field
+    for a in 1..256u128 {
+        let a = BinaryField8b::new(a);
+        assert_eq!(a * a.inverse().unwrap(), BinaryField8b::ONE);
+
+        let b = BinaryField8b::new(a.bits() * 7 + 3);
+        assert_eq!((a * b).embed::<7>(), a.embed::<7>() * b.embed::<7>());
+    }
+    assert_eq!(BinaryField8b::ZERO.inverse(), None);
+}
+
+#[test]
+fn test_128_bits_field() {
+    let elements = sample_elements();
+
+    for a in elements.iter() {
+        assert_eq!(*a + *a, BinaryField128b::ZERO);
+        assert_eq!(*a * a.inverse().unwrap(), BinaryField128b::ONE);
+
+        // a^(2^128) = a
+        let frobenius = (0..128).fold(*a, |power, _| power.square());
+        assert_eq!(frobenius, *a);
+
+        for b in elements.iter() {
+            assert_eq!(*a * *b, *b * *a);
+            for c in elements.iter() {
+                assert_eq!((*a * *b) * *c, *a * (*b * *c));
+                assert_eq!(*a * (*b + *c), *a * *b + *a * *c);
+            }
+        }
+    }
+    assert_eq!(BinaryField128b::new(5).to_bytes().len(), 16);
+    assert_eq!(BinaryTower::<0>::new(3), BinaryTower::<0>::ONE);
+}
+
+#[test]
+fn test_binary_multilinear_polynomial() {
+    let polynomial =
+        BinaryMultilinearPolynomial::<3>::new(&[3, 1, 4, 1, 5, 9, 2, 6].map(BinaryField8b::new));
+
+    // variable 0 is the most significant bit
+    let boolean_point = [1, 1, 0].map(BinaryField128b::new);
+    assert_eq!(polynomial.evaluate(&boolean_point), BinaryField128b::new(2));
+
+    let point: Vec<BinaryField128b> = sample_elements().into_iter().skip(2).collect();
+    let eq_point = BinaryMultilinearPolynomial::eq_polynomial(&point);
+    let sum: BinaryField128b = eq_point
+        .evaluated_values
+        .iter()
+        .zip(polynomial.evaluated_values.iter())
+        .map(|(eq, value)| *eq * value.embed::<7>())
+        .sum();
+    assert_eq!(polynomial.evaluate(&point), sum);
+}
+
+#[test]
+fn test_binary_sumcheck() {
+    let a = BinaryMultilinearPolynomial::<0>::from_bits(&bits(&[1, 0, 1, 1, 0, 1, 1, 1]));
+    let b = BinaryMultilinearPolynomial::<0>::from_bits(&bits(&[1, 1, 0, 1, 0, 1, 1, 0]));
+    let c = BinaryMultilinearPolynomial::<0>::from_bits(&bits(&[0, 1, 1, 0, 0, 0, 1, 0]));
+
+    // sum of a * b + c over the hypercube, with the additions in GF(2)
+    let products = [vec![a.clone(), b.clone()], vec![c.clone()]];
+    let claimed_sum = BinaryField128b::ONE;
+
+    let proof = prove(&products, claimed_sum, &mut Transcript::new());
+    let verification = verify(&proof, 2, &mut Transcript::new());
+    assert!(verification.is_proof_valid);
+
+    let point = verification.random_challenges;
+    assert_eq!(
+        verification.last_claimed_sum,
+        a.evaluate(&point) * b.evaluate(&point) + c.evaluate(&point)
+    );
+
+    let wrong_proof = prove(&products, BinaryField128b::ZERO, &mut Transcript::new());
+    assert!(!verify(&wrong_proof, 2, &mut Transcript::new()).is_proof_valid);
+
+    // a round of another degree is rejected, even when it is consistent with the claimed sum
+    assert!(!verify(&proof, 3, &mut Transcript::new()).is_proof_valid);
+    let mut high_degree_proof = proof.clone();
+    high_degree_proof.round_evaluations[0].push(BinaryField128b::ONE);
+    assert!(!verify(&high_degree_proof, 2, &mut Transcript::new()).is_proof_valid);
+}
+
+#[test]
+fn test_boolean_gate_layer() {
+    let gates = [
+        BooleanGate::Xor,
+        BooleanGate::And,
+        BooleanGate::And,
+        BooleanGate::Xor,
+    ];
+    let left = bits(&[1, 1, 0, 0]);
+    let right = bits(&[1, 1, 1, 0]);
+    let output = evaluate_gate_layer(&gates, &left, &right);
+    assert_eq!(output, bits(&[0, 1, 0, 0]));
+
+    let [left, right, output] =
+        [left, right, output].map(|wire| BinaryMultilinearPolynomial::<0>::from_bits(&wire));
+
+    let (proof, claims) = prove_gate_layer(&gates, &left, &right, &output, &mut Transcript::new());
+    let verified_claims = verify_gate_layer(&gates, &proof, &mut Transcript::new()).unwrap();
+    assert_eq!(claims, verified_claims);
+    for (claim, wire) in claims.iter().zip([&left, &right, &output]) {
+        assert_eq!(wire.evaluate(&claim.point), claim.value);
+    }
+
+    // a layer with a wrong output fails
+    let wrong_output = BinaryMultilinearPolynomial::<0>::from_bits(&bits(&[0, 1, 1, 0]));
+    let (proof, _) = prove_gate_layer(&gates, &left, &right, &wrong_output, &mut Transcript::new());
+    assert!(verify_gate_layer(&gates, &proof, &mut Transcript::new()).is_none());
+}
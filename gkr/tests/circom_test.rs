@@ -0,0 +1,136 @@
+use ark_bn254::Fr;
+use gkr::circom::{read_r1cs, read_r1cs_file, read_witness, read_witness_file, CircomError};
+use gkr::gkr_sumcheck_dependencies::transcript::Transcript;
+use gkr::spartan::{prove, verify};
+
+// multiplier: c <== a * b, wires [1, c, a, b], written as (-a) * b - (-c) = 0
+// cubic: out <== x^3 + x + 5, wires [1, out, x, x2], with (-x) * x = -x2 and (-x2) * x = 5 - out + x
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn test_read_r1cs() {
+    let circom_r1cs = read_r1cs_file(fixture("multiplier.r1cs")).unwrap();
+
+    assert_eq!(circom_r1cs.header.num_wires, 4);
+    assert_eq!(circom_r1cs.header.num_public_outputs, 1);
+    assert_eq!(circom_r1cs.header.num_private_inputs, 2);
+    assert_eq!(circom_r1cs.header.num_constraints, 1);
+    assert_eq!(circom_r1cs.wire_labels, vec![0, 1, 2, 3]);
+    assert_eq!(circom_r1cs.r1cs.num_public_inputs, 1);
+    assert_eq!(circom_r1cs.r1cs.a.entries, vec![(0, 2, -Fr::from(1))]);
+    assert_eq!(circom_r1cs.r1cs.b.entries, vec![(0, 3, Fr::from(1))]);
+    assert_eq!(circom_r1cs.r1cs.c.entries, vec![(0, 1, -Fr::from(1))]);
+}
+
+#[test]
+fn test_witness_satisfaction() {
+    for name in ["multiplier", "cubic"] {
+        let circom_r1cs = read_r1cs_file(fixture(&format!("{name}.r1cs"))).unwrap();
+        let mut wire_values = read_witness_file(fixture(&format!("{name}.wtns"))).unwrap();
+
+        assert_eq!(wire_values.len(), 4);
+        assert!(circom_r1cs.is_satisfied(&wire_values));
+
+        wire_values[1] += Fr::from(1);
+        assert!(!circom_r1cs.is_satisfied(&wire_values));
+        assert!(!circom_r1cs.is_satisfied(&wire_values[..3]));
+    }
+}
+
+#[test]
+fn test_sparse_matrix_mles() {
+    let circom_r1cs = read_r1cs_file(fixture("cubic.r1cs")).unwrap();
+    let point = [Fr::from(3), Fr::from(7), Fr::from(11)];
+
+    for (mle, matrix) in circom_r1cs.matrix_mles().iter().zip([
+        &circom_r1cs.r1cs.a,
+        &circom_r1cs.r1cs.b,
+        &circom_r1cs.r1cs.c,
+    ]) {
+        // 2 constraints and 4 wires
+        assert_eq!(mle.num_vars, 3);
+        assert_eq!(mle.evaluate(&point), mle.to_dense().evaluate(&point));
+        assert_eq!(
+            mle.fix_first_variables(&point[..1]).evaluate(&point[1..]),
+            mle.evaluate(&point)
+        );
+
+        for (row, column, value) in matrix.entries.iter() {
+            assert_eq!(mle.to_dense().evaluated_values[row * 4 + column], *value);
+        }
+    }
+}
+
+#[test]
+fn test_spartan_on_circom_r1cs() {
+    let circom_r1cs = read_r1cs_file(fixture("cubic.r1cs")).unwrap();
+    let wire_values = read_witness_file(fixture("cubic.wtns")).unwrap();
+    let (public_inputs, witness) = circom_r1cs.split_witness(&wire_values).unwrap();
+
+    let (proof, _) = prove(
+        &circom_r1cs.r1cs,
+        public_inputs,
+        witness,
+        &mut Transcript::new(),
+    );
+    let claim = verify(
+        &circom_r1cs.r1cs,
+        public_inputs,
+        &proof,
+        &mut Transcript::new(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        circom_r1cs.r1cs.witness_mle(witness).evaluate(&claim.point),
+        claim.value
+    );
+}
+
+#[test]
+fn test_malformed_files() {
+    let r1cs_bytes = std::fs::read(fixture("cubic.r1cs")).unwrap();
+    let wtns_bytes = std::fs::read(fixture("cubic.wtns")).unwrap();
+
+    assert!(matches!(
+        read_r1cs(&wtns_bytes),
+        Err(CircomError::InvalidMagic)
+    ));
+    assert!(matches!(
+        read_r1cs(&r1cs_bytes[..r1cs_bytes.len() - 1]),
+        Err(CircomError::UnexpectedEnd)
+    ));
+
+    // a header claiming u32::MAX sections, with none of them in the file
+    let mut too_many_sections = r1cs_bytes[..12].to_vec();
+    too_many_sections[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(
+        read_r1cs(&too_many_sections),
+        Err(CircomError::UnexpectedEnd)
+    ));
+
+    let mut wrong_version = wtns_bytes.clone();
+    wrong_version[4] = 1;
+    assert!(matches!(
+        read_witness(&wrong_version),
+        Err(CircomError::UnsupportedVersion(1))
+    ));
+
+    // the prime starts after the magic, the version, the number of sections, the section header and the field size
+    let mut wrong_prime = wtns_bytes.clone();
+    wrong_prime[28] ^= 1;
+    assert!(matches!(
+        read_witness(&wrong_prime),
+        Err(CircomError::WrongField)
+    ));
+
+    // the last byte of the last value, set above the modulus
+    let mut out_of_range = wtns_bytes;
+    *out_of_range.last_mut().unwrap() = 0xff;
+    assert!(matches!(
+        read_witness(&out_of_range),
+        Err(CircomError::Malformed(_))
+    ));
+}
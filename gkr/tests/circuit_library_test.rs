@@ -0,0 +1,90 @@
+use ark_bn254::Fq;
+use gkr::circuits::library::{
+    dot_product, fibonacci, matrix_multiplication, polynomial_evaluation, InputLayout,
+};
+use gkr::gkr_p::{prove, verify};
+
+fn to_field(values: &[u64]) -> Vec<Fq> {
+    values.iter().map(|value| Fq::from(*value)).collect()
+}
+
+#[test]
+fn test_input_layout() {
+    let layout = InputLayout::new(&[("a", 3), ("b", 2)]);
+
+    assert_eq!(layout.num_inputs(), 5);
+    assert_eq!(layout.segment("b").offset, 3);
+    assert_eq!(
+        layout.assign(&[&to_field(&[1, 2, 3]), &to_field(&[4, 5])]),
+        to_field(&[1, 2, 3, 4, 5])
+    );
+}
+
+#[test]
+#[should_panic(expected = "the values must have the length of their segment")]
+fn test_input_layout_rejects_wrong_lengths() {
+    let layout = InputLayout::new(&[("a", 3), ("b", 2)]);
+    layout.assign(&[&to_field(&[1, 2]), &to_field(&[4, 5])]);
+}
+
+#[test]
+fn test_dot_product() {
+    let (mut circuit, layout) = dot_product::<Fq>(5);
+    let inputs = layout.assign(&[&to_field(&[1, 2, 3, 4, 5]), &to_field(&[6, 7, 8, 9, 10])]);
+
+    // 6 + 14 + 24 + 36 + 50
+    assert_eq!(circuit.evaluate(inputs.clone()).output, vec![Fq::from(130)]);
+
+    let proof = prove(&mut circuit, &inputs);
+    assert!(verify(&mut circuit, proof, &inputs));
+}
+
+#[test]
+fn test_matrix_multiplication() {
+    let (mut circuit, layout) = matrix_multiplication::<Fq>(2, 3, 2);
+    let a = to_field(&[1, 2, 3, 4, 5, 6]);
+    let b = to_field(&[7, 8, 9, 10, 11, 12]);
+    let inputs = layout.assign(&[&a, &b]);
+
+    // [[1, 2, 3], [4, 5, 6]] * [[7, 8], [9, 10], [11, 12]]
+    assert_eq!(
+        circuit.evaluate(inputs.clone()).output,
+        to_field(&[58, 64, 139, 154])
+    );
+
+    let proof = prove(&mut circuit, &inputs);
+    assert!(verify(&mut circuit, proof, &inputs));
+
+    let proof = prove(&mut circuit, &inputs);
+    let mut wrong_inputs = inputs.clone();
+    wrong_inputs[layout.segment("B").offset] = Fq::from(0);
+    assert!(!verify(&mut circuit, proof, &wrong_inputs));
+}
+
+#[test]
+fn test_polynomial_evaluation() {
+    let (mut circuit, layout) = polynomial_evaluation::<Fq>(3, 3);
+    // 2 + 3x + x^3
+    let coefficients = to_field(&[2, 3, 0, 1]);
+    let inputs = layout.assign(&[&coefficients, &to_field(&[0, 1, 4])]);
+
+    assert_eq!(
+        circuit.evaluate(inputs.clone()).output,
+        to_field(&[2, 6, 78])
+    );
+
+    let proof = prove(&mut circuit, &inputs);
+    assert!(verify(&mut circuit, proof, &inputs));
+}
+
+#[test]
+fn test_fibonacci() {
+    let (mut circuit, layout) = fibonacci::<Fq>(10);
+    let inputs = layout.assign(&[&to_field(&[0]), &to_field(&[1])]);
+
+    assert_eq!(circuit.layers.len(), 9);
+    assert_eq!(circuit.evaluate(inputs.clone()).output, vec![Fq::from(55)]);
+
+    let proof = prove(&mut circuit, &inputs);
+    assert!(verify(&mut circuit, proof, &inputs));
+}
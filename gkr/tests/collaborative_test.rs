@@ -0,0 +1,165 @@
+use ark_bn254::Fq;
+use gkr::circuits::circuit::Circuit;
+use gkr::circuits::gate::{Gate, Operation};
+use gkr::circuits::layer::Layer;
+use gkr::collaborative::{in_memory_channels, prove_party, share_inputs, PartyShares};
+use gkr::gkr_p::{self, Proof};
+use sharmir_secret_sharing::shamir_secret_sharing::recover_secret;
+use std::io;
+use std::thread;
+
+fn circuit() -> Circuit<Fq> {
+    let layer0 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::ADD),
+        Gate::new(1, 2, 1, Operation::MUL),
+        Gate::new(2, 2, 2, Operation::ADD),
+    ]);
+    let layer1 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::MUL),
+        Gate::new(2, 3, 1, Operation::ADD),
+        Gate::new(3, 4, 2, Operation::MUL),
+    ]);
+
+    Circuit::new(vec![layer0, layer1])
+}
+
+// 4 copies of (x0 + x1) * (x2 * x3)
+fn data_parallel_circuit() -> Circuit<Fq> {
+    let layer0 = (0..4)
+        .map(|copy| Gate::new(2 * copy, 2 * copy + 1, copy, Operation::MUL))
+        .collect();
+    let layer1 = (0..4)
+        .flat_map(|copy| {
+            [
+                Gate::new(4 * copy, 4 * copy + 1, 2 * copy, Operation::ADD),
+                Gate::new(4 * copy + 2, 4 * copy + 3, 2 * copy + 1, Operation::MUL),
+            ]
+        })
+        .collect();
+
+    Circuit::new(vec![Layer::new(layer0), Layer::new(layer1)])
+}
+
+fn inputs() -> Vec<Fq> {
+    [2, 3, 4, 5, 6, 0, 0, 0].map(Fq::from).to_vec()
+}
+
+fn assert_same_proof(proof: &Proof<Fq>, expected: &Proof<Fq>) {
+    assert_eq!(proof.circuit_output, expected.circuit_output);
+    assert_eq!(proof.claimed_sum, expected.claimed_sum);
+    assert_eq!(proof.sumcheck_proofs.len(), expected.sumcheck_proofs.len());
+    for (sumcheck, expected) in proof.sumcheck_proofs.iter().zip(&expected.sumcheck_proofs) {
+        assert_eq!(sumcheck.claimed_sum, expected.claimed_sum);
+        assert_eq!(sumcheck.random_challenges, expected.random_challenges);
+
+        for (polynomial, expected) in sumcheck
+            .round_univariate_polynomials
+            .iter()
+            .zip(&expected.round_univariate_polynomials)
+        {
+            assert_eq!(polynomial.coefficients, expected.coefficients);
+        }
+    }
+    assert_eq!(proof.wb_evaluations, expected.wb_evaluations);
+    assert_eq!(proof.wc_evaluations, expected.wc_evaluations);
+    assert_eq!(
+        proof.multi_input_evaluations,
+        expected.multi_input_evaluations
+    );
+    assert_eq!(proof.line_restrictions, expected.line_restrictions);
+}
+
+// Every party runs on its own thread with only its own shares, they must all get the same proof
+fn prove_collaboratively(
+    circuit: fn() -> Circuit<Fq>,
+    parties: Vec<PartyShares<Fq>>,
+    threshold: u64,
+) -> Proof<Fq> {
+    let channels = in_memory_channels(parties.len());
+    let handles: Vec<_> = parties
+        .into_iter()
+        .zip(channels)
+        .map(|(shares, channels)| {
+            thread::spawn(move || prove_party(&circuit(), &shares, threshold, channels))
+        })
+        .collect();
+
+    let proofs: Vec<Proof<Fq>> = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap().unwrap())
+        .collect();
+    for proof in proofs[1..].iter() {
+        assert_same_proof(proof, &proofs[0]);
+    }
+
+    proofs.into_iter().next().unwrap()
+}
+
+#[test]
+fn test_collaborative_proof_matches_gkr_p() {
+    let mut circuit = circuit();
+    let parties = share_inputs(&inputs(), 2, 3);
+
+    let proof = prove_collaboratively(self::circuit, parties, 2);
+
+    // (2 * 3) + (4 + 5), (4 + 5) * (5 * 6), (5 * 6) + (5 * 6)
+    assert_eq!(proof.circuit_output, [15, 270, 60].map(Fq::from).to_vec());
+    assert_same_proof(&proof, &gkr_p::prove(&mut circuit, &inputs()));
+    assert!(gkr_p::verify(&mut circuit, proof, &inputs()));
+}
+
+#[test]
+fn test_collaborative_proof_with_higher_threshold() {
+    let mut circuit = data_parallel_circuit();
+    let inputs: Vec<Fq> = (1..=16).map(Fq::from).collect();
+
+    let proof = prove_collaboratively(data_parallel_circuit, share_inputs(&inputs, 3, 5), 3);
+
+    assert_same_proof(&proof, &gkr_p::prove(&mut circuit, &inputs));
+    assert!(gkr_p::verify(&mut circuit, proof, &inputs));
+}
+
+#[test]
+fn test_proof_does_not_depend_on_the_sharing() {
+    let proof = prove_collaboratively(circuit, share_inputs(&inputs(), 2, 4), 2);
+    let other_proof = prove_collaboratively(circuit, share_inputs(&inputs(), 2, 4), 2);
+
+    assert_same_proof(&proof, &other_proof);
+}
+
+#[test]
+fn test_threshold_parties_recover_the_inputs() {
+    let inputs = inputs();
+    let parties = share_inputs(&inputs, 3, 5);
+
+    for (index, input) in inputs.iter().enumerate() {
+        let shares = parties[1..4]
+            .iter()
+            .map(|party| (party.x, party.inputs[index]))
+            .collect();
+
+        assert_eq!(recover_secret(shares), *input);
+    }
+}
+
+#[test]
+fn test_collaborative_prove_needs_enough_parties() {
+    let parties = share_inputs(&inputs(), 3, 4);
+    let channels = in_memory_channels(4).swap_remove(0);
+
+    let error = prove_party(&circuit(), &parties[0], 3, channels).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(
+        error.to_string(),
+        "the degree reduction needs at least 2 * threshold - 1 parties"
+    );
+}
+
+#[test]
+fn test_party_fails_when_another_party_is_gone() {
+    let parties = share_inputs(&inputs(), 2, 3);
+    // the parties 1 and 2 never run, their ends of the channels are dropped
+    let channels = in_memory_channels(3).swap_remove(0);
+
+    assert!(prove_party(&circuit(), &parties[0], 2, channels).is_err());
+}
@@ -0,0 +1,219 @@
+use ark_bn254::Fq;
+use gkr::circuits::circuit::Circuit;
+use gkr::circuits::gate::{Gate, Operation};
+use gkr::circuits::layer::Layer;
+use gkr::distributed::{connect_worker, run_worker, Coordinator};
+use gkr::gkr_p::{self, Proof};
+use gkr::interactive::{send_field_elements, InMemoryChannel};
+use std::io;
+use std::net::TcpListener;
+use std::process::Command;
+use std::thread::{self, JoinHandle};
+
+// copies of (x0 + x1) * (x2 * x3), with the copy index in the high bits of every layer
+fn data_parallel_circuit(number_of_copies: usize) -> Circuit<Fq> {
+    let layer0 = (0..number_of_copies)
+        .map(|copy| Gate::new(2 * copy, 2 * copy + 1, copy, Operation::MUL))
+        .collect();
+    let layer1 = (0..number_of_copies)
+        .flat_map(|copy| {
+            [
+                Gate::new(4 * copy, 4 * copy + 1, 2 * copy, Operation::ADD),
+                Gate::new(4 * copy + 2, 4 * copy + 3, 2 * copy + 1, Operation::MUL),
+            ]
+        })
+        .collect();
+
+    Circuit::new(vec![Layer::new(layer0), Layer::new(layer1)])
+}
+
+fn inputs(count: u64) -> Vec<Fq> {
+    (1..=count).map(Fq::from).collect()
+}
+
+fn start_workers(listener: &TcpListener, count: usize) -> Vec<JoinHandle<io::Result<()>>> {
+    let address = listener.local_addr().unwrap();
+
+    (0..count)
+        .map(|_| thread::spawn(move || connect_worker::<Fq, _>(address)))
+        .collect()
+}
+
+fn assert_same_proof(proof: &Proof<Fq>, expected: &Proof<Fq>) {
+    assert_eq!(proof.circuit_output, expected.circuit_output);
+    assert_eq!(proof.claimed_sum, expected.claimed_sum);
+    assert_eq!(proof.sumcheck_proofs.len(), expected.sumcheck_proofs.len());
+    for (sumcheck, expected) in proof.sumcheck_proofs.iter().zip(&expected.sumcheck_proofs) {
+        assert_eq!(sumcheck.claimed_sum, expected.claimed_sum);
+        assert_eq!(sumcheck.random_challenges, expected.random_challenges);
+
+        let coefficients = |proof: &gkr::sum_check::SumcheckProverProof<Fq>| {
+            proof
+                .round_univariate_polynomials
+                .iter()
+                .map(|polynomial| polynomial.coefficients.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(coefficients(sumcheck), coefficients(expected));
+    }
+    assert_eq!(proof.wb_evaluations, expected.wb_evaluations);
+    assert_eq!(proof.wc_evaluations, expected.wc_evaluations);
+    assert_eq!(
+        proof.multi_input_evaluations,
+        expected.multi_input_evaluations
+    );
+    assert_eq!(proof.line_restrictions, expected.line_restrictions);
+}
+
+fn prove_distributed(
+    circuit: &mut Circuit<Fq>,
+    inputs: &[Fq],
+    number_of_workers: usize,
+) -> Proof<Fq> {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let workers = start_workers(&listener, number_of_workers);
+    let mut coordinator = Coordinator::accept(&listener, number_of_workers).unwrap();
+
+    let proof = coordinator.prove(circuit, inputs).unwrap();
+
+    coordinator.shutdown().unwrap();
+    for worker in workers {
+        worker.join().unwrap().unwrap();
+    }
+
+    proof
+}
+
+#[test]
+fn test_distributed_proof_matches_gkr_p() {
+    let mut circuit = data_parallel_circuit(2);
+    let inputs = inputs(8);
+
+    let proof = prove_distributed(&mut circuit, &inputs, 2);
+    let expected = gkr_p::prove(&mut circuit, &inputs);
+
+    // (1 + 2) * (3 * 4) and (5 + 6) * (7 * 8)
+    assert_eq!(proof.circuit_output, [36, 616].map(Fq::from).to_vec());
+    assert_same_proof(&proof, &expected);
+    assert!(gkr_p::verify(&mut circuit, proof, &inputs));
+}
+
+#[test]
+fn test_distributed_proof_with_four_workers() {
+    let mut circuit = data_parallel_circuit(8);
+    let inputs = inputs(32);
+
+    let proof = prove_distributed(&mut circuit, &inputs, 4);
+    let expected = gkr_p::prove(&mut circuit, &inputs);
+
+    assert_same_proof(&proof, &expected);
+    assert!(gkr_p::verify(&mut circuit, proof, &inputs));
+}
+
+#[test]
+fn test_distributed_workers_prove_several_circuits() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let workers = start_workers(&listener, 2);
+    let mut coordinator = Coordinator::accept(&listener, 2).unwrap();
+
+    for (number_of_copies, inputs) in [(4, inputs(16)), (2, inputs(8))] {
+        let mut circuit = data_parallel_circuit(number_of_copies);
+
+        let proof = coordinator.prove(&mut circuit, &inputs).unwrap();
+        assert_same_proof(&proof, &gkr_p::prove(&mut circuit, &inputs));
+    }
+
+    coordinator.shutdown().unwrap();
+    for worker in workers {
+        worker.join().unwrap().unwrap();
+    }
+}
+
+#[test]
+fn test_distributed_small_layers_stay_on_the_coordinator() {
+    // The first layer only reads 2 values: 4 (b, c) pairs for 8 workers
+    let mut circuit = data_parallel_circuit(1);
+    let inputs = inputs(4);
+
+    let proof = prove_distributed(&mut circuit, &inputs, 8);
+
+    assert_same_proof(&proof, &gkr_p::prove(&mut circuit, &inputs));
+}
+
+#[test]
+fn test_distributed_proof_forwards_layers_in_chunks() {
+    // The halves swapped by the workers in the first round of the input layer are 4096 values long
+    let mut circuit = data_parallel_circuit(16);
+    let inputs = inputs(64);
+
+    let proof = prove_distributed(&mut circuit, &inputs, 2);
+
+    assert_same_proof(&proof, &gkr_p::prove(&mut circuit, &inputs));
+}
+
+#[test]
+fn test_distributed_workers_in_their_own_processes() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let mut workers: Vec<_> = (0..2)
+        .map(|_| {
+            Command::new(env!("CARGO_BIN_EXE_gkr_worker"))
+                .arg(&address)
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+    let mut coordinator = Coordinator::accept(&listener, 2).unwrap();
+
+    let mut circuit = data_parallel_circuit(4);
+    let inputs = inputs(16);
+    let proof = coordinator.prove(&mut circuit, &inputs).unwrap();
+
+    assert_same_proof(&proof, &gkr_p::prove(&mut circuit, &inputs));
+    coordinator.shutdown().unwrap();
+    for worker in workers.iter_mut() {
+        assert!(worker.wait().unwrap().success());
+    }
+}
+
+#[test]
+fn test_worker_rejects_invalid_messages() {
+    // an unknown command, a challenge before any table is loaded, an empty message
+    for message in [vec![Fq::from(42)], vec![Fq::from(7), Fq::from(1)], vec![]] {
+        let (mut coordinator_channel, mut worker_channel) = InMemoryChannel::pair();
+        let worker = thread::spawn(move || run_worker::<Fq, _>(&mut worker_channel));
+
+        send_field_elements(&mut coordinator_channel, &message).unwrap();
+
+        let error = worker.join().unwrap().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
+
+#[test]
+fn test_coordinator_rejects_circuits_it_does_not_support() {
+    let workers: Vec<InMemoryChannel> = (0..2).map(|_| InMemoryChannel::pair().0).collect();
+    let mut coordinator = Coordinator::<Fq, _>::new(workers);
+
+    // the second layer reads an input directly, skipping the layer below
+    let mut skip_circuit = data_parallel_circuit(1);
+    skip_circuit.layers[0].gates[0] = Gate::new_with_layers(0, 2, 1, 1, 0, Operation::MUL);
+    let error = coordinator
+        .prove(&mut skip_circuit, &inputs(4))
+        .unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+
+    let error = coordinator
+        .prove(&mut data_parallel_circuit(1), &inputs(5))
+        .unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(error.to_string(), "more inputs than the circuit reads");
+}
+
+#[test]
+#[should_panic(expected = "power of two")]
+fn test_coordinator_needs_a_power_of_two_of_workers() {
+    let workers: Vec<InMemoryChannel> = (0..3).map(|_| InMemoryChannel::pair().0).collect();
+
+    Coordinator::<Fq, _>::new(workers);
+}
@@ -97,3 +97,26 @@ fn test_polynomial_tensor_mul_different_lengths() {
     // Should panic due to different lengths
     MultilinearPolynomialEV::polynomial_tensor_mul(&w_b, &w_c);
 }
+
+#[test]
+fn test_eq_polynomial() {
+    let point = vec![Fq::from(2), Fq::from(5)];
+    let eq_polynomial = MultilinearPolynomialEV::eq_polynomial(&point);
+
+    // eq((2,5), x) = (1-2)(1-5), (1-2)5, 2(1-5), 2*5
+    assert_eq!(
+        eq_polynomial,
+        MultilinearPolynomialEV::new(&[Fq::from(4), -Fq::from(5), -Fq::from(8), Fq::from(10)])
+    );
+
+    let polynomial =
+        MultilinearPolynomialEV::new(&[Fq::from(0), Fq::from(0), Fq::from(3), Fq::from(8)]);
+    let sum: Fq = eq_polynomial
+        .evaluated_values
+        .iter()
+        .zip(polynomial.evaluated_values.iter())
+        .map(|(eq, value)| *eq * value)
+        .sum();
+
+    assert_eq!(sum, polynomial.evaluate(&point));
+}
@@ -0,0 +1,86 @@
+use ark_ff::{Field, PrimeField};
+use gkr::circuits::circuit::Circuit;
+use gkr::circuits::gate::{Gate, Operation};
+use gkr::circuits::layer::Layer;
+use gkr::extension_gkr::{prove, verify};
+use gkr::fields::{BabyBear, BabyBearExt4, Goldilocks, GoldilocksExt2};
+
+// layer 0: (l1[0] + l1[1]), (l1[1] * l1[2]), (l1[2] + l1[2])
+// layer 1: (in0 * in1), (in2 + in3), (in3 * in4)
+fn circuit<F: PrimeField>() -> Circuit<F> {
+    let layer0 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::ADD),
+        Gate::new(1, 2, 1, Operation::MUL),
+        Gate::new(2, 2, 2, Operation::ADD),
+    ]);
+    let layer1 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::MUL),
+        Gate::new(2, 3, 1, Operation::ADD),
+        Gate::new(3, 4, 2, Operation::MUL),
+    ]);
+
+    Circuit::new(vec![layer0, layer1])
+}
+
+fn inputs<F: PrimeField>() -> Vec<F> {
+    [2, 3, 4, 5, 6, 0, 0, 0].map(F::from).to_vec()
+}
+
+fn check_extension_gkr<F: PrimeField, E: Field<BasePrimeField = F>>() {
+    let mut circuit = circuit::<F>();
+
+    let proof = prove::<F, E>(&mut circuit, &inputs());
+
+    // (2 * 3) + (4 + 5), (4 + 5) * (5 * 6), (5 * 6) + (5 * 6)
+    assert_eq!(proof.circuit_output, [15, 270, 60].map(F::from).to_vec());
+    assert!(verify(&circuit, &proof, &inputs()));
+
+    // the challenges are not in the base field
+    assert!(proof.sumcheck_proofs.iter().all(|sumcheck| {
+        sumcheck.random_challenges.iter().all(|challenge| {
+            challenge
+                .to_base_prime_field_elements()
+                .skip(1)
+                .any(|coefficient| coefficient != F::zero())
+        })
+    }));
+}
+
+#[test]
+fn test_goldilocks_gkr_with_extension_challenges() {
+    check_extension_gkr::<Goldilocks, GoldilocksExt2>();
+}
+
+#[test]
+fn test_baby_bear_gkr_with_extension_challenges() {
+    check_extension_gkr::<BabyBear, BabyBearExt4>();
+}
+
+#[test]
+fn test_extension_gkr_rejects_wrong_proofs() {
+    let mut circuit = circuit::<Goldilocks>();
+    let inputs = inputs::<Goldilocks>();
+
+    let mut proof = prove::<Goldilocks, GoldilocksExt2>(&mut circuit, &inputs);
+    proof.circuit_output[1] += Goldilocks::ONE;
+    assert!(!verify(&circuit, &proof, &inputs));
+
+    let mut proof = prove::<Goldilocks, GoldilocksExt2>(&mut circuit, &inputs);
+    proof.wb_evaluations[0] += GoldilocksExt2::ONE;
+    assert!(!verify(&circuit, &proof, &inputs));
+
+    let proof = prove::<Goldilocks, GoldilocksExt2>(&mut circuit, &inputs);
+    let mut wrong_inputs = inputs.clone();
+    wrong_inputs[4] = Goldilocks::from(7u64);
+    assert!(!verify(&circuit, &proof, &wrong_inputs));
+}
+
+#[test]
+#[should_panic(expected = "extension GKR only supports two input gates reading the layer below")]
+fn test_extension_gkr_needs_two_input_gates() {
+    let layer0 = Layer::new(vec![Gate::new_with_layers(0, 2, 1, 1, 0, Operation::ADD)]);
+    let layer1 = Layer::new(vec![Gate::new(0, 1, 0, Operation::MUL)]);
+    let mut circuit = Circuit::<Goldilocks>::new(vec![layer0, layer1]);
+
+    prove::<Goldilocks, GoldilocksExt2>(&mut circuit, &inputs());
+}
@@ -0,0 +1,126 @@
+use ark_bn254::Fq;
+use ark_ff::{One, Zero};
+use gkr::circuits::gadgets::{
+    bit_decomposition, bit_decomposition_witness, bits_of, booleanity, less_than,
+    less_than_witness, range_check, range_check_witness, Gadget,
+};
+use gkr::gkr_p::{prove, verify};
+
+fn outputs(gadget: Gadget, inputs: Vec<Fq>) -> Vec<Fq> {
+    let num_outputs = gadget.num_outputs;
+    let mut circuit = gadget.circuit::<Fq>();
+    let output = circuit.evaluate(inputs).output;
+
+    assert_eq!(output.len(), num_outputs);
+    output
+}
+
+fn all_zero(values: &[Fq]) -> bool {
+    values.iter().all(|value| value.is_zero())
+}
+
+#[test]
+fn test_booleanity() {
+    let inputs = vec![Fq::from(0), Fq::from(1), Fq::from(1), Fq::from(0)];
+    assert!(all_zero(&outputs(booleanity(4), inputs)));
+
+    // 2 - 2^2 = -2
+    let inputs = vec![Fq::from(0), Fq::from(2), Fq::from(1), Fq::from(0)];
+    assert_eq!(
+        outputs(booleanity(4), inputs),
+        vec![Fq::zero(), -Fq::from(2), Fq::zero(), Fq::zero()]
+    );
+}
+
+#[test]
+fn test_bits_of() {
+    assert_eq!(
+        bits_of(Fq::from(11), 5),
+        vec![
+            Fq::from(1),
+            Fq::from(1),
+            Fq::from(0),
+            Fq::from(1),
+            Fq::from(0)
+        ]
+    );
+}
+
+#[test]
+fn test_bit_decomposition() {
+    let gadget = bit_decomposition(7);
+    assert_eq!(gadget.num_inputs, 8);
+    assert_eq!(gadget.num_outputs, 8);
+
+    let witness = bit_decomposition_witness(Fq::from(101), 7);
+    assert!(all_zero(&outputs(bit_decomposition(7), witness.clone())));
+
+    // bits that are not the decomposition of the value
+    let mut wrong_bits = witness.clone();
+    wrong_bits[1] = Fq::zero();
+    let constraints = outputs(bit_decomposition(7), wrong_bits);
+    assert_eq!(constraints[7], -Fq::one());
+
+    // a non-boolean "bit" that still recomposes to the value: 101 = 1 + 2 * 2 + 32 + 64
+    let mut non_boolean = vec![Fq::from(101), Fq::from(1), Fq::from(2)];
+    non_boolean.extend([0, 0, 0, 1, 1].map(Fq::from));
+    let constraints = outputs(bit_decomposition(7), non_boolean);
+    assert_eq!(constraints[1], -Fq::from(2));
+    assert!(constraints[7].is_zero());
+}
+
+#[test]
+fn test_range_check() {
+    let values = [Fq::from(0), Fq::from(255), Fq::from(17)];
+    assert!(all_zero(&outputs(
+        range_check(3, 8),
+        range_check_witness(&values, 8)
+    )));
+
+    // 256 does not fit in 8 bits, the witness helper only keeps the low bits
+    let values = [Fq::from(0), Fq::from(256), Fq::from(17)];
+    let constraints = outputs(range_check(3, 8), range_check_witness(&values, 8));
+    assert_eq!(constraints[17], -Fq::from(256));
+
+    // -1 is the largest field element, it does not fit either
+    let values = [-Fq::one()];
+    assert!(!all_zero(&outputs(
+        range_check(1, 8),
+        range_check_witness(&values, 8)
+    )));
+}
+
+#[test]
+fn test_less_than() {
+    for (a, b) in [(3, 9), (9, 3), (5, 5), (0, 15), (15, 0)] {
+        let witness = less_than_witness(Fq::from(a), Fq::from(b), 4);
+        let result = outputs(less_than(4), witness);
+
+        assert!(all_zero(&result[..result.len() - 1]));
+        assert_eq!(result[result.len() - 1], Fq::from((a < b) as u64));
+    }
+
+    // flipping the top bit claims 9 < 3, which breaks the recomposition
+    let mut witness = less_than_witness(Fq::from(9), Fq::from(3), 4);
+    witness[6] = Fq::zero();
+    let result = outputs(less_than(4), witness);
+    assert!(result[5] == -Fq::from(16));
+    assert!(result[6].is_one());
+}
+
+#[test]
+fn test_gkr_protocol_with_gadgets() {
+    let mut circuit = range_check(2, 4).circuit::<Fq>();
+    let witness = range_check_witness(&[Fq::from(3), Fq::from(12)], 4);
+
+    let proof = prove(&mut circuit, &witness);
+    assert!(all_zero(&proof.circuit_output));
+    assert!(verify(&mut circuit, proof, &witness));
+
+    let mut circuit = less_than(3).circuit::<Fq>();
+    let witness = less_than_witness(Fq::from(2), Fq::from(6), 3);
+
+    let proof = prove(&mut circuit, &witness);
+    assert!(proof.circuit_output[proof.circuit_output.len() - 1].is_one());
+    assert!(verify(&mut circuit, proof, &witness));
+}
@@ -0,0 +1,141 @@
+use ark_bn254::Fq;
+use gkr::circuits::circuit::Circuit;
+use gkr::circuits::gate::{Gate, Operation};
+use gkr::circuits::layer::Layer;
+use gkr::gkr_p::{prove, verify, verify_batch, BatchVerificationError, Proof};
+
+fn circuit() -> Circuit<Fq> {
+    let layer0 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::ADD),
+        Gate::new(2, 3, 1, Operation::MUL),
+    ]);
+    let layer1 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::MUL),
+        Gate::new(2, 3, 1, Operation::ADD),
+        Gate::new(4, 5, 2, Operation::MUL),
+        Gate::new(6, 7, 3, Operation::ADD),
+    ]);
+
+    Circuit::new(vec![layer0, layer1])
+}
+
+fn batch(circuit: &mut Circuit<Fq>, size: u64) -> (Vec<Proof<Fq>>, Vec<Vec<Fq>>) {
+    let inputs: Vec<Vec<Fq>> = (0..size)
+        .map(|k| (1..=8).map(|i| Fq::from(k * 10 + i)).collect())
+        .collect();
+    let proofs = inputs.iter().map(|inputs| prove(circuit, inputs)).collect();
+
+    (proofs, inputs)
+}
+
+#[test]
+fn test_verify_batch() {
+    let mut circuit = circuit();
+    let (proofs, inputs) = batch(&mut circuit, 5);
+
+    assert_eq!(verify_batch(&mut circuit, &proofs, &inputs), Ok(()));
+    for (proof, inputs) in proofs.into_iter().zip(inputs.iter()) {
+        assert!(verify(&mut circuit, proof, inputs));
+    }
+
+    assert_eq!(verify_batch(&mut circuit, &[], &[]), Ok(()));
+}
+
+#[test]
+fn test_verify_batch_with_fewer_inputs_than_the_circuit_reads() {
+    let mut circuit = circuit();
+
+    // the inputs the verifier is not given are zeros, as for verify
+    let inputs: Vec<Vec<Fq>> = (0..3)
+        .map(|k| (1..=5 + k).map(Fq::from).collect())
+        .collect();
+    let proofs: Vec<Proof<Fq>> = inputs
+        .iter()
+        .map(|inputs| {
+            let mut padded_inputs = inputs.clone();
+            padded_inputs.resize(8, Fq::from(0));
+            prove(&mut circuit, &padded_inputs)
+        })
+        .collect();
+
+    assert_eq!(verify_batch(&mut circuit, &proofs, &inputs), Ok(()));
+
+    let mut padded_inputs = inputs.clone();
+    padded_inputs[1].push(Fq::from(0));
+    padded_inputs[2][0] += Fq::from(1);
+    assert_eq!(
+        verify_batch(&mut circuit, &proofs, &padded_inputs),
+        Err(BatchVerificationError::InvalidProofs {
+            failed_proofs: vec![2]
+        })
+    );
+}
+
+#[test]
+fn test_verify_batch_reports_invalid_layer_proof() {
+    let mut circuit = circuit();
+    let (mut proofs, inputs) = batch(&mut circuit, 4);
+
+    proofs[2].wb_evaluations[0] += Fq::from(1);
+
+    assert_eq!(
+        verify_batch(&mut circuit, &proofs, &inputs),
+        Err(BatchVerificationError::InvalidProofs {
+            failed_proofs: vec![2]
+        })
+    );
+}
+
+#[test]
+fn test_verify_batch_reports_wrong_inputs() {
+    let mut circuit = circuit();
+    let (proofs, mut inputs) = batch(&mut circuit, 4);
+
+    // the layer proofs hold, only the final check on the input layer fails
+    inputs[1][3] += Fq::from(1);
+    inputs[3][0] += Fq::from(1);
+
+    let error = verify_batch(&mut circuit, &proofs, &inputs).unwrap_err();
+    assert_eq!(
+        error,
+        BatchVerificationError::InvalidProofs {
+            failed_proofs: vec![1, 3]
+        }
+    );
+    assert_eq!(error.to_string(), "invalid proofs at positions [1, 3]");
+}
+
+#[test]
+fn test_verify_batch_reports_every_failure() {
+    let mut circuit = circuit();
+    let (mut proofs, mut inputs) = batch(&mut circuit, 6);
+
+    proofs[4].circuit_output[0] += Fq::from(1);
+    proofs[0].sumcheck_proofs[1].claimed_sum += Fq::from(1);
+    inputs[2].push(Fq::from(9));
+
+    assert_eq!(
+        verify_batch(&mut circuit, &proofs, &inputs).unwrap_err(),
+        BatchVerificationError::InvalidProofs {
+            failed_proofs: vec![0, 2, 4]
+        }
+    );
+}
+
+#[test]
+fn test_verify_batch_needs_one_list_of_inputs_per_proof() {
+    let mut circuit = circuit();
+    let (proofs, mut inputs) = batch(&mut circuit, 3);
+
+    inputs.pop();
+
+    let error = verify_batch(&mut circuit, &proofs, &inputs).unwrap_err();
+    assert_eq!(
+        error,
+        BatchVerificationError::LengthMismatch {
+            proofs: 3,
+            inputs: 2
+        }
+    );
+    assert_eq!(error.to_string(), "3 proofs for 2 lists of inputs");
+}
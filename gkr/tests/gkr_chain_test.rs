@@ -0,0 +1,69 @@
+use ark_bn254::Fq;
+use gkr::circuits::circuit::Circuit;
+use gkr::circuits::gate::{Gate, Operation};
+use gkr::circuits::layer::Layer;
+use gkr::gkr_p::{prove_chain, verify_chain};
+
+// preprocess: 8 inputs -> 4 values, compute: 4 values -> 2 values, postprocess: 2 values -> 1 output
+fn test_setup() -> Vec<Circuit<Fq>> {
+    let preprocess = Circuit::new(vec![Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::ADD),
+        Gate::new(2, 3, 1, Operation::ADD),
+        Gate::new(4, 5, 2, Operation::MUL),
+        Gate::new(6, 7, 3, Operation::MUL),
+    ])]);
+
+    let compute = Circuit::new(vec![
+        Layer::new(vec![
+            Gate::new(0, 1, 0, Operation::MUL),
+            Gate::new(2, 3, 1, Operation::ADD),
+        ]),
+        Layer::new(vec![
+            Gate::new(0, 1, 0, Operation::ADD),
+            Gate::new(1, 2, 1, Operation::MUL),
+            Gate::new(2, 3, 2, Operation::ADD),
+            Gate::new(3, 3, 3, Operation::MUL),
+        ]),
+    ]);
+
+    let postprocess = Circuit::new(vec![Layer::new(vec![Gate::new(0, 1, 0, Operation::ADD)])]);
+
+    vec![preprocess, compute, postprocess]
+}
+
+#[test]
+fn test_prove_and_verify_chain() {
+    let mut circuits = test_setup();
+    let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+
+    let proof = prove_chain(&mut circuits, &inputs);
+
+    // preprocess => [3, 7, 30, 56], compute => [10 * 210, 86 + 3136] = [2100, 3222], postprocess => 5322
+    assert_eq!(proof.circuit_output, vec![Fq::from(5322)]);
+    assert_eq!(proof.circuit_proofs.len(), 3);
+    assert!(verify_chain(&mut circuits, &proof, &inputs));
+}
+
+#[test]
+fn test_verify_chain_rejects_wrong_output() {
+    let mut circuits = test_setup();
+    let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+
+    let mut proof = prove_chain(&mut circuits, &inputs);
+    proof.circuit_output[0] += Fq::from(1);
+
+    assert!(!verify_chain(&mut circuits, &proof, &inputs));
+}
+
+#[test]
+fn test_verify_chain_rejects_wrong_inputs() {
+    let mut circuits = test_setup();
+    let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+
+    let proof = prove_chain(&mut circuits, &inputs);
+
+    let mut wrong_inputs = inputs.clone();
+    wrong_inputs[0] = Fq::from(2);
+
+    assert!(!verify_chain(&mut circuits, &proof, &wrong_inputs));
+}
@@ -0,0 +1,125 @@
+use ark_bn254::Fq;
+use gkr::circuits::circuit::Circuit;
+use gkr::circuits::gate::{Gate, Operation};
+use gkr::circuits::layer::Layer;
+use gkr::gkr_p::{prove_with_combination, verify_with_combination, ClaimCombination};
+
+fn test_setup() -> Circuit<Fq> {
+    let layer0 = Layer::new(vec![Gate::new(0, 1, 0, Operation::ADD)]);
+
+    let layer1 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::MUL),
+        Gate::new(2, 3, 1, Operation::ADD),
+    ]);
+
+    let layer2 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::ADD),
+        Gate::new(2, 3, 1, Operation::MUL),
+        Gate::new(4, 5, 2, Operation::ADD),
+        Gate::new(6, 7, 3, Operation::MUL),
+    ]);
+
+    Circuit::new(vec![layer0, layer1, layer2])
+}
+
+#[test]
+fn test_gkr_protocol_with_line_restriction() {
+    let mut circuit = test_setup();
+    let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+
+    let proof = prove_with_combination(&mut circuit, &inputs, ClaimCombination::LineRestriction);
+
+    // layers 1, 2 and the input layer each have the two claims at rb and rc, restricted to a line:
+    // a polynomial of degree number_of_variables, sent as number_of_variables + 1 evaluations
+    let restriction_lengths: Vec<usize> = proof
+        .line_restrictions
+        .iter()
+        .map(|restriction| restriction.len())
+        .collect();
+    assert_eq!(restriction_lengths, vec![2, 3, 4]);
+
+    assert!(verify_with_combination(
+        &mut circuit,
+        proof,
+        &inputs,
+        ClaimCombination::LineRestriction
+    ));
+}
+
+#[test]
+fn test_random_linear_combination_sends_no_line_restrictions() {
+    let mut circuit = test_setup();
+    let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+
+    let proof = prove_with_combination(
+        &mut circuit,
+        &inputs,
+        ClaimCombination::RandomLinearCombination,
+    );
+
+    assert!(proof.line_restrictions.is_empty());
+    assert!(verify_with_combination(
+        &mut circuit,
+        proof,
+        &inputs,
+        ClaimCombination::RandomLinearCombination
+    ));
+}
+
+#[test]
+fn test_line_restriction_rejects_tampered_restriction() {
+    let mut circuit = test_setup();
+    let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+
+    let mut proof =
+        prove_with_combination(&mut circuit, &inputs, ClaimCombination::LineRestriction);
+    proof.line_restrictions[1][2] += Fq::from(1);
+
+    assert!(!verify_with_combination(
+        &mut circuit,
+        proof,
+        &inputs,
+        ClaimCombination::LineRestriction
+    ));
+}
+
+#[test]
+fn test_verifier_rejects_other_claim_combination() {
+    let mut circuit = test_setup();
+    let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+
+    let proof = prove_with_combination(&mut circuit, &inputs, ClaimCombination::LineRestriction);
+
+    assert!(!verify_with_combination(
+        &mut circuit,
+        proof,
+        &inputs,
+        ClaimCombination::RandomLinearCombination
+    ));
+}
+
+#[test]
+fn test_line_restriction_with_skip_connections() {
+    // the input layer (layer 2) is read by both layers, so it has four claims, restricted to a curve of degree 3
+    let layer0 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::MUL),
+        Gate::new_with_layers(1, 2, 2, 2, 1, Operation::ADD),
+    ]);
+    let layer1 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::ADD),
+        Gate::new(2, 3, 1, Operation::MUL),
+    ]);
+
+    let mut circuit = Circuit::<Fq>::new(vec![layer0, layer1]);
+    let inputs: Vec<Fq> = (1..=4).map(Fq::from).collect();
+
+    let proof = prove_with_combination(&mut circuit, &inputs, ClaimCombination::LineRestriction);
+    assert_eq!(proof.line_restrictions[1].len(), (4 - 1) * 2 + 1);
+
+    assert!(verify_with_combination(
+        &mut circuit,
+        proof,
+        &inputs,
+        ClaimCombination::LineRestriction
+    ));
+}
@@ -0,0 +1,155 @@
+use ark_bn254::Fq;
+use gkr::circuits::circuit::Circuit;
+use gkr::circuits::gate::{
+    CustomGate, CustomGateInstance, CustomGateTerm, Gate, MulAddGate, Operation, PowerGate,
+};
+use gkr::circuits::layer::Layer;
+use gkr::gkr_p::{
+    prove, prove_with_combination, verify, verify_with_combination, ClaimCombination,
+};
+use std::sync::Arc;
+
+// x - x^2: zero exactly when x is a bit
+#[derive(Debug)]
+struct BooleanityGate;
+
+impl CustomGate for BooleanityGate {
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn degree(&self) -> usize {
+        2
+    }
+
+    fn terms(&self) -> Vec<CustomGateTerm> {
+        vec![
+            CustomGateTerm::new(1, vec![1]),
+            CustomGateTerm::new(-1, vec![2]),
+        ]
+    }
+}
+
+// A gate without terms, always 0
+#[derive(Debug)]
+struct EmptyGate;
+
+impl CustomGate for EmptyGate {
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn degree(&self) -> usize {
+        1
+    }
+
+    fn terms(&self) -> Vec<CustomGateTerm> {
+        vec![]
+    }
+}
+
+// layer 0: out0 = l1[0] * l1[1] + l1[2] (custom), out1 = l1[3] - l1[3]^2 (custom), out2 = l1[0] + l1[3]
+// layer 1: l1[j] = in[j]^5 (custom) for j in 0..3, l1[3] = in3 * in4
+fn test_setup() -> Circuit<Fq> {
+    let pow5: Arc<dyn CustomGate> = Arc::new(PowerGate { exponent: 5 });
+
+    let mut layer0 = Layer::new(vec![Gate::new(0, 3, 2, Operation::ADD)]);
+    layer0.add_custom_gate(CustomGateInstance::new(
+        Arc::new(MulAddGate),
+        vec![0, 1, 2],
+        0,
+    ));
+    layer0.add_custom_gate(CustomGateInstance::new(
+        Arc::new(BooleanityGate),
+        vec![3],
+        1,
+    ));
+
+    let mut layer1 = Layer::new(vec![Gate::new(3, 4, 3, Operation::MUL)]);
+    for j in 0..3 {
+        layer1.add_custom_gate(CustomGateInstance::new(pow5.clone(), vec![j], j));
+    }
+
+    Circuit::new(vec![layer0, layer1])
+}
+
+#[test]
+fn test_evaluate_custom_gates() {
+    let mut circuit = test_setup();
+    let inputs: Vec<Fq> = (1..=5).map(Fq::from).collect();
+
+    let evaluation = circuit.evaluate(inputs);
+
+    // layer 1 => [1, 32, 243, 4 * 5]
+    assert_eq!(
+        evaluation.layer_evaluations[1],
+        vec![Fq::from(1), Fq::from(32), Fq::from(243), Fq::from(20)]
+    );
+    // layer 0 => [1 * 32 + 243, 20 - 400, 1 + 20]
+    assert_eq!(
+        evaluation.output,
+        vec![Fq::from(275), -Fq::from(380), Fq::from(21)]
+    );
+}
+
+#[test]
+fn test_gkr_protocol_with_custom_gates() {
+    let mut circuit = test_setup();
+    let inputs: Vec<Fq> = (1..=5).map(Fq::from).collect();
+
+    let proof = prove(&mut circuit, &inputs);
+
+    // a * b + c has three inputs, so layer 0 needs one evaluation of layer 1 after rb and rc
+    assert_eq!(proof.multi_input_evaluations[0].len(), 1);
+    // x^5 makes the sumcheck of layer 1 degree 6: its round polynomials have 7 coefficients
+    assert_eq!(
+        proof.sumcheck_proofs[1].round_univariate_polynomials[0]
+            .coefficients
+            .len(),
+        7
+    );
+    assert!(verify(&mut circuit, proof, &inputs));
+}
+
+#[test]
+fn test_gkr_protocol_with_custom_gates_rejects_wrong_inputs() {
+    let mut circuit = test_setup();
+    let inputs: Vec<Fq> = (1..=5).map(Fq::from).collect();
+
+    let mut proof = prove(&mut circuit, &inputs);
+    proof.circuit_output[1] = Fq::from(0);
+    assert!(!verify(&mut circuit, proof, &inputs));
+
+    let mut wrong_inputs = inputs.clone();
+    wrong_inputs[2] = Fq::from(2);
+
+    let proof = prove(&mut circuit, &inputs);
+    assert!(!verify(&mut circuit, proof, &wrong_inputs));
+}
+
+#[test]
+fn test_custom_gates_with_line_restriction() {
+    let mut circuit = test_setup();
+    let inputs: Vec<Fq> = (1..=5).map(Fq::from).collect();
+
+    let proof = prove_with_combination(&mut circuit, &inputs, ClaimCombination::LineRestriction);
+
+    assert!(verify_with_combination(
+        &mut circuit,
+        proof,
+        &inputs,
+        ClaimCombination::LineRestriction
+    ));
+}
+
+#[test]
+#[should_panic(expected = "the number of inputs must be the arity of the gate")]
+fn test_custom_gate_needs_arity_inputs() {
+    CustomGateInstance::new(Arc::new(MulAddGate), vec![0, 1], 0);
+}
+
+#[test]
+#[should_panic(expected = "the gate needs at least one term")]
+fn test_custom_gate_needs_a_term() {
+    CustomGateInstance::new(Arc::new(EmptyGate), vec![0, 1], 0);
+}
@@ -0,0 +1,73 @@
+use ark_bn254::Fq;
+use gkr::circuits::circuit::Circuit;
+use gkr::circuits::gate::{Gate, Operation};
+use gkr::circuits::layer::Layer;
+use gkr::gkr_data_parallel::{prove, verify};
+
+fn test_setup() -> Circuit<Fq> {
+    let layer0 = Layer::new(vec![Gate::new(0, 1, 0, Operation::MUL)]);
+    let layer1 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::ADD),
+        Gate::new(2, 3, 1, Operation::MUL),
+    ]);
+
+    Circuit::new(vec![layer0, layer1])
+}
+
+fn inputs(number_of_instances: u64) -> Vec<Vec<Fq>> {
+    (0..number_of_instances)
+        .map(|i| (1..=4).map(|j| Fq::from(i * 4 + j)).collect())
+        .collect()
+}
+
+#[test]
+fn test_data_parallel_prove_and_verify() {
+    let mut circuit = test_setup();
+    let inputs = inputs(8);
+
+    let proof = prove(&mut circuit, &inputs);
+
+    // (1 + 2) * (3 * 4) for the first copy
+    assert_eq!(proof.circuit_outputs[0], vec![Fq::from(36)]);
+    assert_eq!(proof.circuit_outputs.len(), 8);
+    // 3 instance variables + 2 * 1 variables for the first layer
+    assert_eq!(
+        proof.sumcheck_proofs[0].round_univariate_polynomials.len(),
+        5
+    );
+    assert!(verify(&mut circuit, &proof, &inputs));
+}
+
+#[test]
+fn test_data_parallel_non_power_of_two_instances() {
+    let mut circuit = test_setup();
+    let inputs = inputs(3);
+
+    let proof = prove(&mut circuit, &inputs);
+
+    assert!(verify(&mut circuit, &proof, &inputs));
+}
+
+#[test]
+fn test_data_parallel_rejects_wrong_output() {
+    let mut circuit = test_setup();
+    let inputs = inputs(4);
+
+    let mut proof = prove(&mut circuit, &inputs);
+    proof.circuit_outputs[2][0] += Fq::from(1);
+
+    assert!(!verify(&mut circuit, &proof, &inputs));
+}
+
+#[test]
+fn test_data_parallel_rejects_wrong_inputs() {
+    let mut circuit = test_setup();
+    let inputs = inputs(4);
+
+    let proof = prove(&mut circuit, &inputs);
+
+    let mut wrong_inputs = inputs.clone();
+    wrong_inputs[1][3] += Fq::from(1);
+
+    assert!(!verify(&mut circuit, &proof, &wrong_inputs));
+}
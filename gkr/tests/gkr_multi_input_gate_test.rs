@@ -0,0 +1,124 @@
+use ark_bn254::Fq;
+use gkr::circuits::circuit::Circuit;
+use gkr::circuits::gate::{Gate, MultiInputGate, Operation, MAX_MULTIPLICATION_FAN_IN};
+use gkr::circuits::layer::Layer;
+use gkr::gkr_p::{
+    prove, prove_with_combination, verify, verify_with_combination, ClaimCombination,
+};
+
+// layer 0: out0 = in0 * in1 * in2 * in3 (fan-in 4), out1 = l1[0] + l1[1], out2 = l1[1] + l1[2] + l1[3]
+// layer 1: l1[0] = in0 * in1, l1[1] = in2 + in3, l1[2] = in4 * in5 * in6, l1[3] = in0 + ... + in7
+fn test_setup() -> Circuit<Fq> {
+    let layer0 = Layer::new_with_multi_input_gates(
+        vec![Gate::new(0, 1, 1, Operation::ADD)],
+        vec![
+            MultiInputGate::new(vec![0, 1, 2, 3], 0, Operation::MUL),
+            MultiInputGate::new(vec![1, 2, 3], 2, Operation::ADD),
+        ],
+    );
+
+    let layer1 = Layer::new_with_multi_input_gates(
+        vec![
+            Gate::new(0, 1, 0, Operation::MUL),
+            Gate::new(2, 3, 1, Operation::ADD),
+        ],
+        vec![
+            MultiInputGate::new(vec![4, 5, 6], 2, Operation::MUL),
+            MultiInputGate::new((0..8).collect(), 3, Operation::ADD),
+        ],
+    );
+
+    Circuit::new(vec![layer0, layer1])
+}
+
+#[test]
+fn test_evaluate_multi_input_gates() {
+    let mut circuit = test_setup();
+    let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+
+    let evaluation = circuit.evaluate(inputs);
+
+    // layer 1 => [1 * 2, 3 + 4, 5 * 6 * 7, 1 + ... + 8] = [2, 7, 210, 36]
+    assert_eq!(
+        evaluation.layer_evaluations[1],
+        vec![Fq::from(2), Fq::from(7), Fq::from(210), Fq::from(36)]
+    );
+    // layer 0 => [2 * 7 * 210 * 36, 2 + 7, 7 + 210 + 36]
+    assert_eq!(
+        evaluation.output,
+        vec![Fq::from(105840), Fq::from(9), Fq::from(253)]
+    );
+
+    assert_eq!(circuit.layer_arity(0), 4);
+    assert_eq!(circuit.layer_arity(1), 3);
+}
+
+#[test]
+fn test_gkr_protocol_with_multi_input_gates() {
+    let mut circuit = test_setup();
+    let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+
+    let proof = prove(&mut circuit, &inputs);
+
+    // the fan-in 4 multiplication of layer 0 needs two more evaluations of layer 1, the fan-in 3 one of layer 1 needs one more
+    assert_eq!(proof.multi_input_evaluations[0].len(), 2);
+    assert_eq!(proof.multi_input_evaluations[1].len(), 1);
+    assert!(verify(&mut circuit, proof, &inputs));
+}
+
+#[test]
+fn test_sum_of_64_values_in_one_layer() {
+    let layer = Layer::new_with_multi_input_gates(
+        vec![],
+        vec![MultiInputGate::new((0..64).collect(), 0, Operation::ADD)],
+    );
+    let mut circuit = Circuit::<Fq>::new(vec![layer]);
+    let inputs: Vec<Fq> = (1..=64).map(Fq::from).collect();
+
+    let proof = prove(&mut circuit, &inputs);
+
+    assert_eq!(proof.circuit_output, vec![Fq::from(2080)]);
+    assert_eq!(proof.sumcheck_proofs.len(), 1);
+    assert!(verify(&mut circuit, proof, &inputs));
+}
+
+#[test]
+fn test_gkr_protocol_with_multi_input_gates_rejects_tampered_proof() {
+    let mut circuit = test_setup();
+    let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+
+    let mut proof = prove(&mut circuit, &inputs);
+    proof.multi_input_evaluations[0][1] += Fq::from(1);
+    assert!(!verify(&mut circuit, proof, &inputs));
+
+    let mut wrong_inputs = inputs.clone();
+    wrong_inputs[6] = Fq::from(1);
+
+    let proof = prove(&mut circuit, &inputs);
+    assert!(!verify(&mut circuit, proof, &wrong_inputs));
+}
+
+#[test]
+fn test_multi_input_gates_with_line_restriction() {
+    let mut circuit = test_setup();
+    let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+
+    let proof = prove_with_combination(&mut circuit, &inputs, ClaimCombination::LineRestriction);
+
+    assert!(verify_with_combination(
+        &mut circuit,
+        proof,
+        &inputs,
+        ClaimCombination::LineRestriction
+    ));
+}
+
+#[test]
+#[should_panic(expected = "multiplication fan-in is bounded")]
+fn test_multiplication_fan_in_is_bounded() {
+    MultiInputGate::new(
+        (0..MAX_MULTIPLICATION_FAN_IN + 1).collect(),
+        0,
+        Operation::MUL,
+    );
+}
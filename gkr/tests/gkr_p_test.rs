@@ -53,3 +53,66 @@ pub fn test_gkr_protocol2() {
 
     assert!(verify(&mut circuit, proof, &inputs));
 }
+
+#[test]
+pub fn test_gkr_protocol_irregular_layer_widths() {
+    // 3 outputs computed from 5 inputs, the layer widths are not powers of two
+    let layer0 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::ADD),
+        Gate::new(1, 2, 1, Operation::MUL),
+        Gate::new(2, 2, 2, Operation::ADD),
+    ]);
+    let layer1 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::MUL),
+        Gate::new(2, 3, 1, Operation::ADD),
+        Gate::new(3, 4, 2, Operation::MUL),
+    ]);
+
+    let mut circuit = Circuit::<Fq>::new(vec![layer0, layer1]);
+    let inputs = vec![
+        Fq::from(2),
+        Fq::from(3),
+        Fq::from(4),
+        Fq::from(5),
+        Fq::from(6),
+    ];
+
+    let proof = prove(&mut circuit, &inputs);
+
+    // layer1 = [6, 9, 30] => output = [15, 270, 60]
+    assert_eq!(
+        proof.circuit_output,
+        vec![Fq::from(15), Fq::from(270), Fq::from(60)]
+    );
+    assert!(verify(&mut circuit, proof, &inputs));
+}
+
+#[test]
+pub fn test_gkr_proof_has_one_evaluation_per_layer_without_skip_connections() {
+    let gate1 = Gate::new(0, 1, 0, Operation::MUL);
+    let gate2 = Gate::new(0, 1, 0, Operation::ADD);
+    let gate3 = Gate::new(2, 3, 1, Operation::MUL);
+
+    let layer0 = Layer::new(vec![gate1]);
+    let layer1 = Layer::new(vec![gate2, gate3]);
+
+    let mut circuit = Circuit::<Fq>::new(vec![layer0, layer1]);
+    let inputs = vec![Fq::from(2), Fq::from(3), Fq::from(4), Fq::from(5)];
+
+    let proof = prove(&mut circuit, &inputs);
+
+    assert_eq!(proof.wb_evaluations.len(), 2);
+    assert_eq!(proof.wc_evaluations.len(), 2);
+    assert!(proof.source_evaluations.is_empty());
+
+    // the same evaluations sent in both layouts are rejected
+    let mut both_layouts = proof.clone();
+    both_layouts.source_evaluations = proof
+        .wb_evaluations
+        .iter()
+        .zip(proof.wc_evaluations.iter())
+        .map(|(wb, wc)| (vec![*wb], vec![*wc]))
+        .collect();
+    assert!(!verify(&mut circuit, both_layouts, &inputs));
+    assert!(verify(&mut circuit, proof, &inputs));
+}
@@ -0,0 +1,81 @@
+use ark_bn254::Fq;
+use gkr::circuits::circuit::{pad_to_boolean_hypercube, Circuit};
+use gkr::circuits::gate::{Gate, Operation};
+use gkr::circuits::layer::Layer;
+use gkr::gkr_p::{pad_input_layer, prove_reduce, verify_reduce, Claim};
+use gkr::gkr_sumcheck_dependencies::transcript::Transcript;
+
+fn test_setup() -> Circuit<Fq> {
+    let layer0 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::ADD),
+        Gate::new(2, 3, 1, Operation::MUL),
+    ]);
+    let layer1 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::MUL),
+        Gate::new(2, 3, 1, Operation::ADD),
+        Gate::new(4, 5, 2, Operation::ADD),
+        Gate::new(6, 7, 3, Operation::MUL),
+    ]);
+
+    Circuit::new(vec![layer0, layer1])
+}
+
+#[test]
+fn test_prove_reduce_returns_input_claims() {
+    let mut circuit = test_setup();
+    let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+    let circuit_evaluation = circuit.evaluate(inputs.clone());
+
+    // The output claim comes from the caller, at a point of its choice
+    let output_point = vec![Fq::from(9)];
+    let output_claim = Claim {
+        value: pad_to_boolean_hypercube(&circuit_evaluation.output).evaluate(&output_point),
+        point: output_point,
+    };
+
+    let (proof, prover_input_claims) = prove_reduce(
+        &mut circuit,
+        &circuit_evaluation,
+        &output_claim,
+        &mut Transcript::new(),
+    );
+
+    let verifier_input_claims =
+        verify_reduce(&mut circuit, &output_claim, &proof, &mut Transcript::new())
+            .expect("reduction should verify");
+
+    assert_eq!(prover_input_claims, verifier_input_claims);
+    assert_eq!(verifier_input_claims.len(), 2);
+
+    let input_polynomial = pad_input_layer(&circuit, &inputs);
+    for claim in verifier_input_claims {
+        assert_eq!(input_polynomial.evaluate(&claim.point), claim.value);
+    }
+}
+
+#[test]
+fn test_verify_reduce_rejects_wrong_output_claim() {
+    let mut circuit = test_setup();
+    let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+    let circuit_evaluation = circuit.evaluate(inputs);
+
+    let output_point = vec![Fq::from(4)];
+    let output_claim = Claim {
+        value: pad_to_boolean_hypercube(&circuit_evaluation.output).evaluate(&output_point),
+        point: output_point,
+    };
+
+    let (proof, _) = prove_reduce(
+        &mut circuit,
+        &circuit_evaluation,
+        &output_claim,
+        &mut Transcript::new(),
+    );
+
+    let wrong_claim = Claim {
+        point: output_claim.point.clone(),
+        value: output_claim.value + Fq::from(1),
+    };
+
+    assert!(verify_reduce(&mut circuit, &wrong_claim, &proof, &mut Transcript::new()).is_none());
+}
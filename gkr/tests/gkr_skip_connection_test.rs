@@ -0,0 +1,129 @@
+use ark_bn254::Fq;
+use gkr::circuits::circuit::Circuit;
+use gkr::circuits::gate::{Gate, Operation};
+use gkr::circuits::layer::Layer;
+use gkr::gkr_p::{pad_input_layer, prove, prove_reduce, verify, verify_reduce, Claim};
+use gkr::gkr_sumcheck_dependencies::transcript::Transcript;
+
+// Layer 3 is the input layer: the gates of layer 0 read from layers 1, 2 and 3, and layer 1 reads from layers 2 and 3
+fn test_setup() -> Circuit<Fq> {
+    let layer0 = Layer::new(vec![
+        Gate::new_with_layers(0, 1, 0, 2, 0, Operation::ADD),
+        Gate::new_with_layers(1, 1, 5, 3, 1, Operation::MUL),
+    ]);
+
+    let layer1 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::MUL),
+        Gate::new_with_layers(1, 2, 3, 3, 1, Operation::ADD),
+    ]);
+
+    let layer2 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::MUL),
+        Gate::new(2, 3, 1, Operation::ADD),
+    ]);
+
+    Circuit::new(vec![layer0, layer1, layer2])
+}
+
+#[test]
+fn test_evaluate_with_skip_connections() {
+    let mut circuit = test_setup();
+    let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+
+    let evaluation = circuit.evaluate(inputs);
+
+    // layer 2 => [1 * 2, 3 + 4], layer 1 => [2 * 7, 7 + 4], layer 0 => [14 + 2, 11 * 6]
+    assert_eq!(
+        evaluation.layer_evaluations[2],
+        vec![Fq::from(2), Fq::from(7)]
+    );
+    assert_eq!(
+        evaluation.layer_evaluations[1],
+        vec![Fq::from(14), Fq::from(11)]
+    );
+    assert_eq!(evaluation.output, vec![Fq::from(16), Fq::from(66)]);
+
+    assert!(circuit.has_skip_connections());
+    assert_eq!(circuit.source_layers(0), vec![1, 2, 3]);
+    assert_eq!(circuit.input_num_vars(), 3);
+    assert_eq!(circuit.layer_input_num_vars(0), 3);
+}
+
+#[test]
+fn test_gkr_protocol_with_skip_connections() {
+    let mut circuit = test_setup();
+    let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+
+    let proof = prove(&mut circuit, &inputs);
+
+    // layer 0 reads layer 1 as left inputs and layers 2 and 3 as right inputs,
+    // so the evaluations are sent per source layer instead of one per layer
+    assert!(proof.wb_evaluations.is_empty());
+    assert_eq!(proof.source_evaluations[0].0.len(), 1);
+    assert_eq!(proof.source_evaluations[0].1.len(), 2);
+    assert!(verify(&mut circuit, proof, &inputs));
+}
+
+#[test]
+fn test_gkr_protocol_with_skip_connections_rejects_tampered_proof() {
+    let mut circuit = test_setup();
+    let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+
+    let mut proof = prove(&mut circuit, &inputs);
+    proof.source_evaluations[0].1[1] += Fq::from(1);
+    assert!(!verify(&mut circuit, proof, &inputs));
+
+    // the value read from input 5 by the output layer only reaches the verifier through a skip connection
+    let mut wrong_inputs = inputs.clone();
+    wrong_inputs[5] = Fq::from(7);
+
+    let proof = prove(&mut circuit, &inputs);
+    assert!(!verify(&mut circuit, proof, &wrong_inputs));
+}
+
+#[test]
+fn test_reduce_with_skip_connections_returns_every_input_claim() {
+    let mut circuit = test_setup();
+    let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+    let circuit_evaluation = circuit.evaluate(inputs.clone());
+
+    let output_claim = Claim {
+        point: vec![Fq::from(3)],
+        value: circuit_evaluation.output[0] * Fq::from(-2)
+            + circuit_evaluation.output[1] * Fq::from(3),
+    };
+
+    let mut prover_transcript = Transcript::new();
+    let (reduce_proof, prover_input_claims) = prove_reduce(
+        &mut circuit,
+        &circuit_evaluation,
+        &output_claim,
+        &mut prover_transcript,
+    );
+
+    let mut verifier_transcript = Transcript::new();
+    let verifier_input_claims = verify_reduce(
+        &mut circuit,
+        &output_claim,
+        &reduce_proof,
+        &mut verifier_transcript,
+    )
+    .unwrap();
+
+    // the input layer is read by layer 0 (right), layer 1 (right) and layer 2 (left and right)
+    assert_eq!(verifier_input_claims.len(), 4);
+    assert_eq!(prover_input_claims, verifier_input_claims);
+
+    let input_polynomial = pad_input_layer(&circuit, &inputs);
+    assert!(verifier_input_claims
+        .iter()
+        .all(|claim| input_polynomial.evaluate(&claim.point) == claim.value));
+}
+
+#[test]
+fn test_input_num_vars_of_empty_circuit() {
+    let circuit = Circuit::<Fq>::new(vec![]);
+
+    assert_eq!(circuit.input_num_vars(), 1);
+    assert_eq!(circuit.layer_num_vars(0), 1);
+}
@@ -0,0 +1,79 @@
+use ark_bn254::Fq;
+use gkr::evaluation::MultilinearPolynomialEV;
+use gkr::gkr_sumcheck_dependencies::transcript::Transcript;
+use gkr::grand_product::{prove, prove_batch, verify, verify_batch};
+
+fn leaves(values: &[u64]) -> MultilinearPolynomialEV<Fq> {
+    MultilinearPolynomialEV::new(
+        &values
+            .iter()
+            .map(|value| Fq::from(*value))
+            .collect::<Vec<Fq>>(),
+    )
+}
+
+#[test]
+fn test_grand_product() {
+    let leaves = leaves(&[3, 5, 2, 7, 1, 4, 6, 9]);
+
+    let (proof, prover_claim) = prove(&leaves, &mut Transcript::new());
+    assert_eq!(proof.product, Fq::from(3 * 5 * 2 * 7 * 4 * 6 * 9));
+
+    let claim = verify(&proof, 3, &mut Transcript::new()).unwrap();
+    assert_eq!(claim, prover_claim);
+    assert_eq!(leaves.evaluate(&claim.point), claim.value);
+}
+
+#[test]
+fn test_grand_product_single_leaf_layer() {
+    let leaves = leaves(&[11, 13]);
+
+    let (proof, _) = prove(&leaves, &mut Transcript::new());
+    let claim = verify(&proof, 1, &mut Transcript::new()).unwrap();
+
+    assert_eq!(proof.product, Fq::from(143));
+    assert_eq!(leaves.evaluate(&claim.point), claim.value);
+}
+
+#[test]
+fn test_grand_product_rejects_wrong_products() {
+    let leaves = leaves(&[3, 5, 2, 7]);
+
+    let (mut proof, _) = prove(&leaves, &mut Transcript::new());
+    proof.product += Fq::from(1);
+    assert!(verify(&proof, 2, &mut Transcript::new()).is_none());
+
+    let (mut proof, _) = prove(&leaves, &mut Transcript::new());
+    proof.layer_proofs[1].right_evaluations[0] += Fq::from(1);
+    assert!(verify(&proof, 2, &mut Transcript::new()).is_none());
+
+    let (proof, _) = prove(&leaves, &mut Transcript::new());
+    assert!(verify(&proof, 3, &mut Transcript::new()).is_none());
+}
+
+#[test]
+fn test_batch_grand_product() {
+    let trees = [
+        leaves(&[3, 5, 2, 7]),
+        leaves(&[1, 1, 2, 2]),
+        leaves(&[8, 6, 4, 2]),
+    ];
+
+    let (proof, prover_claims) = prove_batch(&trees, &mut Transcript::new());
+    assert_eq!(
+        proof.products,
+        vec![Fq::from(210), Fq::from(4), Fq::from(384)]
+    );
+
+    let claims = verify_batch(&proof, 2, &mut Transcript::new()).unwrap();
+    assert_eq!(claims, prover_claims);
+
+    for (tree, claim) in trees.iter().zip(claims.iter()) {
+        assert_eq!(claim.point, claims[0].point);
+        assert_eq!(tree.evaluate(&claim.point), claim.value);
+    }
+
+    let (mut proof, _) = prove_batch(&trees, &mut Transcript::new());
+    proof.products.swap(0, 2);
+    assert!(verify_batch(&proof, 2, &mut Transcript::new()).is_none());
+}
@@ -0,0 +1,176 @@
+use ark_bn254::Fr;
+use ark_ff::{Field, MontFp, Zero};
+use gkr::circuits::hashes::{
+    hash_circuit, hash_witness, merkle_path_circuit, merkle_path_witness, merkle_root,
+    mimc_circuit, mimc_encrypt, mimc_hash, mimc_witness, poseidon_hash, poseidon_permutation,
+    poseidon_permutation_circuit, poseidon_permutation_witness, AlgebraicHash, MimcParams,
+    PoseidonParams,
+};
+use gkr::gkr_p::{prove, verify};
+
+#[test]
+fn test_mimc_reference_implementation() {
+    let params = MimcParams::<Fr>::default();
+    assert_eq!(params.round_constants.len(), 91);
+    assert!(params.round_constants[0].is_zero());
+
+    let (x, key) = (Fr::from(7), Fr::from(11));
+    let encrypted = mimc_encrypt(x, key, &params);
+
+    // 91 rounds of (x + k + c_i)^7, then + k
+    let expected = params
+        .round_constants
+        .iter()
+        .fold(x, |x, c| (x + key + c).pow([7]))
+        + key;
+    assert_eq!(encrypted, expected);
+
+    // Miyaguchi-Preneel: h_1 = 0 + x + E_0(x)
+    assert_eq!(
+        mimc_hash(&[x], &params),
+        x + mimc_encrypt(x, Fr::zero(), &params)
+    );
+    assert_ne!(mimc_hash(&[x, key], &params), mimc_hash(&[key, x], &params));
+}
+
+#[test]
+fn test_poseidon_reference_implementation() {
+    let params = PoseidonParams::<Fr>::default();
+    assert_eq!(params.round_constants.len(), (8 + 57) * 3);
+    assert!(params.is_full_round(3) && !params.is_full_round(4));
+    assert!(!params.is_full_round(60) && params.is_full_round(61));
+
+    let state = [Fr::from(0), Fr::from(1), Fr::from(2)];
+    let permuted = poseidon_permutation(state, &params);
+    assert_ne!(permuted, state);
+
+    assert_eq!(
+        poseidon_hash(&[Fr::from(1), Fr::from(2)], &params),
+        permuted[0]
+    );
+    assert_ne!(
+        poseidon_hash(&[Fr::from(1), Fr::from(2), Fr::from(3)], &params),
+        permuted[0]
+    );
+}
+
+#[test]
+fn test_poseidon_matches_circomlib() {
+    let params = PoseidonParams::<Fr>::default();
+
+    // the first round constant and MDS entry of circomlib's parameters for 2 inputs
+    let first_constant: Fr =
+        MontFp!("6745197990210204598374042828761989596302876299545964402857411729872131034734");
+    let first_mds_entry: Fr =
+        MontFp!("7511745149465107256748700652201246547602992235352608707588321460060273774987");
+    assert_eq!(params.round_constants[0], first_constant);
+    assert_eq!(params.mds[0][0], first_mds_entry);
+
+    // circomlibjs: poseidon([1, 2])
+    let expected: Fr =
+        MontFp!("7853200120776062878684798364095072458815029376092732009249414926327459813530");
+    assert_eq!(
+        poseidon_hash(&[Fr::from(1), Fr::from(2)], &params),
+        expected
+    );
+}
+
+#[test]
+fn test_mimc_circuit_matches_reference() {
+    let params = MimcParams::<Fr>::default();
+    let (x, key) = (Fr::from(1234), Fr::from(5678));
+
+    let mut circuit = mimc_circuit(&params).circuit::<Fr>();
+    let output = circuit.evaluate(mimc_witness(x, key, &params)).output;
+
+    assert_eq!(output, vec![mimc_encrypt(x, key, &params)]);
+}
+
+#[test]
+fn test_poseidon_permutation_circuit_matches_reference() {
+    let params = PoseidonParams::<Fr>::default();
+    let state = [Fr::from(31), Fr::from(415), Fr::from(9265)];
+
+    let mut circuit = poseidon_permutation_circuit(&params).circuit::<Fr>();
+    let output = circuit
+        .evaluate(poseidon_permutation_witness(state, &params))
+        .output;
+
+    assert_eq!(output, poseidon_permutation(state, &params).to_vec());
+}
+
+#[test]
+fn test_hash_circuits_match_reference() {
+    let inputs: Vec<Fr> = (1..=5).map(Fr::from).collect();
+
+    for hash in [
+        AlgebraicHash::Mimc(MimcParams::default()),
+        AlgebraicHash::Poseidon(PoseidonParams::default()),
+    ] {
+        let mut circuit = hash_circuit(&hash, inputs.len()).circuit::<Fr>();
+        let output = circuit.evaluate(hash_witness(&hash, &inputs)).output;
+
+        assert_eq!(output, vec![hash.hash(&inputs)]);
+    }
+}
+
+#[test]
+fn test_merkle_path_circuits_match_reference() {
+    let leaves: Vec<Fr> = (10..14).map(Fr::from).collect();
+
+    for hash in [
+        AlgebraicHash::Mimc(MimcParams::default()),
+        AlgebraicHash::Poseidon(PoseidonParams::default()),
+    ] {
+        let nodes = [
+            hash.hash(&[leaves[0], leaves[1]]),
+            hash.hash(&[leaves[2], leaves[3]]),
+        ];
+        let root = hash.hash(&nodes);
+
+        // leaf 2 is the left child of nodes[1], which is the right child of the root
+        let path = [(leaves[3], false), (nodes[0], true)];
+        assert_eq!(merkle_root(&hash, leaves[2], &path), root);
+
+        let mut circuit = merkle_path_circuit(&hash, 2).circuit::<Fr>();
+        let output = circuit
+            .evaluate(merkle_path_witness(&hash, leaves[2], &path))
+            .output;
+        assert_eq!(output, vec![root]);
+
+        // the wrong leaf or the wrong direction gives another root
+        let output = circuit
+            .evaluate(merkle_path_witness(&hash, leaves[3], &path))
+            .output;
+        assert_ne!(output, vec![root]);
+
+        let wrong_path = [(leaves[3], true), (nodes[0], true)];
+        let output = circuit
+            .evaluate(merkle_path_witness(&hash, leaves[2], &wrong_path))
+            .output;
+        assert_ne!(output, vec![root]);
+    }
+}
+
+#[test]
+fn test_gkr_protocol_with_hash_circuits() {
+    // fewer rounds than the BN254 parameters, to keep the proof small
+    for hash in [
+        AlgebraicHash::Mimc(MimcParams::<Fr>::new(4)),
+        AlgebraicHash::Poseidon(PoseidonParams::<Fr>::new(2, 2)),
+    ] {
+        let leaf = Fr::from(3);
+        let path = [(Fr::from(4), true)];
+
+        let mut circuit = merkle_path_circuit(&hash, 1).circuit::<Fr>();
+        let witness = merkle_path_witness(&hash, leaf, &path);
+
+        let proof = prove(&mut circuit, &witness);
+        assert_eq!(proof.circuit_output, vec![merkle_root(&hash, leaf, &path)]);
+        assert!(verify(&mut circuit, proof, &witness));
+
+        let proof = prove(&mut circuit, &witness);
+        let wrong_witness = merkle_path_witness(&hash, Fr::from(5), &path);
+        assert!(!verify(&mut circuit, proof, &wrong_witness));
+    }
+}
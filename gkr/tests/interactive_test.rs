@@ -0,0 +1,213 @@
+use ark_bn254::Fq;
+use gkr::circuits::circuit::Circuit;
+use gkr::circuits::gate::{CustomGateInstance, Gate, Operation, PowerGate};
+use gkr::circuits::layer::Layer;
+use gkr::evaluation::MultilinearPolynomialEV;
+use gkr::gkr_sumcheck_dependencies::poly_sum::SumPolynomial;
+use gkr::interactive::{
+    prove_gkr, prove_sumcheck, verify_gkr, verify_sumcheck, InMemoryChannel, OsRandomness,
+    TcpChannel,
+};
+use gkr::product_poly::ProductPolynomial;
+use std::io::ErrorKind;
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread;
+
+fn circuit() -> Circuit<Fq> {
+    let layer0 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::ADD),
+        Gate::new(1, 2, 1, Operation::MUL),
+        Gate::new(2, 2, 2, Operation::ADD),
+    ]);
+    let layer1 = Layer::new(vec![
+        Gate::new(0, 1, 0, Operation::MUL),
+        Gate::new(2, 3, 1, Operation::ADD),
+        Gate::new(3, 4, 2, Operation::MUL),
+    ]);
+
+    Circuit::new(vec![layer0, layer1])
+}
+
+fn inputs() -> Vec<Fq> {
+    [2, 3, 4, 5, 6].map(Fq::from).to_vec()
+}
+
+// f(x) * g(x) + h(x) over 3 variables
+fn sum_polynomial() -> SumPolynomial<Fq> {
+    let polynomial = |values: [u64; 8]| MultilinearPolynomialEV::new(&values.map(Fq::from));
+
+    SumPolynomial::new(vec![
+        ProductPolynomial::new(vec![
+            polynomial([1, 2, 3, 4, 5, 6, 7, 8]),
+            polynomial([2, 0, 1, 3, 1, 1, 2, 0]),
+        ]),
+        ProductPolynomial::new(vec![polynomial([9, 8, 7, 6, 5, 4, 3, 2])]),
+    ])
+}
+
+#[test]
+fn test_interactive_sumcheck() {
+    let polynomial = sum_polynomial();
+    // 2 + 3 + 12 + 5 + 6 + 14 for f * g, 44 for h
+    let claimed_sum = Fq::from(42 + 44);
+    let (mut prover_channel, mut verifier_channel) = InMemoryChannel::pair();
+
+    let prover = thread::spawn(move || prove_sumcheck(&mut prover_channel, sum_polynomial()));
+
+    let mut randomness = OsRandomness::new();
+    let verification =
+        verify_sumcheck(&mut verifier_channel, &mut randomness, claimed_sum, 3, 2).unwrap();
+    drop(verifier_channel);
+    let prover_challenges = prover.join().unwrap().unwrap();
+
+    assert!(verification.is_proof_valid);
+    assert_eq!(prover_challenges, verification.random_challenges);
+    assert_eq!(
+        polynomial.evaluate(&verification.random_challenges),
+        verification.last_claimed_sum
+    );
+}
+
+#[test]
+fn test_interactive_sumcheck_rejects_wrong_sum() {
+    let (mut prover_channel, mut verifier_channel) = InMemoryChannel::pair();
+    let prover = thread::spawn(move || prove_sumcheck(&mut prover_channel, sum_polynomial()));
+
+    let mut randomness = OsRandomness::new();
+    let verification =
+        verify_sumcheck(&mut verifier_channel, &mut randomness, Fq::from(88), 3, 2).unwrap();
+    assert!(!verification.is_proof_valid);
+
+    // the verifier tells the prover, which fails while the channel is still open
+    let error = prover.join().unwrap().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::ConnectionAborted);
+    drop(verifier_channel);
+}
+
+#[test]
+fn test_interactive_gkr_in_memory() {
+    let (mut prover_channel, mut verifier_channel) = InMemoryChannel::pair();
+    let prover = thread::spawn(move || prove_gkr(&mut prover_channel, &mut circuit(), &inputs()));
+
+    let mut randomness = OsRandomness::new();
+    let output = verify_gkr(
+        &mut verifier_channel,
+        &mut randomness,
+        &mut circuit(),
+        &inputs(),
+    )
+    .unwrap();
+    drop(verifier_channel);
+    prover.join().unwrap().unwrap();
+
+    assert_eq!(output, Some(circuit().evaluate(inputs()).output));
+}
+
+#[test]
+fn test_interactive_gkr_over_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let prover = thread::spawn(move || {
+        let mut channel = TcpChannel::connect(address)?;
+        prove_gkr(&mut channel, &mut circuit(), &inputs())
+    });
+
+    let mut verifier_channel = TcpChannel::accept(&listener).unwrap();
+    let mut randomness = OsRandomness::new();
+    let output = verify_gkr(
+        &mut verifier_channel,
+        &mut randomness,
+        &mut circuit(),
+        &inputs(),
+    )
+    .unwrap();
+    drop(verifier_channel);
+    prover.join().unwrap().unwrap();
+
+    assert_eq!(output, Some(circuit().evaluate(inputs()).output));
+}
+
+#[test]
+fn test_interactive_gkr_rejects_other_inputs() {
+    let (mut prover_channel, mut verifier_channel) = InMemoryChannel::pair();
+    let prover = thread::spawn(move || prove_gkr(&mut prover_channel, &mut circuit(), &inputs()));
+
+    let mut other_inputs = inputs();
+    other_inputs[4] += Fq::from(1);
+
+    let mut randomness = OsRandomness::new();
+    let output = verify_gkr(
+        &mut verifier_channel,
+        &mut randomness,
+        &mut circuit(),
+        &other_inputs,
+    )
+    .unwrap();
+    assert_eq!(output, None);
+
+    let error = prover.join().unwrap().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::ConnectionAborted);
+    drop(verifier_channel);
+}
+
+#[test]
+fn test_interactive_gkr_over_tcp_tells_the_prover_it_was_rejected() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let prover = thread::spawn(move || {
+        let mut channel = TcpChannel::connect(address)?;
+        prove_gkr(&mut channel, &mut circuit(), &inputs())
+    });
+
+    let mut other_inputs = inputs();
+    other_inputs[0] += Fq::from(1);
+
+    // the connection stays open until the prover is done: it must not wait for a message that never comes
+    let mut verifier_channel = TcpChannel::accept(&listener).unwrap();
+    let output = verify_gkr(
+        &mut verifier_channel,
+        &mut OsRandomness::default(),
+        &mut circuit(),
+        &other_inputs,
+    )
+    .unwrap();
+    assert_eq!(output, None);
+
+    let error = prover.join().unwrap().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::ConnectionAborted);
+    drop(verifier_channel);
+}
+
+#[test]
+fn test_interactive_gkr_with_custom_gates() {
+    // out0 = in0 + in1, out1 = in2^5: the sumcheck of the layer has degree 6
+    let circuit = || {
+        let mut layer = Layer::new(vec![Gate::new(0, 1, 0, Operation::ADD)]);
+        layer.add_custom_gate(CustomGateInstance::new(
+            Arc::new(PowerGate { exponent: 5 }),
+            vec![2],
+            1,
+        ));
+        Circuit::<Fq>::new(vec![layer])
+    };
+    let inputs = || [3, 4, 2].map(Fq::from).to_vec();
+
+    let (mut prover_channel, mut verifier_channel) = InMemoryChannel::pair();
+    let prover = thread::spawn(move || prove_gkr(&mut prover_channel, &mut circuit(), &inputs()));
+
+    let mut randomness = OsRandomness::new();
+    let output = verify_gkr(
+        &mut verifier_channel,
+        &mut randomness,
+        &mut circuit(),
+        &inputs(),
+    )
+    .unwrap();
+    drop(verifier_channel);
+    prover.join().unwrap().unwrap();
+
+    assert_eq!(output, Some(vec![Fq::from(7), Fq::from(32)]));
+}
@@ -0,0 +1,173 @@
+use ark_bn254::Fq;
+use gkr::evaluation::MultilinearPolynomialEV;
+use gkr::gkr_sumcheck_dependencies::transcript::Transcript;
+use gkr::ligero::{commit, encode_row, open, verify, LigeroParams};
+
+fn test_setup() -> MultilinearPolynomialEV<Fq> {
+    let evaluations: Vec<Fq> = (0..32).map(|i| Fq::from(i * i + 3)).collect();
+    MultilinearPolynomialEV::new(&evaluations)
+}
+
+#[test]
+fn test_encode_row() {
+    // row [1, 2] => 1 + 2x evaluated at 0, 1, 2, 3
+    let encoded = encode_row(&[Fq::from(1), Fq::from(2)], 4);
+
+    assert_eq!(
+        encoded,
+        vec![Fq::from(1), Fq::from(3), Fq::from(5), Fq::from(7)]
+    );
+}
+
+#[test]
+fn test_commit_open_verify() {
+    let polynomial = test_setup();
+    let params = LigeroParams::default();
+    let point = vec![
+        Fq::from(3),
+        Fq::from(7),
+        Fq::from(11),
+        Fq::from(2),
+        Fq::from(5),
+    ];
+
+    let (commitment, prover_data) = commit(&polynomial, &params);
+    let (evaluation, proof) = open(&commitment, &prover_data, &point, &mut Transcript::new());
+
+    assert_eq!(evaluation, polynomial.evaluate(&point));
+    assert_eq!(proof.column_openings.len(), params.num_column_queries);
+    assert!(verify(
+        &commitment,
+        &params,
+        &point,
+        evaluation,
+        &proof,
+        &mut Transcript::new()
+    ));
+}
+
+#[test]
+fn test_verify_rejects_wrong_evaluation() {
+    let polynomial = test_setup();
+    let params = LigeroParams {
+        rate_inverse: 2,
+        num_column_queries: 8,
+    };
+    let point = vec![
+        Fq::from(1),
+        Fq::from(9),
+        Fq::from(4),
+        Fq::from(6),
+        Fq::from(8),
+    ];
+
+    let (commitment, prover_data) = commit(&polynomial, &params);
+    let (evaluation, proof) = open(&commitment, &prover_data, &point, &mut Transcript::new());
+
+    assert_eq!(proof.column_openings.len(), 8);
+    assert!(!verify(
+        &commitment,
+        &params,
+        &point,
+        evaluation + Fq::from(1),
+        &proof,
+        &mut Transcript::new()
+    ));
+}
+
+#[test]
+fn test_verify_rejects_tampered_column() {
+    let polynomial = test_setup();
+    let point = vec![
+        Fq::from(2),
+        Fq::from(2),
+        Fq::from(3),
+        Fq::from(3),
+        Fq::from(4),
+    ];
+
+    let (commitment, prover_data) = commit(&polynomial, &LigeroParams::default());
+    let (evaluation, mut proof) = open(&commitment, &prover_data, &point, &mut Transcript::new());
+
+    proof.column_openings[0].values[0] += Fq::from(1);
+
+    assert!(!verify(
+        &commitment,
+        &LigeroParams::default(),
+        &point,
+        evaluation,
+        &proof,
+        &mut Transcript::new()
+    ));
+}
+
+#[test]
+fn test_verify_uses_its_own_params() {
+    let polynomial = test_setup();
+    let point = vec![
+        Fq::from(5),
+        Fq::from(1),
+        Fq::from(7),
+        Fq::from(3),
+        Fq::from(9),
+    ];
+    let weak_params = LigeroParams {
+        rate_inverse: 2,
+        num_column_queries: 0,
+    };
+
+    // A prover that commits with no column queries cannot convince a verifier expecting the defaults
+    let (commitment, prover_data) = commit(&polynomial, &weak_params);
+    let (evaluation, proof) = open(&commitment, &prover_data, &point, &mut Transcript::new());
+
+    assert!(proof.column_openings.is_empty());
+    assert!(!verify(
+        &commitment,
+        &LigeroParams::default(),
+        &point,
+        evaluation + Fq::from(1),
+        &proof,
+        &mut Transcript::new()
+    ));
+}
+
+#[test]
+fn test_verify_rejects_malformed_commitment_sizes() {
+    let polynomial = test_setup();
+    let params = LigeroParams::default();
+    let point = vec![
+        Fq::from(3),
+        Fq::from(7),
+        Fq::from(11),
+        Fq::from(2),
+        Fq::from(5),
+    ];
+
+    let (commitment, prover_data) = commit(&polynomial, &params);
+    let (evaluation, proof) = open(&commitment, &prover_data, &point, &mut Transcript::new());
+
+    for (num_rows, num_columns) in [(0, commitment.num_columns), (3, commitment.num_columns)] {
+        let mut bad_commitment = commitment.clone();
+        bad_commitment.num_rows = num_rows;
+        bad_commitment.num_columns = num_columns;
+        assert!(!verify(
+            &bad_commitment,
+            &params,
+            &point,
+            evaluation,
+            &proof,
+            &mut Transcript::new()
+        ));
+    }
+
+    let mut deep_proof = proof.clone();
+    deep_proof.merkle_proof.depth = usize::BITS as usize - 1;
+    assert!(!verify(
+        &commitment,
+        &params,
+        &point,
+        evaluation,
+        &deep_proof,
+        &mut Transcript::new()
+    ));
+}
@@ -0,0 +1,151 @@
+use ark_bn254::Fq;
+use gkr::evaluation::MultilinearPolynomialEV;
+use gkr::gkr_sumcheck_dependencies::transcript::Transcript;
+use gkr::logup::{multiplicities, prove, verify};
+use gkr::merkle_tree::MerkleTree;
+use sha3::Keccak256;
+
+// A 4 bits range check: the table is 0..16
+fn range_table() -> Vec<Fq> {
+    (0..16).map(Fq::from).collect()
+}
+
+fn witness() -> Vec<Fq> {
+    [3, 15, 0, 3, 7, 3, 9, 15].map(Fq::from).to_vec()
+}
+
+// The roots of Merkle trees over the witness, the table and the multiplicities
+fn commitments(columns: [&MultilinearPolynomialEV<Fq>; 3]) -> Vec<Vec<u8>> {
+    columns
+        .iter()
+        .map(|column| MerkleTree::<Keccak256>::from_field_elements(&column.evaluated_values).root())
+        .collect()
+}
+
+#[test]
+fn test_multiplicities() {
+    let multiplicities = multiplicities(&witness(), &range_table());
+
+    assert_eq!(multiplicities[3], Fq::from(3));
+    assert_eq!(multiplicities[15], Fq::from(2));
+    assert_eq!(multiplicities[1], Fq::from(0));
+    assert_eq!(multiplicities.iter().sum::<Fq>(), Fq::from(8));
+}
+
+#[test]
+fn test_logup_protocol() {
+    let witness = MultilinearPolynomialEV::new(&witness());
+    let table = MultilinearPolynomialEV::new(&range_table());
+    let multiplicities = MultilinearPolynomialEV::new(&multiplicities(
+        &witness.evaluated_values,
+        &table.evaluated_values,
+    ));
+
+    let commitments = commitments([&witness, &table, &multiplicities]);
+
+    let (proof, prover_claims) = prove(
+        &witness,
+        &table,
+        &multiplicities,
+        &commitments,
+        &mut Transcript::new(),
+    );
+    let claims = verify(&proof, 3, 4, &commitments, &mut Transcript::new()).unwrap();
+
+    assert_eq!(claims, prover_claims);
+    assert_eq!(
+        witness.evaluate(&claims.witness.point),
+        claims.witness.value
+    );
+    assert_eq!(table.evaluate(&claims.table.point), claims.table.value);
+    assert_eq!(
+        multiplicities.evaluate(&claims.multiplicities.point),
+        claims.multiplicities.value
+    );
+}
+
+#[test]
+fn test_logup_protocol_rejects_values_outside_the_table() {
+    let table = MultilinearPolynomialEV::new(&range_table());
+    let multiplicities =
+        MultilinearPolynomialEV::new(&multiplicities(&witness(), &table.evaluated_values));
+
+    // 16 is not in the table, the multiplicities are the ones of the valid witness
+    let mut values = witness();
+    values[1] = Fq::from(16);
+    let witness = MultilinearPolynomialEV::new(&values);
+
+    let (proof, _) = prove(
+        &witness,
+        &table,
+        &multiplicities,
+        &[],
+        &mut Transcript::new(),
+    );
+    assert!(verify(&proof, 3, 4, &[], &mut Transcript::new()).is_none());
+}
+
+#[test]
+fn test_logup_protocol_rejects_tampered_proofs() {
+    let witness = MultilinearPolynomialEV::new(&witness());
+    let table = MultilinearPolynomialEV::new(&range_table());
+    let multiplicities = MultilinearPolynomialEV::new(&multiplicities(
+        &witness.evaluated_values,
+        &table.evaluated_values,
+    ));
+
+    let (mut proof, _) = prove(
+        &witness,
+        &table,
+        &multiplicities,
+        &[],
+        &mut Transcript::new(),
+    );
+    proof.witness_sum.layer_proofs[1].denominator_evaluations[0] += Fq::from(1);
+    assert!(verify(&proof, 3, 4, &[], &mut Transcript::new()).is_none());
+
+    let (mut proof, _) = prove(
+        &witness,
+        &table,
+        &multiplicities,
+        &[],
+        &mut Transcript::new(),
+    );
+    proof.table_sum.numerator += Fq::from(1);
+    assert!(verify(&proof, 3, 4, &[], &mut Transcript::new()).is_none());
+
+    // the claimed sizes are part of the statement
+    let (proof, _) = prove(
+        &witness,
+        &table,
+        &multiplicities,
+        &[],
+        &mut Transcript::new(),
+    );
+    assert!(verify(&proof, 4, 4, &[], &mut Transcript::new()).is_none());
+}
+
+#[test]
+fn test_logup_protocol_binds_the_commitments() {
+    let witness = MultilinearPolynomialEV::new(&witness());
+    let table = MultilinearPolynomialEV::new(&range_table());
+    let multiplicities = MultilinearPolynomialEV::new(&multiplicities(
+        &witness.evaluated_values,
+        &table.evaluated_values,
+    ));
+    let commitments = commitments([&witness, &table, &multiplicities]);
+
+    let (proof, _) = prove(
+        &witness,
+        &table,
+        &multiplicities,
+        &commitments,
+        &mut Transcript::new(),
+    );
+
+    // alpha depends on the commitments: the proof doesn't verify against other ones
+    let mut other_commitments = commitments.clone();
+    other_commitments[2][0] ^= 1;
+    assert!(verify(&proof, 3, 4, &other_commitments, &mut Transcript::new()).is_none());
+    assert!(verify(&proof, 3, 4, &[], &mut Transcript::new()).is_none());
+}
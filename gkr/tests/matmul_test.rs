@@ -0,0 +1,116 @@
+use ark_bn254::Fq;
+use gkr::gkr_sumcheck_dependencies::transcript::Transcript;
+use gkr::matmul::{matrix_mle, prove, prove_at_point, verify, verify_at_point, MatmulDimensions};
+
+fn to_field(values: &[u64]) -> Vec<Fq> {
+    values.iter().map(|value| Fq::from(*value)).collect()
+}
+
+fn multiply(a: &[Fq], b: &[Fq], dimensions: &MatmulDimensions) -> Vec<Fq> {
+    let mut c = vec![Fq::from(0); dimensions.rows * dimensions.columns];
+
+    for i in 0..dimensions.rows {
+        for j in 0..dimensions.columns {
+            for k in 0..dimensions.inner {
+                c[i * dimensions.columns + j] +=
+                    a[i * dimensions.inner + k] * b[k * dimensions.columns + j];
+            }
+        }
+    }
+
+    c
+}
+
+// A: 3 x 5, B: 5 x 2, padded to 4 x 8 and 8 x 2
+fn test_setup() -> (Vec<Fq>, Vec<Fq>, Vec<Fq>, MatmulDimensions) {
+    let dimensions = MatmulDimensions::new(3, 5, 2);
+    let a: Vec<Fq> = (1..=15).map(Fq::from).collect();
+    let b: Vec<Fq> = (3..=12).map(Fq::from).collect();
+    let c = multiply(&a, &b, &dimensions);
+
+    (a, b, c, dimensions)
+}
+
+#[test]
+fn test_matrix_mle() {
+    // [[1, 2, 3]] is padded to [[1, 2, 3, 0], [0, 0, 0, 0]]
+    let mle = matrix_mle(&to_field(&[1, 2, 3]), 1, 3);
+
+    assert_eq!(mle.evaluated_values, to_field(&[1, 2, 3, 0, 0, 0, 0, 0]));
+}
+
+#[test]
+fn test_matmul_sumcheck() {
+    let (a, b, c, dimensions) = test_setup();
+
+    let (proof, prover_claims) = prove(&a, &b, &c, &dimensions, &mut Transcript::new());
+    // one round per variable of the inner dimension
+    assert_eq!(proof.sumcheck_proof.round_univariate_polynomials.len(), 3);
+
+    let claims = verify(&proof, &c, &dimensions, &mut Transcript::new()).unwrap();
+    assert_eq!(claims, prover_claims);
+
+    // the claims are left to the caller, eg: to be checked against the matrices or their commitments
+    assert_eq!(
+        matrix_mle(&a, 3, 5).evaluate(&claims.a.point),
+        claims.a.value
+    );
+    assert_eq!(
+        matrix_mle(&b, 5, 2).evaluate(&claims.b.point),
+        claims.b.value
+    );
+    assert_eq!(
+        matrix_mle(&c, 3, 2).evaluate(&claims.c.point),
+        claims.c.value
+    );
+}
+
+#[test]
+fn test_matmul_sumcheck_rejects_wrong_product() {
+    let (a, b, mut c, dimensions) = test_setup();
+    c[4] += Fq::from(1);
+
+    let (proof, _) = prove(&a, &b, &c, &dimensions, &mut Transcript::new());
+    assert!(verify(&proof, &c, &dimensions, &mut Transcript::new()).is_none());
+}
+
+#[test]
+fn test_matmul_sumcheck_rejects_wrong_evaluations() {
+    let (a, b, c, dimensions) = test_setup();
+
+    let (mut proof, _) = prove(&a, &b, &c, &dimensions, &mut Transcript::new());
+    proof.a_evaluation += Fq::from(1);
+    assert!(verify(&proof, &c, &dimensions, &mut Transcript::new()).is_none());
+
+    // c is not the product of another matrix A with b
+    let mut wrong_a = a.clone();
+    wrong_a[0] = Fq::from(0);
+    let (proof, _) = prove(&wrong_a, &b, &c, &dimensions, &mut Transcript::new());
+    assert!(verify(&proof, &c, &dimensions, &mut Transcript::new()).is_none());
+}
+
+#[test]
+fn test_matmul_sumcheck_at_point() {
+    let (a, b, c, dimensions) = test_setup();
+    // a claim on C left by another protocol
+    let point = to_field(&[5, 7, 11]);
+    let c_claim = gkr::gkr_p::Claim {
+        value: matrix_mle(&c, 3, 2).evaluate(&point),
+        point: point.clone(),
+    };
+
+    let (proof, _) = prove_at_point(&a, &b, &dimensions, &point, &mut Transcript::new());
+    let claims = verify_at_point(&proof, &dimensions, &c_claim, &mut Transcript::new()).unwrap();
+
+    assert_eq!(claims.c, c_claim);
+    assert_eq!(
+        matrix_mle(&a, 3, 5).evaluate(&claims.a.point),
+        claims.a.value
+    );
+
+    let wrong_claim = gkr::gkr_p::Claim {
+        value: c_claim.value + Fq::from(1),
+        point,
+    };
+    assert!(verify_at_point(&proof, &dimensions, &wrong_claim, &mut Transcript::new()).is_none());
+}
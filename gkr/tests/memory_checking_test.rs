@@ -0,0 +1,134 @@
+use ark_bn254::Fq;
+use gkr::evaluation::MultilinearPolynomialEV;
+use gkr::gkr_sumcheck_dependencies::transcript::Transcript;
+use gkr::memory_checking::{prove, timestamp_multiplicities, verify, MemoryLog, MemoryTrace};
+use gkr::merkle_tree::MerkleTree;
+use sha3::Keccak256;
+
+fn test_log() -> MemoryLog<Fq> {
+    let mut log = MemoryLog::new([5, 0, 0, 7].map(Fq::from).to_vec());
+
+    log.write(1, Fq::from(9));
+    assert_eq!(log.read(1), Fq::from(9));
+    assert_eq!(log.read(0), Fq::from(5));
+    log.write(3, Fq::from(2));
+    assert_eq!(log.read(3), Fq::from(2));
+
+    log
+}
+
+// The roots of Merkle trees over the columns of the trace and the timestamp multiplicities
+fn commitments(trace: &MemoryTrace<Fq>) -> Vec<Vec<u8>> {
+    [
+        &trace.addresses,
+        &trace.read_values,
+        &trace.read_timestamps,
+        &trace.write_values,
+        &trace.final_values,
+        &trace.final_timestamps,
+        &timestamp_multiplicities(trace),
+    ]
+    .iter()
+    .map(|column| MerkleTree::<Keccak256>::from_field_elements(column).root())
+    .collect()
+}
+
+#[test]
+fn test_memory_log() {
+    let log = test_log();
+    let trace = log.trace();
+
+    assert_eq!(log.records.len(), 5);
+    assert_eq!(log.records[1].read_timestamp, 1);
+    // padded with reads of address 0
+    assert_eq!(trace.num_operations(), 8);
+    assert_eq!(trace.final_values, [5, 9, 0, 2].map(Fq::from).to_vec());
+    assert_eq!(trace.final_timestamps, [8, 2, 0, 5].map(Fq::from).to_vec());
+}
+
+#[test]
+fn test_memory_checking_protocol() {
+    let trace = test_log().trace();
+
+    let commitments = commitments(&trace);
+
+    let (proof, prover_claims) = prove(&trace, &commitments, &mut Transcript::new());
+    let claims = verify(
+        &trace.initial_memory,
+        8,
+        &proof,
+        &commitments,
+        &mut Transcript::new(),
+    )
+    .unwrap();
+
+    assert_eq!(claims, prover_claims);
+
+    for (column, claim) in [
+        (&trace.addresses, &claims.addresses),
+        (&trace.read_values, &claims.read_values),
+        (&trace.read_timestamps, &claims.read_timestamps[0]),
+        (&trace.read_timestamps, &claims.read_timestamps[1]),
+        (&trace.write_values, &claims.write_values),
+        (&trace.final_values, &claims.final_values),
+        (&trace.final_timestamps, &claims.final_timestamps),
+        (
+            &timestamp_multiplicities(&trace),
+            &claims.timestamp_multiplicities,
+        ),
+    ] {
+        assert_eq!(
+            MultilinearPolynomialEV::new(column).evaluate(&claim.point),
+            claim.value
+        );
+    }
+}
+
+#[test]
+fn test_memory_checking_rejects_inconsistent_reads() {
+    let mut trace = test_log().trace();
+    // the read of address 1 returns 10 instead of the 9 written before
+    trace.read_values[1] = Fq::from(10);
+    trace.write_values[1] = Fq::from(10);
+
+    let (proof, _) = prove(&trace, &[], &mut Transcript::new());
+    assert!(verify(
+        &trace.initial_memory,
+        8,
+        &proof,
+        &[],
+        &mut Transcript::new()
+    )
+    .is_none());
+
+    // a different initial memory
+    let trace = test_log().trace();
+    let (proof, _) = prove(&trace, &[], &mut Transcript::new());
+    let initial_memory = [5, 0, 1, 7].map(Fq::from);
+    assert!(verify(&initial_memory, 8, &proof, &[], &mut Transcript::new()).is_none());
+}
+
+#[test]
+fn test_memory_checking_rejects_reads_from_the_future() {
+    // The first operation reads the 8 written by the second one at timestamp 2:
+    // init + writes = reads + final as multisets, but the read timestamp is after the read
+    let trace = MemoryTrace {
+        initial_memory: [5, 0].map(Fq::from).to_vec(),
+        addresses: [0, 0].map(Fq::from).to_vec(),
+        read_values: [8, 5].map(Fq::from).to_vec(),
+        read_timestamps: [2, 0].map(Fq::from).to_vec(),
+        write_values: [8, 8].map(Fq::from).to_vec(),
+        final_values: [8, 0].map(Fq::from).to_vec(),
+        final_timestamps: [1, 0].map(Fq::from).to_vec(),
+    };
+
+    let (proof, _) = prove(&trace, &[], &mut Transcript::new());
+    assert!(verify(
+        &trace.initial_memory,
+        2,
+        &proof,
+        &[],
+        &mut Transcript::new()
+    )
+    .is_none());
+}
@@ -0,0 +1,118 @@
+use ark_bn254::Fq;
+use gkr::merkle_tree::{MerkleProof, MerkleTree};
+use sha3::Keccak256;
+
+fn test_setup() -> Vec<Vec<u8>> {
+    (0..7u8).map(|i| vec![i; 4]).collect()
+}
+
+#[test]
+fn test_single_proof() {
+    let leaves = test_setup();
+    let tree = MerkleTree::<Keccak256>::new(&leaves);
+
+    // 7 leaves are padded to 8
+    assert_eq!(tree.depth(), 3);
+
+    for (index, leaf) in leaves.iter().enumerate() {
+        let proof = tree.prove(index);
+        assert!(MerkleTree::<Keccak256>::verify(&tree.root(), leaf, &proof));
+    }
+
+    let proof = tree.prove(2);
+    assert!(!MerkleTree::<Keccak256>::verify(
+        &tree.root(),
+        &leaves[3],
+        &proof
+    ));
+}
+
+#[test]
+fn test_batch_proof_deduplicates_shared_nodes() {
+    let leaves = test_setup();
+    let tree = MerkleTree::<Keccak256>::new(&leaves);
+
+    let proof = tree.prove_batch(&[5, 0, 1, 5]);
+
+    assert_eq!(proof.leaf_indices, vec![0, 1, 5]);
+    // leaf layer: sibling of 5 => 1 node, second layer: siblings of 0 and 2 => 2 nodes, last layer: nothing
+    assert_eq!(proof.nodes.len(), 3);
+
+    let opened_leaves = vec![leaves[0].clone(), leaves[1].clone(), leaves[5].clone()];
+    assert!(MerkleTree::<Keccak256>::verify_batch(
+        &tree.root(),
+        &opened_leaves,
+        &proof
+    ));
+
+    let wrong_leaves = vec![leaves[0].clone(), leaves[2].clone(), leaves[5].clone()];
+    assert!(!MerkleTree::<Keccak256>::verify_batch(
+        &tree.root(),
+        &wrong_leaves,
+        &proof
+    ));
+}
+
+#[test]
+fn test_field_element_leaves() {
+    let values: Vec<Fq> = (1..=4).map(Fq::from).collect();
+    let tree = MerkleTree::<Keccak256>::from_field_elements(&values);
+
+    let proof = tree.prove_batch(&[1, 3]);
+    let opened_leaves = vec![
+        gkr::utility::utils::field_element_to_bytes(values[1]),
+        gkr::utility::utils::field_element_to_bytes(values[3]),
+    ];
+
+    assert!(MerkleTree::<Keccak256>::verify_batch(
+        &tree.root(),
+        &opened_leaves,
+        &proof
+    ));
+}
+
+#[test]
+fn test_padded_index_cannot_be_opened() {
+    let leaves = test_setup();
+    let tree = MerkleTree::<Keccak256>::new(&leaves);
+
+    // index 7 only exists because 7 leaves are padded to 8:
+    // the prover refuses it, so build the proofs from the tree by hand
+    let mut siblings = tree.prove(6).siblings;
+    siblings[0] = tree.layers[0][6].clone();
+    let proof = MerkleProof {
+        leaf_index: 7,
+        siblings,
+    };
+    assert!(!MerkleTree::<Keccak256>::verify(&tree.root(), &[], &proof));
+
+    let mut batch_proof = tree.prove_batch(&[6]);
+    batch_proof.leaf_indices = vec![6, 7];
+    batch_proof.nodes.remove(0);
+    assert!(!MerkleTree::<Keccak256>::verify_batch(
+        &tree.root(),
+        &[leaves[6].clone(), vec![]],
+        &batch_proof
+    ));
+}
+
+#[test]
+#[should_panic(expected = "leaf index out of bounds")]
+fn test_prove_rejects_padded_index() {
+    let tree = MerkleTree::<Keccak256>::new(&test_setup());
+    tree.prove_batch(&[1, 7]);
+}
+
+#[test]
+fn test_verify_batch_rejects_overflowing_depth() {
+    let leaves = test_setup();
+    let tree = MerkleTree::<Keccak256>::new(&leaves);
+
+    let mut proof = tree.prove_batch(&[0]);
+    proof.depth = usize::BITS as usize;
+    assert!(!MerkleTree::<Keccak256>::verify_batch(
+        &tree.root(),
+        &[leaves[0].clone()],
+        &proof
+    ));
+}
@@ -0,0 +1,164 @@
+use ark_bn254::Fq;
+use gkr::gkr_sumcheck_dependencies::transcript::Transcript;
+use gkr::plonkish::{prove, verify, GateTerm, PlonkishCircuit, PlonkishGate, WirePosition};
+
+fn position(wire: usize, row: usize) -> WirePosition {
+    WirePosition { wire, row }
+}
+
+// x^3 + x + 5 = out, with out public, on the vanilla gate (q_L, q_R, q_M, q_O, q_C):
+// row 0: w_0 = out (public input), row 1: x * x = x2, row 2: x2 * x = x3, row 3: x3 + x + 5 = out
+fn cubic_circuit() -> PlonkishCircuit<Fq> {
+    let (one, minus_one, zero) = (Fq::from(1), -Fq::from(1), Fq::from(0));
+
+    let selectors = vec![
+        vec![one, zero, zero, one],
+        vec![zero, zero, zero, one],
+        vec![zero, one, one, zero],
+        vec![zero, minus_one, minus_one, minus_one],
+        vec![zero, zero, zero, Fq::from(5)],
+    ];
+    let copy_constraints = [
+        (position(0, 1), position(1, 1)),
+        (position(1, 1), position(1, 2)),
+        (position(1, 2), position(1, 3)),
+        (position(2, 1), position(0, 2)),
+        (position(2, 2), position(0, 3)),
+        (position(2, 3), position(0, 0)),
+    ];
+
+    PlonkishCircuit::new(PlonkishGate::vanilla(), &selectors, &copy_constraints, 1)
+}
+
+fn cubic_wires(x: u64) -> Vec<Vec<Fq>> {
+    let x = Fq::from(x);
+    let out = x * x * x + x + Fq::from(5);
+
+    vec![
+        vec![out, x, x * x, x * x * x],
+        vec![Fq::from(0), x, x, x],
+        vec![Fq::from(0), x * x, x * x * x, out],
+    ]
+}
+
+#[test]
+fn test_plonkish_satisfaction() {
+    let circuit = cubic_circuit();
+    let wires = cubic_wires(3);
+
+    assert!(circuit.is_satisfied(&[Fq::from(35)], &wires));
+    assert!(!circuit.is_satisfied(&[Fq::from(36)], &wires));
+
+    // every gate holds, but x is not copied to the last row
+    let mut broken_copy = wires.clone();
+    broken_copy[1][3] = Fq::from(4);
+    broken_copy[2][3] = Fq::from(36);
+    broken_copy[0][0] = Fq::from(36);
+    assert!(!circuit.is_satisfied(&[Fq::from(36)], &broken_copy));
+}
+
+#[test]
+fn test_plonkish_protocol() {
+    let circuit = cubic_circuit();
+    let public_inputs = [Fq::from(35)];
+    let wires = cubic_wires(3);
+
+    let (proof, prover_claims) = prove(&circuit, &public_inputs, &wires, &mut Transcript::new());
+    let claims = verify(&circuit, &public_inputs, &proof, &mut Transcript::new()).unwrap();
+
+    assert_eq!(claims, prover_claims);
+
+    for (wire, (gate_claim, permutation_claim)) in circuit
+        .wire_mles(&wires)
+        .iter()
+        .zip(claims.gate.iter().zip(claims.permutation.iter()))
+    {
+        assert_eq!(wire.evaluate(&gate_claim.point), gate_claim.value);
+        assert_eq!(
+            wire.evaluate(&permutation_claim.point),
+            permutation_claim.value
+        );
+    }
+}
+
+#[test]
+fn test_plonkish_protocol_rejects_unsatisfied_gates() {
+    let circuit = cubic_circuit();
+    let public_inputs = [Fq::from(36)];
+
+    let (proof, _) = prove(
+        &circuit,
+        &public_inputs,
+        &cubic_wires(3),
+        &mut Transcript::new(),
+    );
+    assert!(verify(&circuit, &public_inputs, &proof, &mut Transcript::new()).is_none());
+}
+
+#[test]
+fn test_plonkish_protocol_rejects_broken_copy_constraints() {
+    let circuit = cubic_circuit();
+    let public_inputs = [Fq::from(36)];
+
+    let mut wires = cubic_wires(3);
+    wires[1][3] = Fq::from(4);
+    wires[2][3] = Fq::from(36);
+    wires[0][0] = Fq::from(36);
+
+    let (proof, _) = prove(&circuit, &public_inputs, &wires, &mut Transcript::new());
+    assert!(verify(&circuit, &public_inputs, &proof, &mut Transcript::new()).is_none());
+}
+
+#[test]
+fn test_plonkish_protocol_rejects_tampered_proofs() {
+    let circuit = cubic_circuit();
+    let public_inputs = [Fq::from(35)];
+    let wires = cubic_wires(3);
+
+    let (mut proof, _) = prove(&circuit, &public_inputs, &wires, &mut Transcript::new());
+    proof.gate_wire_evaluations[0] += Fq::from(1);
+    assert!(verify(&circuit, &public_inputs, &proof, &mut Transcript::new()).is_none());
+
+    let (mut proof, _) = prove(&circuit, &public_inputs, &wires, &mut Transcript::new());
+    proof.permutation_proof.layer_proofs[1].left_evaluations[2] += Fq::from(1);
+    assert!(verify(&circuit, &public_inputs, &proof, &mut Transcript::new()).is_none());
+
+    let (mut proof, _) = prove(&circuit, &public_inputs, &wires, &mut Transcript::new());
+    proof.permutation_wire_evaluations[1] += Fq::from(1);
+    assert!(verify(&circuit, &public_inputs, &proof, &mut Transcript::new()).is_none());
+}
+
+#[test]
+fn test_plonkish_custom_gate() {
+    // q_pow * w_0^5 - q_pow * w_1 = 0, chained over the rows: y = x^25 over two rows
+    let gate = PlonkishGate::new(
+        2,
+        2,
+        vec![
+            GateTerm {
+                selector: 0,
+                wires: vec![0, 0, 0, 0, 0],
+            },
+            GateTerm {
+                selector: 1,
+                wires: vec![1],
+            },
+        ],
+    );
+    let (one, minus_one) = (Fq::from(1), -Fq::from(1));
+    let circuit = PlonkishCircuit::new(
+        gate,
+        &[vec![one, one], vec![minus_one, minus_one]],
+        &[(position(1, 0), position(0, 1))],
+        0,
+    );
+
+    let x = Fq::from(2);
+    let x5 = x * x * x * x * x;
+    let wires = vec![vec![x, x5], vec![x5, x5 * x5 * x5 * x5 * x5]];
+
+    assert!(circuit.is_satisfied(&[], &wires));
+
+    let (proof, _) = prove(&circuit, &[], &wires, &mut Transcript::new());
+    assert!(verify(&circuit, &[], &proof, &mut Transcript::new()).is_some());
+}
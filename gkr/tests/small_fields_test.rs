@@ -0,0 +1,124 @@
+use ark_ff::{Field, PrimeField};
+use gkr::evaluation::MultilinearPolynomialEV;
+use gkr::extension_sum_check::{evaluate_at_extension_point, prove, verify};
+use gkr::fields::{BabyBear, BabyBearExt4, Goldilocks, GoldilocksExt2};
+use gkr::gkr_sumcheck_dependencies::poly_sum::SumPolynomial;
+use gkr::gkr_sumcheck_dependencies::transcript::Transcript;
+use gkr::product_poly::ProductPolynomial;
+
+fn polynomial<F: PrimeField>(values: &[i64]) -> MultilinearPolynomialEV<F> {
+    MultilinearPolynomialEV::new(
+        &values
+            .iter()
+            .map(|value| F::from(*value))
+            .collect::<Vec<F>>(),
+    )
+}
+
+// sum over x of f(x) * g(x) + h(x)
+fn test_polynomials<F: PrimeField>() -> [MultilinearPolynomialEV<F>; 3] {
+    [
+        polynomial(&[3, -1, 4, 1, -5, 9, 2, 6]),
+        polynomial(&[2, 7, -1, 8, 2, 8, 1, 8]),
+        polynomial(&[1, 1, 2, 3, 5, 8, 13, 21]),
+    ]
+}
+
+fn check_extension_sumcheck<F: PrimeField, E: Field<BasePrimeField = F>>() {
+    let [f, g, h] = test_polynomials::<F>();
+    let claimed_sum: F = (0..8)
+        .map(|index| {
+            f.evaluated_values[index] * g.evaluated_values[index] + h.evaluated_values[index]
+        })
+        .sum();
+    let sum_polynomial = SumPolynomial::new(vec![
+        ProductPolynomial::new(vec![f.clone(), g.clone()]),
+        ProductPolynomial::new(vec![h.clone()]),
+    ]);
+
+    let proof = prove::<F, E>(sum_polynomial.clone(), claimed_sum, &mut Transcript::new());
+    let verification = verify(&proof, 2, &mut Transcript::new());
+    assert!(verification.is_proof_valid);
+
+    let point = verification.random_challenges;
+    assert!(point.iter().all(|challenge| !challenge
+        .to_base_prime_field_elements()
+        .skip(1)
+        .all(|coefficient| coefficient == F::zero())));
+    assert_eq!(
+        verification.last_claimed_sum,
+        evaluate_at_extension_point(&f, &point) * evaluate_at_extension_point(&g, &point)
+            + evaluate_at_extension_point(&h, &point)
+    );
+
+    let mut proof = prove::<F, E>(
+        sum_polynomial.clone(),
+        claimed_sum + F::one(),
+        &mut Transcript::new(),
+    );
+    assert!(!verify(&proof, 2, &mut Transcript::new()).is_proof_valid);
+
+    proof.round_evaluations[1][2] += E::one();
+    let verification = verify(&proof, 2, &mut Transcript::new());
+    assert!(!verification.is_proof_valid);
+
+    // A round of higher degree is rejected, even when it is consistent with the claimed sum
+    let mut proof = prove::<F, E>(sum_polynomial, claimed_sum, &mut Transcript::new());
+    assert!(verify(&proof, 2, &mut Transcript::new()).is_proof_valid);
+    assert!(!verify(&proof, 3, &mut Transcript::new()).is_proof_valid);
+    proof.round_evaluations[0].push(E::zero());
+    assert!(!verify(&proof, 2, &mut Transcript::new()).is_proof_valid);
+}
+
+#[test]
+fn test_goldilocks_field() {
+    // 2^64 = 2^32 - 1 mod p
+    assert_eq!(
+        Goldilocks::from(2u64).pow([64]),
+        Goldilocks::from(u32::MAX as u64)
+    );
+    assert_eq!(Goldilocks::MODULUS_BIT_SIZE, 64);
+
+    let element = GoldilocksExt2::new(Goldilocks::from(3), Goldilocks::from(5));
+    assert_eq!(element * element.inverse().unwrap(), GoldilocksExt2::ONE);
+
+    let mut frobenius = element;
+    frobenius.frobenius_map_in_place(1);
+    assert_eq!(element.pow(Goldilocks::MODULUS), frobenius);
+}
+
+#[test]
+fn test_baby_bear_field() {
+    assert_eq!(BabyBear::from(2013265920u64), -BabyBear::ONE);
+    assert_eq!(BabyBear::MODULUS_BIT_SIZE, 31);
+
+    let element: BabyBearExt4 =
+        BabyBearExt4::from_base_prime_field_elems([1, 2, 3, 4].map(BabyBear::from)).unwrap();
+    assert_eq!(element * element.inverse().unwrap(), BabyBearExt4::ONE);
+
+    let mut frobenius = element;
+    frobenius.frobenius_map_in_place(1);
+    assert_eq!(element.pow(BabyBear::MODULUS), frobenius);
+}
+
+#[test]
+fn test_goldilocks_sumcheck_with_extension_challenges() {
+    check_extension_sumcheck::<Goldilocks, GoldilocksExt2>();
+}
+
+#[test]
+fn test_baby_bear_sumcheck_with_extension_challenges() {
+    check_extension_sumcheck::<BabyBear, BabyBearExt4>();
+}
+
+#[test]
+fn test_extension_challenges() {
+    let mut transcript = Transcript::new();
+    transcript.absorb(b"challenge");
+
+    let challenge: BabyBearExt4 = transcript.random_challenge_as_extension_element();
+    assert_eq!(challenge.to_base_prime_field_elements().count(), 4);
+    assert!(challenge
+        .to_base_prime_field_elements()
+        .all(|coefficient| coefficient != BabyBear::from(0u64)));
+}
@@ -0,0 +1,94 @@
+use ark_bn254::Fq;
+use gkr::gkr_sumcheck_dependencies::transcript::Transcript;
+use gkr::r1cs::{SparseMatrix, R1CS};
+use gkr::spartan::{prove, verify};
+
+// x^3 + x + 5 = y, with y public
+// z = [1, y, x, sym_1, y_1, sym_2]
+// x * x = sym_1, sym_1 * x = y_1, (y_1 + x) * 1 = sym_2, (sym_2 + 5) * 1 = y
+fn test_setup() -> R1CS<Fq> {
+    let one = Fq::from(1);
+
+    let a = SparseMatrix::new(
+        4,
+        6,
+        vec![
+            (0, 2, one),
+            (1, 3, one),
+            (2, 4, one),
+            (2, 2, one),
+            (3, 5, one),
+            (3, 0, Fq::from(5)),
+        ],
+    );
+    let b = SparseMatrix::new(
+        4,
+        6,
+        vec![(0, 2, one), (1, 2, one), (2, 0, one), (3, 0, one)],
+    );
+    let c = SparseMatrix::new(
+        4,
+        6,
+        vec![(0, 3, one), (1, 4, one), (2, 5, one), (3, 1, one)],
+    );
+
+    R1CS::new(a, b, c, 1)
+}
+
+fn witness(x: u64) -> Vec<Fq> {
+    let x = Fq::from(x);
+    vec![x, x * x, x * x * x, x * x * x + x]
+}
+
+#[test]
+fn test_r1cs_satisfaction() {
+    let r1cs = test_setup();
+
+    assert_eq!(r1cs.num_witness_variables(), 4);
+    assert!(r1cs.is_satisfied(&[Fq::from(35)], &witness(3)));
+    assert!(!r1cs.is_satisfied(&[Fq::from(36)], &witness(3)));
+    assert!(!r1cs.is_satisfied(&[Fq::from(35)], &witness(4)));
+}
+
+#[test]
+fn test_spartan_protocol() {
+    let r1cs = test_setup();
+    let public_inputs = [Fq::from(35)];
+    let witness = witness(3);
+
+    let (proof, prover_claim) = prove(&r1cs, &public_inputs, &witness, &mut Transcript::new());
+    let claim = verify(&r1cs, &public_inputs, &proof, &mut Transcript::new()).unwrap();
+
+    assert_eq!(claim, prover_claim);
+    // the caller checks the claim on the witness, here against the witness itself
+    assert_eq!(
+        r1cs.witness_mle(&witness).evaluate(&claim.point),
+        claim.value
+    );
+}
+
+#[test]
+fn test_spartan_protocol_rejects_unsatisfied_constraints() {
+    let r1cs = test_setup();
+
+    let (proof, _) = prove(&r1cs, &[Fq::from(36)], &witness(3), &mut Transcript::new());
+    assert!(verify(&r1cs, &[Fq::from(36)], &proof, &mut Transcript::new()).is_none());
+
+    // a valid proof for y = 35 is not a proof for y = 36
+    let (proof, _) = prove(&r1cs, &[Fq::from(35)], &witness(3), &mut Transcript::new());
+    assert!(verify(&r1cs, &[Fq::from(36)], &proof, &mut Transcript::new()).is_none());
+}
+
+#[test]
+fn test_spartan_protocol_rejects_tampered_proofs() {
+    let r1cs = test_setup();
+    let public_inputs = [Fq::from(35)];
+
+    let (mut proof, _) = prove(&r1cs, &public_inputs, &witness(3), &mut Transcript::new());
+    proof.matrix_evaluations[2] += Fq::from(1);
+    assert!(verify(&r1cs, &public_inputs, &proof, &mut Transcript::new()).is_none());
+
+    let (mut proof, _) = prove(&r1cs, &public_inputs, &witness(3), &mut Transcript::new());
+    proof.witness_evaluation += Fq::from(1);
+    assert!(verify(&r1cs, &public_inputs, &proof, &mut Transcript::new()).is_none());
+}
@@ -5,12 +5,15 @@ use crate::utility::DenseUnivariatePolynomial;
 use ark_ff::PrimeField;
 
 // secret: this is the values to be shared from a finite field
-// Minimum number of shares required to reconstruct the secret.
+// threshold: Minimum number of shares required to reconstruct the secret: the polynomial has threshold coefficients
+// (degree threshold - 1), so any threshold shares recover the secret and fewer reveal nothing about it.
+// Before, the polynomial only had threshold - 1 coefficients and threshold - 1 shares were already enough:
+// callers that passed the number of shares they needed plus one should now pass that number.
 // number_shares: Total number of shares to generate.
 // this outputs a value of (x,y) pair x is the share identifier / index of the polynomials evaluated at x.
 pub fn shares<F: PrimeField>(secret: F, threshold: u64, number_shares: u64) -> Vec<(F, F)> {
 let mut rnd = rand::thread_rng();
- let y_values = iter::once(secret).chain(iter::from_fn(|| Some(F::rand(&mut rnd)))).take(threshold as usize - 1).collect();
+ let y_values = iter::once(secret).chain(iter::from_fn(|| Some(F::rand(&mut rnd)))).take(threshold as usize).collect();
 
     let polynomial = DenseUnivariatePolynomial::new(y_values);
     // y is the result of evaluating the polynomial at x-coordinates
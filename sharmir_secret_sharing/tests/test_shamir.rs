@@ -30,3 +30,14 @@ fn test_recover_wrong_secret_fails() {
 
     assert_ne!(recovered_secret, Fq::from(10));
 }
+
+#[test]
+fn test_threshold_shares_recover_secret() {
+    let secret = Fq::from(42);
+    let shares = shares(secret, 3, 5);
+
+    // any 3 of the 5 shares are enough, 2 of them are not
+    assert_eq!(recover_secret(shares[2..].to_vec()), secret);
+    assert_eq!(recover_secret(vec![shares[0], shares[3], shares[4]]), secret);
+    assert_ne!(recover_secret(shares[..2].to_vec()), secret);
+}